@@ -17,9 +17,10 @@ mod usuarios_sistema {
     ///
     /// # Campos
     /// - `usuarios`: Mapeo de AccountId a struct Usuario, representa todos los usuarios registrados.
-    /// - `publicaciones`: Vector con todas las publicaciones activas en el sistema.
+    /// - `publicaciones`: Mapeo de id de publicación a struct Publicacion, representa todas las publicaciones creadas.
     /// - `productos`: Mapeo de id de producto a struct Producto, representa todos los productos creados.
-    /// - `ordenes`: Vector con todas las órdenes de compra generadas.
+    /// - `ordenes`: Mapeo de id de orden a struct OrdenCompra, representa todas las órdenes de compra generadas.
+    ///   Cada usuario guarda en `Usuario::ordenes` los ids de las órdenes en las que participa.
     /// - `proximo_id_publicacion`: Contador para el próximo id único de publicación.
     /// - `proximo_id_producto`: Contador para el próximo id único de producto.
     /// - `proximo_id_orden`: Contador para el próximo id único de orden de compra.
@@ -31,9 +32,9 @@ mod usuarios_sistema {
     /// ```
     pub struct Sistema {
         usuarios: ink::storage::Mapping<AccountId, Usuario>,
-        publicaciones: Vec<Publicacion>,
+        publicaciones: Mapping<u128, Publicacion>,
         productos: Mapping<u128, Producto>,
-        ordenes: Vec<OrdenCompra>,
+        ordenes: Mapping<u128, OrdenCompra>,
         proximo_id_publicacion: u128,
         proximo_id_producto: u128,
         proximo_id_orden: u128,
@@ -69,6 +70,8 @@ mod usuarios_sistema {
         DineroInsuficiente,
         FueraDeRango,
         OrdenCancelada,
+        TransferenciaFallida,
+        PublicacionInactiva,
     }
 
     /// # Esta es la estructura de un usuario.
@@ -209,7 +212,8 @@ mod usuarios_sistema {
         
 
         lista_productos: Vec<(u128, u32)>,
-        // El vec lo pense con un vec de tuplas, con el id del producto y la cantidad comprada.
+        // El vec lo pense con un vec de tuplas, con el id de la publicación y la cantidad comprada.
+        // Se guarda la publicación (y no el producto) para poder devolverle el stock si se cancela la orden.
 
         // Se me ocurre que dentro del usuario podemos tener un vec de ordenes de compra
         // y para acceder a una en especifica que se use el id de orden
@@ -223,10 +227,18 @@ mod usuarios_sistema {
 
         solicitud_cancelacion: Option<AccountId>,
 
+        // Lo que pago el comprador por esta orden. Queda retenido en el contrato hasta que
+        // se recibe (se le paga al vendedor) o se cancela (se le devuelve al comprador).
         monto:u32,
 
     }
 
+    /// Parte de una compra que corresponde a un mismo vendedor: el vendedor y las tuplas (id_publicacion, cantidad).
+    type CompraDeVendedor = (AccountId, Vec<(u128, u32)>);
+
+    /// Cantidad máxima de elementos que devuelve una página de `get_publicaciones` o de `ver_mis_ordenes`.
+    pub const MAX_PUBLICACIONES_POR_PAGINA: u32 = 50;
+
     #[derive(Debug, Clone, PartialEq)]
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
     #[cfg_attr(
@@ -253,7 +265,7 @@ mod usuarios_sistema {
         /// ```
         #[ink(constructor)]
         pub fn new() -> Self {
-            Self {  usuarios: Mapping::new(), publicaciones: Vec::<Publicacion>::new(), productos: Mapping::new(), ordenes:Vec::new(), proximo_id_publicacion: 0, proximo_id_producto: 0 , proximo_id_orden: 0}
+            Self {  usuarios: Mapping::new(), publicaciones: Mapping::new(), productos: Mapping::new(), ordenes: Mapping::new(), proximo_id_publicacion: 0, proximo_id_producto: 0 , proximo_id_orden: 0}
         }
 
 
@@ -489,7 +501,7 @@ mod usuarios_sistema {
             // Agrego la publicación
             let id_publicacion = self.generar_id_publicacion()?;
 
-            self.publicaciones.push(Publicacion {
+            self.publicaciones.insert(id_publicacion, &Publicacion {
                 id_publicacion,
                 id_producto,
                 id_publicador: usuario_id,
@@ -516,84 +528,188 @@ mod usuarios_sistema {
             Ok(())
         }
 
-        // Orden de compra
+        /// Desactiva una publicación propia. Mientras está inactiva no se la puede incluir en una orden de compra.
+        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no es el publicador o ya estaba inactiva.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///     sistema.desactivar_publicacion(0);
+        /// ```
+        #[ink(message)]
+        pub fn desactivar_publicacion(&mut self, id_publicacion: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._cambiar_estado_publicacion(id_publicacion, false, caller)
+        }
 
+        /// Reactiva una publicación propia que había sido desactivada.
+        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no es el publicador o ya estaba activa.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///     sistema.reactivar_publicacion(0);
+        /// ```
+        #[ink(message)]
+        pub fn reactivar_publicacion(&mut self, id_publicacion: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._cambiar_estado_publicacion(id_publicacion, true, caller)
+        }
+
+        fn _cambiar_estado_publicacion(&mut self, id_publicacion: u128, activa: bool, caller: AccountId) -> Result<(), ErrorSistema> {
+            let mut publicacion = self.publicacion_propia(id_publicacion, caller)?;
+
+            if publicacion.activa == activa {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            publicacion.activa = activa;
+            self.publicaciones.insert(id_publicacion, &publicacion);
+            Ok(())
+        }
 
-        /// Genera una nueva orden de compra para el usuario que llama.
-        /// Recibe una lista de tuplas (id_publicacion, cantidad).
-        /// Retorna la orden creada o un error si hay algún problema.
+        /// Modifica el precio y el stock de una publicación propia.
+        /// Los cambios solo afectan a las órdenes que se generen a partir de ahora.
+        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no es el publicador.
         ///
         /// # Ejemplo
         /// ```
-        ///     let orden = sistema.generar_orden_compra(vec![(0, 2), (1, 1)])?;
+        ///     sistema.editar_publicacion(0, 1500, 8);
         /// ```
         #[ink(message)]
-        pub fn generar_orden_compra(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, dinero_disponible: u32)->Result<OrdenCompra, ErrorSistema>{
+        pub fn editar_publicacion(&mut self, id_publicacion: u128, precio: u32, stock: u32) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._editar_publicacion(id_publicacion, precio, stock, caller)
+        }
+
+        fn _editar_publicacion(&mut self, id_publicacion: u128, precio: u32, stock: u32, caller: AccountId) -> Result<(), ErrorSistema> {
+            let mut publicacion = self.publicacion_propia(id_publicacion, caller)?;
+
+            publicacion.precio = precio;
+            publicacion.stock = stock;
+            self.publicaciones.insert(id_publicacion, &publicacion);
+            Ok(())
+        }
+
+        // Busca la publicación y verifica que el caller sea quien la publicó.
+        fn publicacion_propia(&self, id_publicacion: u128, caller: AccountId) -> Result<Publicacion, ErrorSistema> {
+            let publicacion = self.publicaciones.get(id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?;
+            if publicacion.id_publicador != caller {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+            Ok(publicacion)
+        }
+
+        // Orden de compra
+
+
+        /// Genera las órdenes de compra para el usuario que llama y las paga con el valor transferido.
+        /// Recibe una lista de tuplas (id_publicacion, cantidad), que pueden ser de distintos vendedores.
+        /// Se genera una orden por cada vendedor involucrado, cada una con la parte del pago que le corresponde.
+        /// El pago queda retenido en el contrato hasta que el comprador marca la orden como recibida
+        /// o hasta que se completa la cancelación; lo que se transfiera de más se le devuelve al comprador.
+        /// Retorna las órdenes creadas o un error si hay algún problema (`DineroInsuficiente` si no alcanza el pago).
+        ///
+        /// # Ejemplo
+        /// ```
+        ///     let ordenes = sistema.generar_orden_compra(vec![(0, 2), (1, 1)])?;
+        /// ```
+        #[ink(message, payable)]
+        pub fn generar_orden_compra(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>)->Result<Vec<OrdenCompra>, ErrorSistema>{
             let caller = self.env().caller();
-            return self._generar_orden_compra(lista_publicaciones_con_cantidades, dinero_disponible, caller);
+            let pago = self.env().transferred_value();
+            return self._generar_orden_compra(lista_publicaciones_con_cantidades, pago, caller);
         }
         
-        // Recibe un vector con las publicaciones y la cantidad de cada una para armar la orden.
-        fn _generar_orden_compra(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)> , dinero_disponible:u32, caller:AccountId) -> Result<OrdenCompra, ErrorSistema>{
+        // Recibe un vector con las publicaciones y la cantidad de cada una para armar las ordenes.
+        fn _generar_orden_compra(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)> , pago:Balance, caller:AccountId) -> Result<Vec<OrdenCompra>, ErrorSistema>{
             // Checkeo si el usuario que esta tratando de realizar la compra tiene el rol debido
             
             self.es_vendedor()?;
 
             // Verifico que por lo menos exista una compra
-
-            // Busco el id del vendedor
-            let vendedor_actual:AccountId;
-            if let Some(publi) = self.publicaciones.iter().find(|x|x.id_publicacion == lista_publicaciones_con_cantidades[0].0) {
-                vendedor_actual = publi.id_publicador;
-            }
-            else {
-                return Err(ErrorSistema::PublicacionNoValida)
+            if lista_publicaciones_con_cantidades.is_empty() {
+                return Err(ErrorSistema::CompraSinItems);
             }
 
-            //Si el usuario que creo la publicacion trata de realizar una compra hay error
+            // Separo la compra por vendedor, cada uno tiene su propia orden
+            let compras_por_vendedor = self.agrupar_por_vendedor(lista_publicaciones_con_cantidades.clone())?;
+
+            //Si el usuario que creo alguna de las publicaciones trata de realizar una compra hay error
 
-            if vendedor_actual == caller {
+            if compras_por_vendedor.iter().any(|(vendedor, _)| *vendedor == caller) {
                 return Err(ErrorSistema::NoPuedeComprarPublicacionPropia);
             }
 
-            
-            self.validar_orden(lista_publicaciones_con_cantidades.clone(), vendedor_actual.clone())?;
+            // Valido la compra de cada vendedor y calculo lo que le corresponde a cada uno
+            let mut montos = Vec::new();
+            for (vendedor_actual, lista_vendedor) in compras_por_vendedor.iter() {
+                self.validar_orden(lista_vendedor.clone(), *vendedor_actual)?;
+                montos.push(self.calcular_monto(lista_vendedor.clone())?);
+            }
 
-            let monto_total = self.validar_precio(lista_publicaciones_con_cantidades.clone(), dinero_disponible)?;
+            let monto_total = self.validar_precio(lista_publicaciones_con_cantidades, pago)?;
+            let excedente = pago.checked_sub(Balance::from(monto_total)).ok_or(ErrorSistema::DineroInsuficiente)?;
 
 
-            // Una vez pasadas todas las validaciones, actualizo el stock
+            // Una vez pasadas todas las validaciones, actualizo el stock y creo una orden por vendedor
 
-            let lista_compra = self.actualizar_stock_de_orden(lista_publicaciones_con_cantidades);
+            let mut ordenes = Vec::new();
+            for ((vendedor_actual, lista_vendedor), monto) in compras_por_vendedor.into_iter().zip(montos) {
 
+                let lista_compra = self.actualizar_stock_de_orden(lista_vendedor);
 
-            let id_orden = self.generar_id_orden()?;
-            
+                let id_orden = self.generar_id_orden()?;
 
-            // Creo la orden
+                let orden = OrdenCompra {
+                    id_comprador: caller,
+                    lista_productos: lista_compra,
+                    id_orden_compra: id_orden,
+                    estado: EstadoOrdenCompra::Pendiente,
+                    id_vendedor: vendedor_actual,
+                    solicitud_cancelacion: None,
+                    monto,
+                };
 
-            let orden = OrdenCompra {
-                id_comprador: caller,
-                lista_productos: lista_compra,
-                id_orden_compra: id_orden,
-                estado: EstadoOrdenCompra::Pendiente,
-                id_vendedor: vendedor_actual,
-                solicitud_cancelacion: None,
-                monto: monto_total,
-            };
-            
-            // Agrego la orden al vector de ordenes
-            self.ordenes.push(orden.clone());
-        
-            // Agrego al vector de ambos usuarios
-            self.agregar_orden_usuario(caller, id_orden)?;
-            self.agregar_orden_usuario(vendedor_actual, id_orden)?;
+                // Guardo la orden con su id
+                self.ordenes.insert(id_orden, &orden);
+
+                // Agrego al vector de ambos usuarios
+                self.agregar_orden_usuario(caller, id_orden)?;
+                self.agregar_orden_usuario(vendedor_actual, id_orden)?;
 
+                ordenes.push(orden);
+            }
+
+            // Le devuelvo al comprador lo que haya pagado de mas
+            if excedente > 0 {
+                self.transferir(caller, excedente)?;
+            }
 
-            Ok(orden.clone())
+            Ok(ordenes)
             
         }
 
+        // Agrupa la lista de compra por vendedor, respetando el orden en que aparecen las publicaciones.
+        fn agrupar_por_vendedor(&self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>)->Result<Vec<CompraDeVendedor>, ErrorSistema>{
+            let mut compras_por_vendedor: Vec<CompraDeVendedor> = Vec::new();
+            for (id_publicacion, cant_productos) in lista_publicaciones_con_cantidades {
+                let vendedor = match self.publicaciones.get(id_publicacion) {
+                    Some(publi) => publi.id_publicador,
+                    None => return Err(ErrorSistema::PublicacionNoValida),
+                };
+
+                match compras_por_vendedor.iter_mut().find(|(id, _)| *id == vendedor) {
+                    Some((_, lista)) => lista.push((id_publicacion, cant_productos)),
+                    None => compras_por_vendedor.push((vendedor, vec![(id_publicacion, cant_productos)])),
+                }
+            }
+            Ok(compras_por_vendedor)
+        }
+
+        // Transfiere fondos retenidos en el contrato a la cuenta indicada.
+        fn transferir(&self, destino:AccountId, monto:Balance)->Result<(), ErrorSistema>{
+            self.env().transfer(destino, monto).map_err(|_| ErrorSistema::TransferenciaFallida)
+        }
+
         fn agregar_orden_usuario(&mut self, user_id:AccountId, id_orden:u128)->Result<(), ErrorSistema>{
             if let Some(mut user) = self.usuarios.get(&user_id){
                 user.ordenes.push(id_orden);
@@ -623,13 +739,18 @@ mod usuarios_sistema {
                     return Err(ErrorSistema::NoPuedeComprarCero)
                 }
 
-                if let Some(publicacion_actual) = self.publicaciones.iter().find(|x| x.id_publicacion == id_publicacion_actual){
+                if let Some(publicacion_actual) = self.publicaciones.get(id_publicacion_actual){
 
                     // Veo que todas las publicaciones sean del mismo vendedor
                     if publicacion_actual.id_publicador != vendedor_actual {
                         return Err(ErrorSistema::VendedorDistinto)
                     }
 
+                    // Veo que la publicacion este activa
+                    if !publicacion_actual.activa {
+                        return Err(ErrorSistema::PublicacionInactiva)
+                    }
+
                     // Veo que la publicacion tengo el stock necesario para la compra
                     if !publicacion_actual.tiene_stock_suficiente(cant_productos) {
                         return Err(ErrorSistema::StockInsuficiente)
@@ -643,10 +764,21 @@ mod usuarios_sistema {
         
         }
 
-        fn validar_precio(&self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, dinero_disponible: u32)->Result<u32, ErrorSistema>{
+        fn validar_precio(&self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, pago: Balance)->Result<u32, ErrorSistema>{
+            let monto_total = self.calcular_monto(lista_publicaciones_con_cantidades)?;
+
+            if pago >= Balance::from(monto_total) {
+                return Ok(monto_total)
+            }
+            else {
+                return Err(ErrorSistema::DineroInsuficiente);
+            }
+        }
+
+        fn calcular_monto(&self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>)->Result<u32, ErrorSistema>{
             let mut monto_total:u32=0;
             for (id_publicacion, cant_productos) in lista_publicaciones_con_cantidades {
-                if let Some(publicacion_actual) = self.publicaciones.get(id_publicacion as usize){
+                if let Some(publicacion_actual) = self.publicaciones.get(id_publicacion){
 
                     let monto_actual = match publicacion_actual.precio.checked_mul(cant_productos) {
                         Some(val) => val,
@@ -661,13 +793,7 @@ mod usuarios_sistema {
                     return Err(ErrorSistema::PublicacionNoValida);
                 }
             }
-
-            if dinero_disponible >= monto_total {
-                return Ok(monto_total)
-            }
-            else {
-                return Err(ErrorSistema::DineroInsuficiente);
-            }
+            Ok(monto_total)
         }
 
         fn generar_id_orden(&mut self)->Result<u128, ErrorSistema>{
@@ -684,17 +810,30 @@ mod usuarios_sistema {
         fn actualizar_stock_de_orden(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>)->Vec<(u128,u32)>{
             let mut lista_productos = Vec::new();
             for(id_publi, cant_productos) in lista_publicaciones_con_cantidades{
-                if let Some(posicion) = self.publicaciones.iter().position(|x| x.id_publicacion == id_publi){
-
-                    if let Some(publicacion_actual) = self.publicaciones.get_mut(posicion) {
-                        publicacion_actual.actualizar_stock(cant_productos);
-                        lista_productos.push((publicacion_actual.id_producto, cant_productos));
-                    }
+                if let Some(mut publicacion_actual) = self.publicaciones.get(id_publi){
+                    publicacion_actual.actualizar_stock(cant_productos);
+                    lista_productos.push((id_publi, cant_productos));
+                    self.publicaciones.insert(id_publi, &publicacion_actual);
                 }
             }
             lista_productos
         }
 
+        // Devuelve a cada publicación las unidades de la orden. Primero valida todas las sumas para no dejar el stock a medias.
+        fn restaurar_stock_de_orden(&mut self, lista_productos:&[(u128, u32)])->Result<(), ErrorSistema>{
+            let mut publicaciones = Vec::new();
+            for (id_publi, cant_productos) in lista_productos {
+                if let Some(mut publicacion_actual) = self.publicaciones.get(id_publi){
+                    publicacion_actual.stock = publicacion_actual.stock.checked_add(*cant_productos).ok_or(ErrorSistema::FueraDeRango)?;
+                    publicaciones.push(publicacion_actual);
+                }
+            }
+            for publicacion_actual in publicaciones {
+                self.publicaciones.insert(publicacion_actual.id_publicacion, &publicacion_actual);
+            }
+            Ok(())
+        }
+
 
 
 
@@ -714,14 +853,16 @@ mod usuarios_sistema {
         fn _marcar_orden_como_enviada(&mut self, id_actual:u128, caller:AccountId)->Result<(), ErrorSistema>{
 
 
-            if let Some(orden_acutal) = self.ordenes.get_mut(id_actual as usize){
+            if let Some(mut orden_acutal) = self.ordenes.get(id_actual){
                 if orden_acutal.id_vendedor != caller {
                     return Err(ErrorSistema::OperacionNoValida)
                 } 
                 match &orden_acutal.estado {
-                    EstadoOrdenCompra::Pendiente => Ok(orden_acutal.estado = EstadoOrdenCompra::Enviado),
+                    EstadoOrdenCompra::Pendiente => orden_acutal.estado = EstadoOrdenCompra::Enviado,
                     _ => return Err(ErrorSistema::OperacionNoValida),
                 }
+                self.ordenes.insert(id_actual, &orden_acutal);
+                Ok(())
                  
             }
             else {
@@ -733,6 +874,7 @@ mod usuarios_sistema {
 
 
         /// Marca una orden como recibida. Solo el comprador puede hacerlo.
+        /// Al recibirla se le transfiere al vendedor el monto que estaba retenido en el contrato.
         /// Retorna `Ok(())` si la operación fue exitosa, o un error si no corresponde.
         ///
         /// # Ejemplo
@@ -748,14 +890,19 @@ mod usuarios_sistema {
         fn _marcar_orden_como_recibida(&mut self, id_actual:u128, caller:AccountId)->Result<(), ErrorSistema>{
             
 
-            if let Some(orden_acutal) = self.ordenes.get_mut(id_actual as usize){
+            if let Some(mut orden_acutal) = self.ordenes.get(id_actual){
                 if orden_acutal.id_comprador != caller {
                     return Err(ErrorSistema::OperacionNoValida)
                 } 
                 match &orden_acutal.estado {
-                    EstadoOrdenCompra::Enviado => Ok(orden_acutal.estado = EstadoOrdenCompra::Recibido),
+                    EstadoOrdenCompra::Enviado => orden_acutal.estado = EstadoOrdenCompra::Recibido,
                     _ => return Err(ErrorSistema::OperacionNoValida),
                 }
+                self.ordenes.insert(id_actual, &orden_acutal);
+
+                // Libero el pago retenido al vendedor
+                let (vendedor, monto) = (orden_acutal.id_vendedor, orden_acutal.monto);
+                self.transferir(vendedor, Balance::from(monto))
                  
             }
             else {
@@ -768,7 +915,8 @@ mod usuarios_sistema {
 
 
         /// Solicita la cancelación de una orden. Puede ser solicitada por comprador o vendedor.
-        /// Si ambos la solicitan, la orden se cancela.
+        /// Si ambos la solicitan, la orden se cancela y se le devuelve al comprador el monto retenido.
+        /// Una orden ya recibida no se puede cancelar, porque el pago ya fue liberado al vendedor.
         /// Retorna `Ok(())` si la operación fue exitosa, o un error si no corresponde.
         ///
         /// # Ejemplo
@@ -784,24 +932,36 @@ mod usuarios_sistema {
         fn _cancelar_orden(&mut self, id_actual:u128, caller:AccountId) -> Result<(), ErrorSistema> {
             
 
-            if let Some(orden_actual) = self.ordenes.get_mut(id_actual as usize) {
+            if let Some(mut orden_actual) = self.ordenes.get(id_actual) {
 
                 if orden_actual.estado == EstadoOrdenCompra::Cancelado {
                     return Err(ErrorSistema::OrdenCancelada);
                 }
 
+                if orden_actual.estado == EstadoOrdenCompra::Recibido {
+                    return Err(ErrorSistema::OperacionNoValida);
+                }
+
                 if let Some(id_anterior) = orden_actual.solicitud_cancelacion {
                     if id_anterior == caller {
                         return Err(ErrorSistema::CancelacionYaSolicitada);
                     }
                     else {
                         if id_anterior == orden_actual.id_comprador || id_anterior == orden_actual.id_vendedor{
-                            self.ordenes.get_mut(id_actual as usize).unwrap().estado = EstadoOrdenCompra::Cancelado;
-                            return Ok(())
+                            // Le devuelvo a cada publicación las unidades que se habían descontado
+                            self.restaurar_stock_de_orden(&orden_actual.lista_productos)?;
+
+                            let (comprador, monto) = (orden_actual.id_comprador, orden_actual.monto);
+                            orden_actual.estado = EstadoOrdenCompra::Cancelado;
+                            self.ordenes.insert(id_actual, &orden_actual);
+
+                            // Le devuelvo al comprador el pago retenido
+                            return self.transferir(comprador, Balance::from(monto));
                         }
                     }
                 }
-                self.ordenes.get_mut(id_actual as usize).unwrap().solicitud_cancelacion = Some(caller);
+                orden_actual.solicitud_cancelacion = Some(caller);
+                self.ordenes.insert(id_actual, &orden_actual);
                 return Ok(())
                     
             }
@@ -814,43 +974,78 @@ mod usuarios_sistema {
 
 
 
-        /// Devuelve la lista de todas las publicaciones existentes en el sistema.
+        /// Devuelve una página de las publicaciones existentes en el sistema, ordenadas por id.
+        /// Arranca en la publicación con id `desde` y devuelve hasta `cantidad` publicaciones,
+        /// nunca más de `MAX_PUBLICACIONES_POR_PAGINA`. Si `desde` supera la última publicación devuelve una lista vacía.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      let primera_pagina = sistema.get_publicaciones(0, 20);
+        ///      let segunda_pagina = sistema.get_publicaciones(20, 20);
+        /// ```
+        #[ink(message)]
+        pub fn get_publicaciones(&self, desde:u128, cantidad:u32)->Vec<Publicacion>{
+            let cantidad = cantidad.min(MAX_PUBLICACIONES_POR_PAGINA);
+            let hasta = desde.saturating_add(u128::from(cantidad)).min(self.proximo_id_publicacion);
+            (desde..hasta).filter_map(|id| self.publicaciones.get(id)).collect()
+        }
+
+
+
+        /// Devuelve la cantidad total de publicaciones creadas, para poder recorrerlas con `get_publicaciones`.
         ///
         /// # Ejemplo
         /// ```
-        ///      let publicaciones = sistema.get_publicaciones();
+        ///      let total = sistema.get_cantidad_publicaciones();
         /// ```
         #[ink(message)]
-        pub fn get_publicaciones(&self)->Vec<Publicacion>{
-            self.publicaciones.clone()
+        pub fn get_cantidad_publicaciones(&self)->u128{
+            self.proximo_id_publicacion
         }
 
 
 
-        /// Devuelve la lista de órdenes asociadas al usuario que llama.
+        /// Devuelve una página de las órdenes asociadas al usuario que llama, en el orden en que se crearon.
+        /// Arranca en la posición `desde` de su lista de órdenes y devuelve hasta `cantidad` órdenes,
+        /// nunca más de `MAX_PUBLICACIONES_POR_PAGINA`. Si `desde` supera la última orden devuelve una lista vacía.
         ///
         /// # Ejemplo
         /// ```
-        ///   let mis_ordenes = sistema.ver_mis_ordenes();
+        ///   let mis_ordenes = sistema.ver_mis_ordenes(0, 20);
         /// ```
         #[ink(message)]
-        pub fn ver_mis_ordenes(&self)->Vec<OrdenCompra>{
+        pub fn ver_mis_ordenes(&self, desde:u32, cantidad:u32)->Vec<OrdenCompra>{
             let caller = self.env().caller();
-            self._ver_mis_ordenes(caller)
+            self._ver_mis_ordenes(caller, desde, cantidad)
         }
 
-        fn _ver_mis_ordenes(&self, caller:AccountId)->Vec<OrdenCompra>{
+        fn _ver_mis_ordenes(&self, caller:AccountId, desde:u32, cantidad:u32)->Vec<OrdenCompra>{
             let mut mis_ordenes = Vec::new();
             if let Some(user) = self.usuarios.get(caller){
-                for id in user.ordenes {
-                    if let Some(orden) = self.ordenes.get(id as usize){
-                        mis_ordenes.push(orden.clone())
+                let cantidad = cantidad.min(MAX_PUBLICACIONES_POR_PAGINA);
+                for id in user.ordenes.into_iter().skip(desde as usize).take(cantidad as usize) {
+                    if let Some(orden) = self.ordenes.get(id){
+                        mis_ordenes.push(orden)
                     }
                     
                 }
             }
             mis_ordenes
         }
+
+
+
+        /// Devuelve la cantidad de órdenes asociadas al usuario que llama, para poder recorrerlas con `ver_mis_ordenes`.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///   let total = sistema.get_cantidad_mis_ordenes();
+        /// ```
+        #[ink(message)]
+        pub fn get_cantidad_mis_ordenes(&self)->u32{
+            let caller = self.env().caller();
+            self.usuarios.get(caller).map_or(0, |user| user.ordenes.len() as u32)
+        }
     }
 
     impl Usuario {
@@ -891,6 +1086,38 @@ mod usuarios_sistema {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
 
+        /// Simula el pago de una compra: acredita `pago` en el contrato y lo envía como valor transferido.
+        /// Si la compra falla el saldo del contrato vuelve a como estaba, igual que al revertirse en la cadena.
+        fn generar_orden_pagando(sistema: &mut Sistema, lista: Vec<(u128, u32)>, pago: Balance) -> Result<Vec<OrdenCompra>, ErrorSistema> {
+            let contrato = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            let saldo = saldo_de(contrato);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contrato, saldo + pago);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(pago);
+
+            let resultado = sistema.generar_orden_compra(lista);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            if resultado.is_err() {
+                ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contrato, saldo);
+            }
+            resultado
+        }
+
+        fn saldo_de(cuenta: AccountId) -> Balance {
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(cuenta).unwrap_or(0)
+        }
+
+        /// Saldo con el que arranca el contrato en los tests (el mínimo que acepta el entorno de pruebas).
+        const SALDO_CONTRATO: Balance = 1_000_000;
+
+        /// Usa una cuenta propia para el contrato, así los fondos retenidos no se mezclan con los de los usuarios.
+        fn set_contrato() -> AccountId {
+            let contrato = AccountId::from([0xFF; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contrato);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contrato, SALDO_CONTRATO);
+            contrato
+        }
+
 
         /// We test that we can register a user.
         /// In this test the user is added successfully.
@@ -1049,9 +1276,9 @@ mod usuarios_sistema {
             }
 
             sistema.crear_publicacion(0, 10, 19);
-            assert_eq!(sistema.get_publicaciones().len(), 1);
+            assert_eq!(sistema.get_publicaciones(0, 10).len(), 1);
 
-            assert_eq!(sistema.publicaciones[0].tiene_stock_suficiente(20), false);
+            assert_eq!(sistema.publicaciones.get(0).unwrap().tiene_stock_suficiente(20), false);
 
             let mut lista_compra = Vec::new();
             lista_compra.push((0,2));
@@ -1061,11 +1288,11 @@ mod usuarios_sistema {
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
 
-            if let Err(e) = sistema.generar_orden_compra(lista_compra.clone(), 1) {
+            if let Err(e) = generar_orden_pagando(&mut sistema, lista_compra.clone(), 1) {
                 assert_eq!(e, ErrorSistema::DineroInsuficiente);
             }
             
-            assert!(sistema.generar_orden_compra(lista_compra, 200).is_ok());
+            assert!(generar_orden_pagando(&mut sistema, lista_compra, 200).is_ok());
 
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
 
@@ -1135,14 +1362,14 @@ mod usuarios_sistema {
             sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
             //Quiero forzar el error de publicacionNoValida
-            let error_publicacion_invalida = sistema.generar_orden_compra(vec![(0, 1)],1).unwrap_err();
+            let error_publicacion_invalida = generar_orden_pagando(&mut sistema, vec![(0, 1)],1).unwrap_err();
             assert_eq!(error_publicacion_invalida, ErrorSistema::PublicacionNoValida); //Ok
 
             //Quiero forzar el error de NoPuedeComprarPublicacionPropia
             sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
             sistema.crear_publicacion(0, 1000, 4);
 
-            let error_no_puede_comprar_publicacion_propia = sistema.generar_orden_compra(vec![(0, 1)],4000).unwrap_err();
+            let error_no_puede_comprar_publicacion_propia = generar_orden_pagando(&mut sistema, vec![(0, 1)],4000).unwrap_err();
             assert_eq!(error_no_puede_comprar_publicacion_propia, ErrorSistema::NoPuedeComprarPublicacionPropia); //Ok.
         }
 
@@ -1211,7 +1438,7 @@ mod usuarios_sistema {
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
             let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            assert!(generar_orden_pagando(&mut sistema, lista_compra, 4000).is_ok());
 
             //Quiero forzar el error de OperacionNoValida.
             let error_operacion_no_valida = sistema.marcar_orden_como_enviada(0).unwrap_err();
@@ -1240,7 +1467,7 @@ mod usuarios_sistema {
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
             let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            assert!(generar_orden_pagando(&mut sistema, lista_compra, 4000).is_ok());
 
 
             //Quiero marcar la orden como recibida.
@@ -1278,7 +1505,7 @@ mod usuarios_sistema {
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
             let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            assert!(generar_orden_pagando(&mut sistema, lista_compra, 4000).is_ok());
 
             //Quiero forzar el error de OperacionNoValida.
             let error_operacion_no_valida = sistema.marcar_orden_como_recibida(0).unwrap_err();
@@ -1324,11 +1551,12 @@ mod usuarios_sistema {
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
             let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            assert!(generar_orden_pagando(&mut sistema, lista_compra, 4000).is_ok());
 
             //Verifico que la orden de compra se haya agregado a las órdenes del usuario Alice.
-            let mis_ordenes = sistema.ver_mis_ordenes();
+            let mis_ordenes = sistema.ver_mis_ordenes(0, 10);
             assert_eq!(mis_ordenes.len(), 1);
+            assert_eq!(sistema.get_cantidad_mis_ordenes(), 1);
         }
         
     
@@ -1356,19 +1584,19 @@ mod usuarios_sistema {
             lista_compra.push((1,3));
 
 
-            if let Err(e) = sistema.generar_orden_compra(lista_compra.clone(), 70){
+            if let Err(e) = generar_orden_pagando(&mut sistema, lista_compra.clone(), 70){
                 assert_eq!(e, ErrorSistema::DineroInsuficiente);
             }
 
-            if let Ok(ord) = sistema.generar_orden_compra(lista_compra.clone(), 200){
-                assert_eq!(ord.monto, 80);
+            if let Ok(ord) = generar_orden_pagando(&mut sistema, lista_compra.clone(), 200){
+                assert_eq!(ord[0].monto, 80);
             }
 
-            if let Err(e) = sistema.generar_orden_compra(vec![(1,1), (2,1)], 200) {
+            if let Err(e) = generar_orden_pagando(&mut sistema, vec![(1,1), (2,1)], 200) {
                 assert_eq!(e, ErrorSistema::FueraDeRango);
             }
 
-            if let Err(e) = sistema.generar_orden_compra(vec![(2,3)], 200) {
+            if let Err(e) = generar_orden_pagando(&mut sistema, vec![(2,3)], 200) {
                 assert_eq!(e, ErrorSistema::FueraDeRango);
             }
 
@@ -1405,11 +1633,277 @@ mod usuarios_sistema {
 
             sistema.crear_publicacion(0, 10, 19);
 
-            if let Some(p) = sistema.publicaciones.get_mut(0) {
+            if let Some(mut p) = sistema.publicaciones.get(0) {
                 assert_eq!(p.actualizar_stock(u32::MAX), Err(ErrorSistema::PublicacionesLleno))
             }
 
         }
+
+        #[ink::test]
+        fn test_pago_retenido_hasta_recibir() {
+            let contrato = set_contrato();
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap();
+            sistema.crear_publicacion(0, 1000, 4).unwrap();
+
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+
+            //Con un pago menor al monto de la orden no se genera nada.
+            assert_eq!(generar_orden_pagando(&mut sistema, vec![(0, 2)], 1999).unwrap_err(), ErrorSistema::DineroInsuficiente);
+            assert_eq!(saldo_de(contrato), SALDO_CONTRATO);
+
+            //Paga de más: el excedente vuelve al comprador y el resto queda retenido.
+            let saldo_alice = saldo_de(alice);
+            let ordenes = generar_orden_pagando(&mut sistema, vec![(0, 2)], 2500).unwrap();
+            assert_eq!(ordenes.len(), 1);
+            assert_eq!(ordenes[0].monto, 2000);
+            assert_eq!(saldo_de(contrato), SALDO_CONTRATO + 2000);
+            assert_eq!(saldo_de(alice), saldo_alice + 500);
+
+            //Enviar la orden no libera el pago.
+            let saldo_charlie = saldo_de(charlie);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.marcar_orden_como_enviada(0).is_ok());
+            assert_eq!(saldo_de(charlie), saldo_charlie);
+
+            //Al recibirla el vendedor cobra.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert!(sistema.marcar_orden_como_recibida(0).is_ok());
+            assert_eq!(saldo_de(charlie), saldo_charlie + 2000);
+            assert_eq!(saldo_de(contrato), SALDO_CONTRATO);
+
+            //Una orden recibida ya no se puede cancelar.
+            assert_eq!(sistema.cancelar_orden(0), Err(ErrorSistema::OperacionNoValida));
+        }
+
+        #[ink::test]
+        fn test_pago_dividido_entre_vendedores() {
+            let contrato = set_contrato();
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap();
+            sistema.crear_publicacion(0, 1000, 4).unwrap(); //Publicación 0 de Charlie.
+
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa).unwrap();
+            sistema.crear_publicacion(1, 300, 10).unwrap(); //Publicación 1 de Bob.
+            sistema.crear_publicacion(0, 50, 10).unwrap(); //Publicación 2 de Bob.
+
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+
+            //Una compra con publicaciones de dos vendedores genera una orden para cada uno.
+            let ordenes = generar_orden_pagando(&mut sistema, vec![(1, 2), (0, 1), (2, 4)], 1800).unwrap();
+            assert_eq!(ordenes.len(), 2);
+            assert_eq!(ordenes[0].id_vendedor, bob);
+            assert_eq!(ordenes[0].monto, 800);
+            assert_eq!(ordenes[0].lista_productos, vec![(1, 2), (2, 4)]);
+            assert_eq!(ordenes[1].id_vendedor, charlie);
+            assert_eq!(ordenes[1].monto, 1000);
+            assert_eq!(saldo_de(contrato), SALDO_CONTRATO + 1800);
+            assert_eq!(sistema.ver_mis_ordenes(0, 10).len(), 2);
+
+            //Cada vendedor cobra su parte cuando se recibe su orden.
+            let saldo_bob = saldo_de(bob);
+            let saldo_charlie = saldo_de(charlie);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert!(sistema.marcar_orden_como_enviada(0).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert!(sistema.marcar_orden_como_recibida(0).is_ok());
+            assert_eq!(saldo_de(bob), saldo_bob + 800);
+            assert_eq!(saldo_de(charlie), saldo_charlie);
+            assert_eq!(saldo_de(contrato), SALDO_CONTRATO + 1000);
+        }
+
+        #[ink::test]
+        fn test_cancelar_orden_devuelve_pago() {
+            let contrato = set_contrato();
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap();
+            sistema.crear_publicacion(0, 1000, 4).unwrap();
+
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            assert!(generar_orden_pagando(&mut sistema, vec![(0, 1)], 1000).is_ok());
+            let saldo_alice = saldo_de(alice);
+
+            //Con una sola solicitud el pago sigue retenido.
+            assert!(sistema.cancelar_orden(0).is_ok());
+            assert_eq!(saldo_de(alice), saldo_alice);
+            assert_eq!(saldo_de(contrato), SALDO_CONTRATO + 1000);
+
+            //Cuando el vendedor acepta, se cancela y se le devuelve el pago al comprador.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.cancelar_orden(0).is_ok());
+            assert_eq!(saldo_de(alice), saldo_alice + 1000);
+            assert_eq!(saldo_de(contrato), SALDO_CONTRATO);
+        }
+
+        #[ink::test]
+        fn test_cancelar_orden_restaura_stock() {
+            set_contrato();
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap();
+            sistema.crear_publicacion(0, 100, 4).unwrap();
+            sistema.crear_publicacion(0, 50, 10).unwrap();
+
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            assert!(generar_orden_pagando(&mut sistema, vec![(0, 3), (1, 6)], 600).is_ok());
+            assert_eq!(sistema.publicaciones.get(0).unwrap().stock, 1);
+            assert_eq!(sistema.publicaciones.get(1).unwrap().stock, 4);
+
+            //Con una sola solicitud el stock no vuelve.
+            assert!(sistema.cancelar_orden(0).is_ok());
+            assert_eq!(sistema.publicaciones.get(0).unwrap().stock, 1);
+
+            //Con la cancelación mutua cada publicación recupera sus unidades.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.cancelar_orden(0).is_ok());
+            assert_eq!(sistema.publicaciones.get(0).unwrap().stock, 4);
+            assert_eq!(sistema.publicaciones.get(1).unwrap().stock, 10);
+        }
+
+        #[ink::test]
+        fn test_desactivar_y_reactivar_publicacion() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap();
+            sistema.crear_publicacion(0, 1000, 4).unwrap();
+
+            assert!(sistema.desactivar_publicacion(0).is_ok());
+            assert!(!sistema.publicaciones.get(0).unwrap().activa);
+            assert_eq!(sistema.desactivar_publicacion(0), Err(ErrorSistema::OperacionNoValida)); //Ya estaba inactiva.
+
+            //No se puede comprar una publicación inactiva.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            assert_eq!(generar_orden_pagando(&mut sistema, vec![(0, 1)], 1000).unwrap_err(), ErrorSistema::PublicacionInactiva);
+
+            //Solo el publicador puede reactivarla.
+            assert_eq!(sistema.reactivar_publicacion(0), Err(ErrorSistema::OperacionNoValida));
+            assert_eq!(sistema.reactivar_publicacion(5), Err(ErrorSistema::PublicacionNoValida));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.reactivar_publicacion(0).is_ok());
+            assert_eq!(sistema.reactivar_publicacion(0), Err(ErrorSistema::OperacionNoValida)); //Ya estaba activa.
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert!(generar_orden_pagando(&mut sistema, vec![(0, 1)], 1000).is_ok());
+        }
+
+        #[ink::test]
+        fn test_editar_publicacion() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap();
+            sistema.crear_publicacion(0, 1000, 4).unwrap();
+
+            assert!(sistema.editar_publicacion(0, 1500, 8).is_ok());
+            let publicacion = sistema.publicaciones.get(0).unwrap();
+            assert_eq!(publicacion.precio, 1500);
+            assert_eq!(publicacion.stock, 8);
+
+            //Otro usuario no puede editarla.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos).unwrap();
+            assert_eq!(sistema.editar_publicacion(0, 1, 1), Err(ErrorSistema::OperacionNoValida));
+
+            //La compra usa el nuevo precio.
+            assert_eq!(generar_orden_pagando(&mut sistema, vec![(0, 2)], 2000).unwrap_err(), ErrorSistema::DineroInsuficiente);
+            assert!(generar_orden_pagando(&mut sistema, vec![(0, 2)], 3000).is_ok());
+        }
+
+        #[ink::test]
+        fn test_get_publicaciones_paginado() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap();
+
+            let total = MAX_PUBLICACIONES_POR_PAGINA + 10;
+            for precio in 0..total {
+                sistema.crear_publicacion(0, precio, 1).unwrap();
+            }
+            assert_eq!(sistema.get_cantidad_publicaciones(), u128::from(total));
+
+            //Las páginas respetan el orden de los ids.
+            let pagina = sistema.get_publicaciones(5, 3);
+            assert_eq!(pagina.iter().map(|p| p.id_publicacion).collect::<Vec<u128>>(), vec![5, 6, 7]);
+
+            //Nunca se devuelven más publicaciones que el máximo por página.
+            assert_eq!(sistema.get_publicaciones(0, u32::MAX).len(), MAX_PUBLICACIONES_POR_PAGINA as usize);
+
+            //La última página puede venir incompleta y pasado el final no hay nada.
+            assert_eq!(sistema.get_publicaciones(u128::from(MAX_PUBLICACIONES_POR_PAGINA), MAX_PUBLICACIONES_POR_PAGINA).len(), 10);
+            assert!(sistema.get_publicaciones(u128::from(total), 10).is_empty());
+            assert!(sistema.get_publicaciones(u128::MAX, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn test_ordenes_indexadas_por_usuario() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            generar_orden_pagando(&mut sistema, vec![(0, 1)], 100).unwrap();
+
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            generar_orden_pagando(&mut sistema, vec![(0, 2)], 200).unwrap();
+
+            //Cada comprador solo ve sus órdenes y el vendedor ve las de ambos.
+            let ids = |ordenes: Vec<OrdenCompra>| ordenes.iter().map(|o| o.id_orden_compra).collect::<Vec<u128>>();
+            assert_eq!(ids(sistema.ver_mis_ordenes(0, 10)), vec![1]);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(ids(sistema.ver_mis_ordenes(0, 10)), vec![0]);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(ids(sistema.ver_mis_ordenes(0, 10)), vec![0, 1]);
+
+            //Las órdenes también se recorren por páginas.
+            assert_eq!(sistema.get_cantidad_mis_ordenes(), 2);
+            assert_eq!(ids(sistema.ver_mis_ordenes(1, 10)), vec![1]);
+            assert_eq!(ids(sistema.ver_mis_ordenes(0, 1)), vec![0]);
+            assert!(sistema.ver_mis_ordenes(2, 10).is_empty());
+
+            //Los cambios de estado quedan guardados en el Mapping.
+            assert!(sistema.marcar_orden_como_enviada(1).is_ok());
+            assert_eq!(sistema.ordenes.get(1).unwrap().estado, EstadoOrdenCompra::Enviado);
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Pendiente);
+            assert_eq!(sistema.publicaciones.get(0).unwrap().stock, 7);
+        }
     }
 
 
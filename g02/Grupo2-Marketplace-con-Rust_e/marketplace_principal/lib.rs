@@ -83,6 +83,12 @@ Simulación de pagos.
 
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub use self::marketplace_principal::{
+    EstadoOrden, EstadoOrdenActualizado, MarketplacePrincipal, MarketplacePrincipalRef, Orden,
+    OrdenCreada, Producto, ProductoPublicado, RolUsuario, SistemaError, Usuario,
+    UsuarioRegistrado,
+};
+
 #[ink::contract]
 mod marketplace_principal {
     // Importa los derive macros y tipos
@@ -90,18 +96,38 @@ mod marketplace_principal {
     use scale_info::TypeInfo;
     use ink::storage::traits::StorageLayout;
     use ink::storage::Mapping;
+    use ink::storage::traits::StorageKey;
     use ink::prelude::string::String;
     use ink::prelude::vec::Vec;
 
+    /// Máxima cantidad de elementos que devuelve una consulta paginada.
+    const MAX_POR_PAGINA: u32 = 50;
+
     /// Estructura principal del contrato Marketplace.
     #[ink(storage)]
     pub struct MarketplacePrincipal {
         /// Mapeo de usuarios registrados (por dirección).
         usuarios: Mapping<AccountId, Usuario>,
-        /// Lista de productos publicados.
-        productos: Vec<Producto>,
-        /// Lista de órdenes generadas.
-        ordenes: Vec<Orden>,
+        /// Productos publicados (por id).
+        productos: Mapping<u32, Producto>,
+        /// Órdenes generadas (por id).
+        ordenes: Mapping<u32, Orden>,
+        /// Id que recibirá el próximo producto publicado.
+        next_producto_id: u32,
+        /// Id que recibirá la próxima orden creada.
+        next_orden_id: u32,
+        /// Ids de los productos publicados por cada vendedor: (vendedor, posición) -> id.
+        productos_por_vendedor: Mapping<(AccountId, u32), u32>,
+        /// Cantidad de productos publicados por cada vendedor.
+        cantidad_productos_por_vendedor: Mapping<AccountId, u32>,
+        /// Ids de las órdenes recibidas por cada vendedor: (vendedor, posición) -> id.
+        ordenes_por_vendedor: Mapping<(AccountId, u32), u32>,
+        /// Cantidad de órdenes recibidas por cada vendedor.
+        cantidad_ordenes_por_vendedor: Mapping<AccountId, u32>,
+        /// Ids de las órdenes realizadas por cada comprador: (comprador, posición) -> id.
+        ordenes_por_comprador: Mapping<(AccountId, u32), u32>,
+        /// Cantidad de órdenes realizadas por cada comprador.
+        cantidad_ordenes_por_comprador: Mapping<AccountId, u32>,
     }
 
     impl MarketplacePrincipal {
@@ -110,8 +136,16 @@ mod marketplace_principal {
         pub fn new() -> Self {
             Self {
                 usuarios: Mapping::default(),
-                productos: Vec::new(),
-                ordenes: Vec::new(),
+                productos: Mapping::default(),
+                ordenes: Mapping::default(),
+                next_producto_id: 0,
+                next_orden_id: 0,
+                productos_por_vendedor: Mapping::default(),
+                cantidad_productos_por_vendedor: Mapping::default(),
+                ordenes_por_vendedor: Mapping::default(),
+                cantidad_ordenes_por_vendedor: Mapping::default(),
+                ordenes_por_comprador: Mapping::default(),
+                cantidad_ordenes_por_comprador: Mapping::default(),
             }
         }
 
@@ -144,6 +178,113 @@ mod marketplace_principal {
             self.usuarios.get(&usuario)
         }
 
+        /// Obtiene un producto publicado por su id.
+        ///
+        /// # Retorna
+        /// - `Some(Producto)` si el producto existe.
+        /// - `None` si el producto no existe.
+        #[ink(message)]
+        pub fn obtener_producto(&self, producto_id: u32) -> Option<Producto> {
+            self.productos.get(producto_id)
+        }
+
+        /// Devuelve una página de los productos publicados en el marketplace, en orden de id.
+        ///
+        /// # Parámetros
+        /// - `desde`: id del primer producto.
+        /// - `limite`: cantidad máxima de productos, acotada a `MAX_POR_PAGINA`.
+        #[ink(message)]
+        pub fn listar_productos(&self, desde: u32, limite: u32) -> Vec<Producto> {
+            Self::pagina(desde, limite, self.next_producto_id)
+                .filter_map(|id| self.productos.get(id))
+                .collect()
+        }
+
+        /// Devuelve cuántos productos se publicaron en el marketplace.
+        #[ink(message)]
+        pub fn cantidad_productos(&self) -> u32 {
+            self.next_producto_id
+        }
+
+        /// Devuelve una página de los productos publicados por un vendedor.
+        ///
+        /// # Parámetros
+        /// - `desde`: posición del primer producto (0 es el primero que publicó).
+        /// - `limite`: cantidad máxima de productos, acotada a `MAX_POR_PAGINA`.
+        #[ink(message)]
+        pub fn listar_productos_de_vendedor(&self, vendedor: AccountId, desde: u32, limite: u32) -> Vec<Producto> {
+            Self::ids_segun_indice(&self.productos_por_vendedor, &self.cantidad_productos_por_vendedor, vendedor, desde, limite)
+                .into_iter()
+                .filter_map(|id| self.productos.get(id))
+                .collect()
+        }
+
+        /// Devuelve cuántos productos publicó un vendedor.
+        #[ink(message)]
+        pub fn cantidad_productos_de_vendedor(&self, vendedor: AccountId) -> u32 {
+            self.cantidad_productos_por_vendedor.get(vendedor).unwrap_or(0)
+        }
+
+        /// Obtiene una orden por su id.
+        ///
+        /// # Retorna
+        /// - `Some(Orden)` si la orden existe.
+        /// - `None` si la orden no existe.
+        #[ink(message)]
+        pub fn obtener_orden(&self, orden_id: u32) -> Option<Orden> {
+            self.ordenes.get(orden_id)
+        }
+
+        /// Devuelve una página de las órdenes generadas en el marketplace, en orden de id.
+        ///
+        /// # Parámetros
+        /// - `desde`: id de la primera orden.
+        /// - `limite`: cantidad máxima de órdenes, acotada a `MAX_POR_PAGINA`.
+        #[ink(message)]
+        pub fn listar_ordenes(&self, desde: u32, limite: u32) -> Vec<Orden> {
+            Self::pagina(desde, limite, self.next_orden_id)
+                .filter_map(|id| self.ordenes.get(id))
+                .collect()
+        }
+
+        /// Devuelve cuántas órdenes se generaron en el marketplace.
+        #[ink(message)]
+        pub fn cantidad_ordenes(&self) -> u32 {
+            self.next_orden_id
+        }
+
+        /// Devuelve una página de las órdenes realizadas por un comprador.
+        ///
+        /// # Parámetros
+        /// - `desde`: posición de la primera orden (0 es la más antigua).
+        /// - `limite`: cantidad máxima de órdenes, acotada a `MAX_POR_PAGINA`.
+        #[ink(message)]
+        pub fn listar_ordenes_de_comprador(&self, comprador: AccountId, desde: u32, limite: u32) -> Vec<Orden> {
+            self.ordenes_segun_indice(&self.ordenes_por_comprador, &self.cantidad_ordenes_por_comprador, comprador, desde, limite)
+        }
+
+        /// Devuelve cuántas órdenes realizó un comprador.
+        #[ink(message)]
+        pub fn cantidad_ordenes_de_comprador(&self, comprador: AccountId) -> u32 {
+            self.cantidad_ordenes_por_comprador.get(comprador).unwrap_or(0)
+        }
+
+        /// Devuelve una página de las órdenes recibidas por un vendedor.
+        ///
+        /// # Parámetros
+        /// - `desde`: posición de la primera orden (0 es la más antigua).
+        /// - `limite`: cantidad máxima de órdenes, acotada a `MAX_POR_PAGINA`.
+        #[ink(message)]
+        pub fn listar_ordenes_de_vendedor(&self, vendedor: AccountId, desde: u32, limite: u32) -> Vec<Orden> {
+            self.ordenes_segun_indice(&self.ordenes_por_vendedor, &self.cantidad_ordenes_por_vendedor, vendedor, desde, limite)
+        }
+
+        /// Devuelve cuántas órdenes recibió un vendedor.
+        #[ink(message)]
+        pub fn cantidad_ordenes_de_vendedor(&self, vendedor: AccountId) -> u32 {
+            self.cantidad_ordenes_por_vendedor.get(vendedor).unwrap_or(0)
+        }
+
         /// Lógica interna para registrar un usuario.
         fn registrar_usuario_interno(&mut self, rol: RolUsuario) -> Result<(), SistemaError> {
             let usuario_llamador = self.env().caller();
@@ -159,6 +300,10 @@ mod marketplace_principal {
                 reputacion_como_vendedor: 0,
             };
             self.usuarios.insert(usuario_llamador, &nuevo_usuario);
+            self.env().emit_event(UsuarioRegistrado {
+                usuario: usuario_llamador,
+                rol: nuevo_usuario.rol,
+            });
             Ok(())
         }
 
@@ -229,22 +374,16 @@ mod marketplace_principal {
             // Validar que la cantidad solicitada sea válida
             self.verificar_cantidad(cantidad)?;
             
-            // Obtén el vendedor antes del mutable borrow
-            let vendedor = {
-                let producto_ref = self.productos.iter().find(|p| p.id == producto_id)
-                    .ok_or(SistemaError::ProductosVacios)?;
-                
-                // Verificar stock disponible antes de proceder
-                self.verificar_stock_disponible(producto_ref, cantidad)?;
-                
-                producto_ref.vendedor
-            };
+            let mut producto = self.buscar_producto(producto_id)?;
+            
+            // Verificar stock disponible antes de proceder
+            self.verificar_stock_disponible(&producto, cantidad)?;
             
-            // Ahora sí obtener el producto mutable y descontar stock
-            let producto = self.obtener_producto_mut(producto_id)?;
+            // Descontar stock y guardar el producto actualizado
             producto.cantidad = producto.cantidad.saturating_sub(cantidad);
+            self.productos.insert(producto_id, &producto);
             
-            self.crear_y_emitir_orden(comprador, vendedor, producto_id, cantidad)
+            self.crear_y_emitir_orden(comprador, producto.vendedor, producto_id, cantidad)
         }
 
         /// Permite al vendedor marcar una orden como enviada.
@@ -271,15 +410,17 @@ mod marketplace_principal {
         fn actualizar_estado_orden(&mut self, orden_id: u32, nuevo_estado: EstadoOrden) -> Result<(), SistemaError> {
             let caller = self.env().caller();
             self.verificar_registro(caller)?;
-            // Primero obten la orden de forma inmutable para verificar el permiso
-            {
-                let orden_ref = self.ordenes.get(orden_id as usize).ok_or(SistemaError::OrdenNoExiste)?;
-                self.verificar_permiso_orden(caller, orden_ref, &nuevo_estado)?;
-            }
-            // Luego pide el borrow mutable para modificar el estado
-            let orden = self.obtener_orden_mut(orden_id)?;
-            let _estado_anterior = orden.estado.clone();
-            orden.estado = nuevo_estado;
+            let mut orden = self.buscar_orden(orden_id)?;
+            self.verificar_permiso_orden(caller, &orden, &nuevo_estado)?;
+            // Modifica el estado y guarda la orden actualizada
+            let estado_anterior = orden.estado.clone();
+            orden.estado = nuevo_estado.clone();
+            self.ordenes.insert(orden_id, &orden);
+            self.env().emit_event(EstadoOrdenActualizado {
+                orden_id,
+                estado_anterior,
+                estado_nuevo: nuevo_estado,
+            });
             Ok(())
         }
 
@@ -352,16 +493,18 @@ mod marketplace_principal {
             categoria: String,
             vendedor: AccountId,
         ) -> Result<(), SistemaError> {
-            let id = self.productos.len() as u32;
+            let id = self.next_producto_id;
+            self.next_producto_id = id.checked_add(1).ok_or(SistemaError::IdsAgotados)?;
             let nuevo_producto = Producto::new(id, nombre, descripcion, precio, cantidad, categoria, vendedor);
-            self.productos.push(nuevo_producto);
+            self.emitir_evento_publicacion(&nuevo_producto);
+            self.productos.insert(id, &nuevo_producto);
+            Self::agregar_a_indice(&mut self.productos_por_vendedor, &mut self.cantidad_productos_por_vendedor, vendedor, id)?;
             Ok(())
         }
-        /// Obtiene un producto mutable por su id.
-        fn obtener_producto_mut(&mut self, id: u32) -> Result<&mut Producto, SistemaError> {
+        /// Obtiene un producto por su id.
+        fn buscar_producto(&self, id: u32) -> Result<Producto, SistemaError> {
             self.productos
-                .iter_mut()
-                .find(|p| p.id == id)
+                .get(id)
                 .ok_or(SistemaError::ProductosVacios)
         }
 
@@ -373,17 +516,96 @@ mod marketplace_principal {
             producto_id: u32,
             cantidad: u32
         ) -> Result<u32, SistemaError> {
-            let id = self.ordenes.len() as u32;
+            let id = self.next_orden_id;
+            self.next_orden_id = id.checked_add(1).ok_or(SistemaError::IdsAgotados)?;
             let nueva_orden = Orden::new(id, comprador, vendedor, producto_id, cantidad);
-            self.ordenes.push(nueva_orden.clone());
-            // self.emitir_evento_creacion(nueva_orden);
+            self.emitir_evento_creacion(&nueva_orden);
+            self.ordenes.insert(id, &nueva_orden);
+            Self::agregar_a_indice(&mut self.ordenes_por_comprador, &mut self.cantidad_ordenes_por_comprador, comprador, id)?;
+            Self::agregar_a_indice(&mut self.ordenes_por_vendedor, &mut self.cantidad_ordenes_por_vendedor, vendedor, id)?;
             Ok(id)
         }
 
-        /// Obtiene una orden mutable por su id.
-        fn obtener_orden_mut(&mut self, id: u32) -> Result<&mut Orden, SistemaError> {
+        /// Agrega un id al final del índice de una cuenta.
+        ///
+        /// Cada id ocupa su propia celda, así el costo no depende de cuántos ids tenga la cuenta.
+        fn agregar_a_indice<K: StorageKey, C: StorageKey>(
+            indice: &mut Mapping<(AccountId, u32), u32, K>,
+            contador: &mut Mapping<AccountId, u32, C>,
+            cuenta: AccountId,
+            id: u32,
+        ) -> Result<(), SistemaError> {
+            let posicion = contador.get(cuenta).unwrap_or(0);
+            let siguiente = posicion.checked_add(1).ok_or(SistemaError::IdsAgotados)?;
+            indice.insert((cuenta, posicion), &id);
+            contador.insert(cuenta, &siguiente);
+            Ok(())
+        }
+
+        /// Obtiene los ids de una página del índice de una cuenta.
+        fn ids_segun_indice<K: StorageKey, C: StorageKey>(
+            indice: &Mapping<(AccountId, u32), u32, K>,
+            contador: &Mapping<AccountId, u32, C>,
+            cuenta: AccountId,
+            desde: u32,
+            limite: u32,
+        ) -> Vec<u32> {
+            let total = contador.get(cuenta).unwrap_or(0);
+            Self::pagina(desde, limite, total)
+                .filter_map(|posicion| indice.get((cuenta, posicion)))
+                .collect()
+        }
+
+        /// Posiciones de una página que empieza en `desde`, con a lo sumo
+        /// `limite` (acotado a `MAX_POR_PAGINA`) elementos y sin pasar de `total`.
+        fn pagina(desde: u32, limite: u32, total: u32) -> core::ops::Range<u32> {
+            let hasta = desde.saturating_add(limite.min(MAX_POR_PAGINA)).min(total);
+            desde..hasta
+        }
+
+        /// Obtiene las órdenes de una página del índice de una cuenta.
+        fn ordenes_segun_indice<K: StorageKey, C: StorageKey>(
+            &self,
+            indice: &Mapping<(AccountId, u32), u32, K>,
+            contador: &Mapping<AccountId, u32, C>,
+            cuenta: AccountId,
+            desde: u32,
+            limite: u32,
+        ) -> Vec<Orden> {
+            Self::ids_segun_indice(indice, contador, cuenta, desde, limite)
+                .into_iter()
+                .filter_map(|id| self.ordenes.get(id))
+                .collect()
+        }
+
+        /// Emite el evento `ProductoPublicado` con todos los datos del producto.
+        fn emitir_evento_publicacion(&self, producto: &Producto) {
+            self.env().emit_event(ProductoPublicado {
+                producto_id: producto.id,
+                vendedor: producto.vendedor,
+                nombre: producto.nombre.clone(),
+                descripcion: producto.descripcion.clone(),
+                precio: producto.precio,
+                cantidad: producto.cantidad,
+                categoria: producto.categoria.clone(),
+            });
+        }
+
+        /// Emite el evento `OrdenCreada`. El stock del producto se descuenta en `cantidad`.
+        fn emitir_evento_creacion(&self, orden: &Orden) {
+            self.env().emit_event(OrdenCreada {
+                orden_id: orden.id,
+                comprador: orden.comprador,
+                vendedor: orden.vendedor,
+                producto_id: orden.producto_id,
+                cantidad: orden.cantidad,
+            });
+        }
+
+        /// Obtiene una orden por su id.
+        fn buscar_orden(&self, id: u32) -> Result<Orden, SistemaError> {
             self.ordenes
-                .get_mut(id as usize)
+                .get(id)
                 .ok_or(SistemaError::OrdenNoExiste)
         }
         /// Verifica si el caller tiene permiso para cambiar el estado de la orden.
@@ -454,6 +676,7 @@ mod marketplace_principal {
         OrdenNoExiste,
         UsuarioExistente,
         StockInsuficiente,
+        IdsAgotados,
     }
     impl core::fmt::Display for SistemaError {
         fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -466,10 +689,84 @@ mod marketplace_principal {
                 SistemaError::OrdenNoExiste => write!(f, "La orden no existe"),
                 SistemaError::UsuarioExistente => write!(f, "El usuario ya está registrado"),
                 SistemaError::StockInsuficiente => write!(f, "Stock insuficiente para la cantidad solicitada"),
+                SistemaError::IdsAgotados => write!(f, "No quedan identificadores disponibles"),
             }
         }
     }
 
+    // ────────────────
+    // EVENTOS
+    // ────────────────
+
+    /// Se emite cuando un usuario se registra en el marketplace.
+    #[ink(event)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct UsuarioRegistrado {
+        /// Dirección del usuario registrado.
+        #[ink(topic)]
+        pub usuario: AccountId,
+        /// Rol con el que se registró.
+        pub rol: RolUsuario,
+    }
+
+    /// Se emite cuando un vendedor publica un producto.
+    ///
+    /// Incluye todos los datos del producto para que pueda reconstruirse sin consultar al contrato.
+    #[ink(event)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ProductoPublicado {
+        /// Identificador del producto.
+        #[ink(topic)]
+        pub producto_id: u32,
+        /// Dirección del vendedor.
+        #[ink(topic)]
+        pub vendedor: AccountId,
+        /// Nombre del producto.
+        pub nombre: String,
+        /// Descripción del producto.
+        pub descripcion: String,
+        /// Precio del producto.
+        pub precio: Balance,
+        /// Cantidad publicada.
+        pub cantidad: u32,
+        /// Categoría del producto.
+        pub categoria: String,
+    }
+
+    /// Se emite cuando un comprador crea una orden.
+    ///
+    /// La orden nace en estado `Pendiente` y descuenta `cantidad` del stock del producto.
+    #[ink(event)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct OrdenCreada {
+        /// Identificador de la orden.
+        #[ink(topic)]
+        pub orden_id: u32,
+        /// Dirección del comprador.
+        #[ink(topic)]
+        pub comprador: AccountId,
+        /// Dirección del vendedor.
+        #[ink(topic)]
+        pub vendedor: AccountId,
+        /// Identificador del producto comprado.
+        pub producto_id: u32,
+        /// Cantidad comprada.
+        pub cantidad: u32,
+    }
+
+    /// Se emite cuando una orden cambia de estado.
+    #[ink(event)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct EstadoOrdenActualizado {
+        /// Identificador de la orden.
+        #[ink(topic)]
+        pub orden_id: u32,
+        /// Estado previo al cambio.
+        pub estado_anterior: EstadoOrden,
+        /// Estado nuevo de la orden.
+        pub estado_nuevo: EstadoOrden,
+    }
+
     // ────────────────
     // ESTRUCTURAS PRINCIPALES
     // ────────────────
@@ -692,9 +989,9 @@ mod marketplace_principal {
             );
 
             assert!(resultado.is_ok());
-            assert_eq!(contrato.productos.len(), 1);
+            assert_eq!(contrato.next_producto_id, 1);
 
-            let producto = &contrato.productos[0];
+            let producto = contrato.productos.get(0).unwrap();
             assert_eq!(producto.nombre, "Celular");
             assert_eq!(producto.precio, 1000);
         }
@@ -782,9 +1079,9 @@ mod marketplace_principal {
 
             assert!(resultado.is_ok());
             let orden_id = resultado.unwrap();
-            assert_eq!(contrato.ordenes.len(), 1);
+            assert_eq!(contrato.next_orden_id, 1);
 
-            let orden = &contrato.ordenes[0];
+            let orden = contrato.ordenes.get(0).unwrap();
             assert_eq!(orden.id, orden_id);
             assert_eq!(orden.cantidad, 2);
             assert_eq!(orden.estado, EstadoOrden::Pendiente);
@@ -963,10 +1260,10 @@ mod marketplace_principal {
 
             assert!(resultado.is_ok());
             let _orden_id = resultado.unwrap();
-            assert_eq!(contrato.ordenes.len(), 1);
+            assert_eq!(contrato.next_orden_id, 1);
 
             // Verifica que el stock se haya descontado correctamente
-            let producto = &contrato.productos[0];
+            let producto = contrato.productos.get(0).unwrap();
             assert_eq!(producto.cantidad, 7); // Debería quedar 7 después de la compra
         }
 
@@ -985,7 +1282,7 @@ mod marketplace_principal {
             let resultado = contrato.marcar_orden_como_enviada(orden_id);
 
             assert!(resultado.is_ok());
-            let orden = &contrato.ordenes[0];
+            let orden = contrato.ordenes.get(0).unwrap();
             assert_eq!(orden.estado, EstadoOrden::Enviada);
         }
 
@@ -1016,7 +1313,7 @@ mod marketplace_principal {
             let resultado = contrato.marcar_como_recibida(orden_id);
 
             assert!(resultado.is_ok());
-            let orden = &contrato.ordenes[0];
+            let orden = contrato.ordenes.get(0).unwrap();
             assert_eq!(orden.estado, EstadoOrden::Recibida);
         }
 
@@ -1050,21 +1347,52 @@ mod marketplace_principal {
             assert!(matches!(resultado, Err(SistemaError::EstadoInvalido)));
         }*/
 
+        // --- Consultas de solo lectura ---
+        #[ink::test]
+        fn consultas_de_productos_y_ordenes_ok() {
+            let mut contrato = setup_contract_con_vendedor();
+
+            // Publica un producto
+            let _ = contrato.publicar_producto(
+                "Monitor".to_string(),
+                "Monitor 24 pulgadas".to_string(),
+                3000,
+                4,
+                "Tecnología".to_string(),
+            );
+
+            // Cambia el caller a un usuario comprador y crea una orden
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let _ = contrato.registrar_usuario(RolUsuario::Comprador);
+            let orden_id = contrato.crear_orden(0, 1).unwrap();
+
+            // Las consultas reflejan el estado almacenado
+            assert_eq!(contrato.listar_productos(0, 10).len(), 1);
+            assert_eq!(contrato.obtener_producto(0).unwrap().cantidad, 3);
+            assert!(contrato.obtener_producto(1).is_none());
+
+            assert_eq!(contrato.listar_ordenes(0, 10).len(), 1);
+            let orden = contrato.obtener_orden(orden_id).unwrap();
+            assert_eq!(orden.comprador, accounts.bob);
+            assert!(contrato.obtener_orden(99).is_none());
+        }
+
         // --- Errores y validaciones ---
         #[ink::test]
         fn acceder_orden_inexistente_falla() {
-            let mut contrato = MarketplacePrincipal::new();
+            let contrato = MarketplacePrincipal::new();
 
-            let resultado = contrato.obtener_orden_mut(999); // ID que no existe
+            let resultado = contrato.buscar_orden(999); // ID que no existe
 
             assert!(matches!(resultado, Err(SistemaError::OrdenNoExiste)));
         }
 
         #[ink::test]
         fn acceder_producto_inexistente_falla() {
-            let mut contrato = MarketplacePrincipal::new();
+            let contrato = MarketplacePrincipal::new();
 
-            let resultado = contrato.obtener_producto_mut(999); // ID que no existe
+            let resultado = contrato.buscar_producto(999); // ID que no existe
 
             assert!(matches!(resultado, Err(SistemaError::ProductosVacios)));
         }
@@ -1264,7 +1592,7 @@ mod marketplace_principal {
             assert!(resultado.is_ok());
 
             // Verifica que el estado cambió a Recibida
-            let orden = &contrato.ordenes[orden_id as usize];
+            let orden = contrato.ordenes.get(orden_id).unwrap();
             assert_eq!(orden.estado, EstadoOrden::Recibida);
         }
 
@@ -1292,12 +1620,317 @@ mod marketplace_principal {
             let orden_id = resultado.unwrap();
 
             // Verifica que la orden está en estado Pendiente
-            let orden = &contrato.ordenes[orden_id as usize];
+            let orden = contrato.ordenes.get(orden_id).unwrap();
             assert_eq!(orden.estado, EstadoOrden::Pendiente);
 
             // Intenta marcar la orden como recibida directamente desde Pendiente (debe fallar)
             let resultado = contrato.marcar_como_recibida(orden_id);
             assert!(matches!(resultado, Err(SistemaError::EstadoInvalido)));
         }
+
+        // --- Eventos ---
+
+        /// Eventos del contrato decodificados, tal como los vería un listener off-chain.
+        #[derive(Debug)]
+        enum Evento {
+            UsuarioRegistrado(UsuarioRegistrado),
+            ProductoPublicado(ProductoPublicado),
+            OrdenCreada(OrdenCreada),
+            EstadoOrdenActualizado(EstadoOrdenActualizado),
+        }
+
+        /// Decodifica un evento según su topic de firma (el primero de la lista).
+        fn decodificar_evento(evento: &test::EmittedEvent) -> Evento {
+            fn es<E: ink::env::Event>(evento: &test::EmittedEvent) -> bool {
+                E::SIGNATURE_TOPIC.map(|firma| firma.to_vec()) == evento.topics.first().cloned()
+            }
+            let mut datos = &evento.data[..];
+            if es::<UsuarioRegistrado>(evento) {
+                Evento::UsuarioRegistrado(Decode::decode(&mut datos).unwrap())
+            } else if es::<ProductoPublicado>(evento) {
+                Evento::ProductoPublicado(Decode::decode(&mut datos).unwrap())
+            } else if es::<OrdenCreada>(evento) {
+                Evento::OrdenCreada(Decode::decode(&mut datos).unwrap())
+            } else if es::<EstadoOrdenActualizado>(evento) {
+                Evento::EstadoOrdenActualizado(Decode::decode(&mut datos).unwrap())
+            } else {
+                panic!("evento desconocido")
+            }
+        }
+
+        fn eventos_emitidos() -> Vec<Evento> {
+            test::recorded_events().map(|e| decodificar_evento(&e)).collect()
+        }
+
+        #[ink::test]
+        fn registrar_usuario_emite_evento_con_rol() {
+            let mut contrato = MarketplacePrincipal::new();
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+
+            contrato.registrar_usuario(RolUsuario::Ambos).unwrap();
+            // Un registro duplicado no emite nada
+            assert!(contrato.registrar_usuario(RolUsuario::Vendedor).is_err());
+
+            let eventos = eventos_emitidos();
+            assert_eq!(eventos.len(), 1);
+            match &eventos[0] {
+                Evento::UsuarioRegistrado(e) => {
+                    assert_eq!(e.usuario, accounts.alice);
+                    assert_eq!(e.rol, RolUsuario::Ambos);
+                }
+                otro => panic!("se esperaba UsuarioRegistrado, llegó {:?}", otro),
+            }
+        }
+
+        #[ink::test]
+        fn crear_orden_emite_evento_con_topics_de_comprador_y_vendedor() {
+            let mut contrato = setup_contract_con_vendedor();
+            let vendedor = AccountId::from([0x01; 32]);
+            contrato.publicar_producto("Mouse".to_string(), "Mouse óptico".to_string(), 300, 5, "Tecnología".to_string()).unwrap();
+
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.registrar_usuario(RolUsuario::Comprador).unwrap();
+            let orden_id = contrato.crear_orden(0, 2).unwrap();
+
+            let emitido = test::recorded_events().last().unwrap();
+            // Topics: firma, orden_id, comprador y vendedor
+            assert_eq!(emitido.topics.len(), 4);
+            assert_eq!(&emitido.topics[2][..], AsRef::<[u8]>::as_ref(&accounts.bob));
+            assert_eq!(&emitido.topics[3][..], AsRef::<[u8]>::as_ref(&vendedor));
+
+            match decodificar_evento(&emitido) {
+                Evento::OrdenCreada(e) => {
+                    assert_eq!(e.orden_id, orden_id);
+                    assert_eq!(e.producto_id, 0);
+                    assert_eq!(e.cantidad, 2);
+                }
+                otro => panic!("se esperaba OrdenCreada, llegó {:?}", otro),
+            }
+        }
+
+        #[ink::test]
+        fn actualizar_estado_orden_emite_transicion() {
+            let mut contrato = setup_contract_con_vendedor();
+            let vendedor = AccountId::from([0x01; 32]);
+            contrato.publicar_producto("Mouse".to_string(), "Mouse óptico".to_string(), 300, 5, "Tecnología".to_string()).unwrap();
+
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.registrar_usuario(RolUsuario::Comprador).unwrap();
+            let orden_id = contrato.crear_orden(0, 1).unwrap();
+
+            test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+            contrato.marcar_orden_como_enviada(orden_id).unwrap();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.marcar_como_recibida(orden_id).unwrap();
+
+            let transiciones: Vec<(u32, EstadoOrden, EstadoOrden)> = eventos_emitidos()
+                .into_iter()
+                .filter_map(|e| match e {
+                    Evento::EstadoOrdenActualizado(e) => Some((e.orden_id, e.estado_anterior, e.estado_nuevo)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                transiciones,
+                vec![
+                    (orden_id, EstadoOrden::Pendiente, EstadoOrden::Enviada),
+                    (orden_id, EstadoOrden::Enviada, EstadoOrden::Recibida),
+                ]
+            );
+        }
+
+        #[ink::test]
+        fn estado_del_marketplace_se_reconstruye_desde_eventos() {
+            let mut contrato = MarketplacePrincipal::new();
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Vendedor con dos productos
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contrato.registrar_usuario(RolUsuario::Vendedor).unwrap();
+            contrato.publicar_producto("Silla".to_string(), "Silla de madera".to_string(), 500, 10, "Hogar".to_string()).unwrap();
+            contrato.publicar_producto("Notebook".to_string(), "Notebook 14".to_string(), 9000, 3, "Tecnología".to_string()).unwrap();
+
+            // Dos compradores, uno con la orden completa
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.registrar_usuario(RolUsuario::Comprador).unwrap();
+            let orden_bob = contrato.crear_orden(0, 4).unwrap();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contrato.registrar_usuario(RolUsuario::Ambos).unwrap();
+            contrato.crear_orden(1, 1).unwrap();
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contrato.marcar_orden_como_enviada(orden_bob).unwrap();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.marcar_como_recibida(orden_bob).unwrap();
+
+            // Reconstrucción usando únicamente los eventos
+            let mut usuarios: Vec<Usuario> = Vec::new();
+            let mut productos: Vec<Producto> = Vec::new();
+            let mut ordenes: Vec<Orden> = Vec::new();
+            for evento in eventos_emitidos() {
+                match evento {
+                    Evento::UsuarioRegistrado(e) => usuarios.push(Usuario {
+                        direccion: e.usuario,
+                        rol: e.rol,
+                        reputacion_como_comprador: 0,
+                        reputacion_como_vendedor: 0,
+                    }),
+                    Evento::ProductoPublicado(e) => productos.push(Producto::new(
+                        e.producto_id, e.nombre, e.descripcion, e.precio, e.cantidad, e.categoria, e.vendedor,
+                    )),
+                    Evento::OrdenCreada(e) => {
+                        let producto = productos.iter_mut().find(|p| p.id == e.producto_id).unwrap();
+                        producto.cantidad -= e.cantidad;
+                        ordenes.push(Orden::new(e.orden_id, e.comprador, e.vendedor, e.producto_id, e.cantidad));
+                    }
+                    Evento::EstadoOrdenActualizado(e) => {
+                        let orden = ordenes.iter_mut().find(|o| o.id == e.orden_id).unwrap();
+                        assert_eq!(orden.estado, e.estado_anterior);
+                        orden.estado = e.estado_nuevo;
+                    }
+                }
+            }
+
+            assert_eq!(productos, contrato.listar_productos(0, MAX_POR_PAGINA));
+            assert_eq!(ordenes, contrato.listar_ordenes(0, MAX_POR_PAGINA));
+            assert_eq!(usuarios.len(), 3);
+            for usuario in usuarios {
+                assert_eq!(Some(usuario.clone()), contrato.obtener_usuario(usuario.direccion));
+            }
+        }
+
+        // --- Almacenamiento e índices ---
+
+        /// Cantidad de lecturas de storage hechas hasta ahora sobre el contrato.
+        fn lecturas_de_storage() -> usize {
+            let contrato = test::callee::<ink::env::DefaultEnvironment>();
+            test::get_contract_storage_rw::<ink::env::DefaultEnvironment>(&contrato).0
+        }
+
+        /// Publica `cantidad` productos con el caller actual.
+        fn publicar_varios(contrato: &mut MarketplacePrincipal, cantidad: u32) {
+            for _ in 0..cantidad {
+                contrato.publicar_producto("Producto".to_string(), "Descripción".to_string(), 100, 10, "Otros".to_string()).unwrap();
+            }
+        }
+
+        #[ink::test]
+        fn indices_por_vendedor_y_comprador_ok() {
+            let mut contrato = MarketplacePrincipal::new();
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Dos vendedores con productos intercalados
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contrato.registrar_usuario(RolUsuario::Vendedor).unwrap();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.registrar_usuario(RolUsuario::Ambos).unwrap();
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            publicar_varios(&mut contrato, 1); // id 0
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            publicar_varios(&mut contrato, 1); // id 1
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            publicar_varios(&mut contrato, 1); // id 2
+
+            let ids = |productos: Vec<Producto>| productos.iter().map(|p| p.id).collect::<Vec<u32>>();
+            assert_eq!(ids(contrato.listar_productos_de_vendedor(accounts.alice, 0, 10)), vec![0, 2]);
+            assert_eq!(ids(contrato.listar_productos_de_vendedor(accounts.bob, 0, 10)), vec![1]);
+            assert!(contrato.listar_productos_de_vendedor(accounts.charlie, 0, 10).is_empty());
+            assert_eq!(contrato.cantidad_productos_de_vendedor(accounts.alice), 2);
+
+            // Bob compra a Alice y Charlie compra a Alice y a Bob
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contrato.registrar_usuario(RolUsuario::Comprador).unwrap();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let orden_bob = contrato.crear_orden(0, 1).unwrap();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            let orden_charlie_a_alice = contrato.crear_orden(2, 1).unwrap();
+            let orden_charlie_a_bob = contrato.crear_orden(1, 1).unwrap();
+
+            let ids = |ordenes: Vec<Orden>| ordenes.iter().map(|o| o.id).collect::<Vec<u32>>();
+            assert_eq!(ids(contrato.listar_ordenes_de_comprador(accounts.bob, 0, 10)), vec![orden_bob]);
+            assert_eq!(
+                ids(contrato.listar_ordenes_de_comprador(accounts.charlie, 0, 10)),
+                vec![orden_charlie_a_alice, orden_charlie_a_bob]
+            );
+            assert_eq!(
+                ids(contrato.listar_ordenes_de_vendedor(accounts.alice, 0, 10)),
+                vec![orden_bob, orden_charlie_a_alice]
+            );
+            assert_eq!(ids(contrato.listar_ordenes_de_vendedor(accounts.bob, 0, 10)), vec![orden_charlie_a_bob]);
+            assert_eq!(contrato.cantidad_ordenes_de_comprador(accounts.charlie), 2);
+            assert_eq!(contrato.cantidad_ordenes_de_vendedor(accounts.bob), 1);
+            assert_eq!(contrato.cantidad_ordenes(), 3);
+        }
+
+        #[ink::test]
+        fn indices_paginados() {
+            let mut contrato = setup_contract_con_vendedor();
+            let vendedor = AccountId::from([0x01; 32]);
+            publicar_varios(&mut contrato, 60);
+
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.registrar_usuario(RolUsuario::Comprador).unwrap();
+            for producto_id in 0..60 {
+                contrato.crear_orden(producto_id, 1).unwrap();
+            }
+
+            let ids = |ordenes: Vec<Orden>| ordenes.iter().map(|o| o.id).collect::<Vec<u32>>();
+            assert_eq!(contrato.cantidad_ordenes_de_vendedor(vendedor), 60);
+            assert_eq!(ids(contrato.listar_ordenes_de_vendedor(vendedor, 10, 3)), vec![10, 11, 12]);
+            // El límite se acota a MAX_POR_PAGINA
+            assert_eq!(contrato.listar_ordenes_de_comprador(accounts.bob, 0, 1000).len(), MAX_POR_PAGINA as usize);
+            // La última página se corta en el total
+            assert_eq!(ids(contrato.listar_ordenes_de_comprador(accounts.bob, 58, 10)), vec![58, 59]);
+            assert!(contrato.listar_ordenes_de_comprador(accounts.bob, 60, 10).is_empty());
+            assert!(contrato.listar_ordenes_de_comprador(accounts.bob, u32::MAX, u32::MAX).is_empty());
+
+            // Los listados generales se paginan igual
+            assert_eq!(contrato.cantidad_productos(), 60);
+            assert_eq!(contrato.cantidad_ordenes(), 60);
+            assert_eq!(contrato.listar_productos(0, 1000).len(), MAX_POR_PAGINA as usize);
+            assert_eq!(ids(contrato.listar_ordenes(57, 10)), vec![57, 58, 59]);
+            assert!(contrato.listar_ordenes(u32::MAX, u32::MAX).is_empty());
+        }
+
+        #[ink::test]
+        fn lecturas_de_storage_constantes_al_crecer_el_catalogo() {
+            let mut contrato = setup_contract_con_vendedor();
+            let vendedor = AccountId::from([0x01; 32]);
+            publicar_varios(&mut contrato, 1);
+
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.registrar_usuario(RolUsuario::Comprador).unwrap();
+
+            // Costo de una compra con un solo producto publicado
+            let antes = lecturas_de_storage();
+            contrato.crear_orden(0, 1).unwrap();
+            let lecturas_catalogo_chico = lecturas_de_storage() - antes;
+
+            // El catálogo crece a 101 productos
+            test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+            publicar_varios(&mut contrato, 100);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            // La misma compra lee la misma cantidad de celdas
+            let antes = lecturas_de_storage();
+            contrato.crear_orden(0, 1).unwrap();
+            let lecturas_catalogo_grande = lecturas_de_storage() - antes;
+            assert_eq!(lecturas_catalogo_chico, lecturas_catalogo_grande);
+
+            // También al comprar el último producto y al consultarlo
+            let antes = lecturas_de_storage();
+            contrato.crear_orden(100, 1).unwrap();
+            assert_eq!(lecturas_de_storage() - antes, lecturas_catalogo_chico);
+
+            let antes = lecturas_de_storage();
+            assert!(contrato.obtener_producto(100).is_some());
+            assert_eq!(lecturas_de_storage() - antes, 1);
+        }
     } // <-- cierre del mod tests
 } // <-- cierre del mod marketplace_principal
@@ -1,141 +1,275 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+/// Contrato 2 – ReportesView (solo lectura)
+///
+/// Consulta los datos públicos de `MarketplacePrincipal` mediante llamadas
+/// entre contratos y los agrega en reportes. Este contrato no puede modificar
+/// órdenes, publicar productos ni emitir calificaciones.
 #[ink::contract]
 mod reportes_view {
+    use ink::env::call::FromAccountId;
+    use ink::prelude::collections::BTreeMap;
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use marketplace_principal::{EstadoOrden, MarketplacePrincipalRef, Orden, Producto};
 
-    /// Defines the storage of your contract.
-    /// Add new fields to the below struct in order
-    /// to add new static storage fields to your contract.
+    /// Estructura principal del contrato de reportes.
     #[ink(storage)]
     pub struct ReportesView {
-        /// Stores a single `bool` value on the storage.
-        value: bool,
+        /// Referencia al contrato `MarketplacePrincipal` del que se leen los datos.
+        marketplace: MarketplacePrincipalRef,
     }
 
     impl ReportesView {
-        /// Constructor that initializes the `bool` value to the given `init_value`.
+        /// Crea el contrato de reportes apuntando a un `MarketplacePrincipal` ya desplegado.
         #[ink(constructor)]
-        pub fn new(init_value: bool) -> Self {
-            Self { value: init_value }
+        pub fn new(marketplace: AccountId) -> Self {
+            Self {
+                marketplace: MarketplacePrincipalRef::from_account_id(marketplace),
+            }
         }
 
-        /// Constructor that initializes the `bool` value to `false`.
+        /// Devuelve la dirección del contrato `MarketplacePrincipal` consultado.
+        #[ink(message)]
+        pub fn obtener_marketplace(&self) -> AccountId {
+            ink::ToAccountId::to_account_id(&self.marketplace)
+        }
+
+        /// Vendedores con más órdenes completadas (en estado `Recibida`) dentro de una página de órdenes.
         ///
-        /// Constructors can delegate to other constructors.
-        #[ink(constructor)]
-        pub fn default() -> Self {
-            Self::new(Default::default())
+        /// Los reportes que recorren órdenes leen una sola página por llamada: el que consulta
+        /// pide las páginas hasta `cantidad_ordenes` del marketplace y suma los resultados.
+        ///
+        /// # Parámetros
+        /// - `desde`: id de la primera orden de la página.
+        /// - `limite`: cantidad máxima de órdenes, acotada por el marketplace.
+        ///
+        /// # Retorna
+        /// Pares `(vendedor, ordenes_completadas)` de la página, ordenados de mayor a menor.
+        #[ink(message)]
+        pub fn top_vendedores(&self, desde: u32, limite: u32) -> Vec<(AccountId, u32)> {
+            top_vendedores_por_ordenes_completadas(&self.marketplace.listar_ordenes(desde, limite))
+        }
+
+        /// Compradores con más órdenes realizadas dentro de una página de órdenes.
+        ///
+        /// # Retorna
+        /// Pares `(comprador, cantidad_de_ordenes)` de la página, ordenados de mayor a menor.
+        /// Las órdenes canceladas no se cuentan.
+        #[ink(message)]
+        pub fn top_compradores(&self, desde: u32, limite: u32) -> Vec<(AccountId, u32)> {
+            top_compradores_por_cantidad_de_ordenes(&self.marketplace.listar_ordenes(desde, limite))
+        }
+
+        /// Unidades vendidas agrupadas por la categoría del producto, dentro de una página de órdenes.
+        ///
+        /// # Retorna
+        /// Pares `(categoria, unidades)` ordenados alfabéticamente por categoría.
+        /// Las órdenes canceladas no se cuentan.
+        #[ink(message)]
+        pub fn unidades_vendidas_por_categoria(&self, desde: u32, limite: u32) -> Vec<(String, u32)> {
+            let ordenes = self.marketplace.listar_ordenes(desde, limite);
+            // Sólo se consultan los productos que aparecen en la página
+            let mut productos: Vec<Producto> = Vec::new();
+            for orden in ordenes.iter() {
+                if !productos.iter().any(|p| p.id == orden.producto_id) {
+                    if let Some(producto) = self.marketplace.obtener_producto(orden.producto_id) {
+                        productos.push(producto);
+                    }
+                }
+            }
+            unidades_por_categoria(&productos, &ordenes)
         }
 
-        /// A message that can be called on instantiated contracts.
-        /// This one flips the value of the stored `bool` from `true`
-        /// to `false` and vice versa.
+        /// Cantidad de órdenes en cada `EstadoOrden`, dentro de una página de órdenes.
+        ///
+        /// # Retorna
+        /// Un par `(estado, cantidad)` por cada estado posible, incluso si la cantidad es 0.
         #[ink(message)]
-        pub fn flip(&mut self) {
-            self.value = !self.value;
+        pub fn ordenes_por_estado(&self, desde: u32, limite: u32) -> Vec<(EstadoOrden, u32)> {
+            contar_ordenes_por_estado(&self.marketplace.listar_ordenes(desde, limite))
         }
 
-        /// Simply returns the current value of our `bool`.
+        /// Cantidad de órdenes en las que participa un usuario, como comprador o como vendedor.
+        ///
+        /// Se lee de los contadores por cuenta del marketplace, sin recorrer órdenes.
         #[ink(message)]
-        pub fn get(&self) -> bool {
-            self.value
+        pub fn ordenes_de_usuario(&self, usuario: AccountId) -> u32 {
+            self.marketplace
+                .cantidad_ordenes_de_comprador(usuario)
+                .saturating_add(self.marketplace.cantidad_ordenes_de_vendedor(usuario))
+        }
+    }
+
+    // ────────────────
+    // CÁLCULO DE REPORTES
+    // ────────────────
+
+    /// Ordena los conteos de mayor a menor, desempatando por dirección.
+    fn ranking(conteos: BTreeMap<AccountId, u32>) -> Vec<(AccountId, u32)> {
+        let mut ranking: Vec<(AccountId, u32)> = conteos.into_iter().collect();
+        ranking.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranking
+    }
+
+    /// Calcula el top de vendedores según sus órdenes recibidas.
+    fn top_vendedores_por_ordenes_completadas(ordenes: &[Orden]) -> Vec<(AccountId, u32)> {
+        let mut conteos = BTreeMap::new();
+        for orden in ordenes.iter().filter(|o| o.estado == EstadoOrden::Recibida) {
+            *conteos.entry(orden.vendedor).or_insert(0u32) += 1;
+        }
+        ranking(conteos)
+    }
+
+    /// Calcula el top de compradores según la cantidad de órdenes no canceladas.
+    fn top_compradores_por_cantidad_de_ordenes(ordenes: &[Orden]) -> Vec<(AccountId, u32)> {
+        let mut conteos = BTreeMap::new();
+        for orden in ordenes.iter().filter(|o| o.estado != EstadoOrden::Cancelada) {
+            *conteos.entry(orden.comprador).or_insert(0u32) += 1;
         }
+        ranking(conteos)
+    }
+
+    /// Suma las unidades de las órdenes no canceladas según la categoría de su producto.
+    fn unidades_por_categoria(productos: &[Producto], ordenes: &[Orden]) -> Vec<(String, u32)> {
+        let mut unidades: BTreeMap<String, u32> = BTreeMap::new();
+        for orden in ordenes.iter().filter(|o| o.estado != EstadoOrden::Cancelada) {
+            if let Some(producto) = productos.iter().find(|p| p.id == orden.producto_id) {
+                let total = unidades.entry(producto.categoria.clone()).or_insert(0);
+                *total = total.saturating_add(orden.cantidad);
+            }
+        }
+        unidades.into_iter().collect()
+    }
+
+    /// Cuenta las órdenes de cada estado.
+    fn contar_ordenes_por_estado(ordenes: &[Orden]) -> Vec<(EstadoOrden, u32)> {
+        [
+            EstadoOrden::Pendiente,
+            EstadoOrden::Enviada,
+            EstadoOrden::Recibida,
+            EstadoOrden::Cancelada,
+        ]
+        .into_iter()
+        .map(|estado| {
+            let cantidad = ordenes.iter().filter(|o| o.estado == estado).count() as u32;
+            (estado, cantidad)
+        })
+        .collect()
     }
 
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
     #[cfg(test)]
     mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
 
-        /// We test if the default constructor does its job.
+        fn cuenta(n: u8) -> AccountId {
+            AccountId::from([n; 32])
+        }
+
+        fn producto(id: u32, categoria: &str, vendedor: AccountId) -> Producto {
+            Producto::new(
+                id,
+                "Producto".to_string(),
+                "Descripción".to_string(),
+                100,
+                50,
+                categoria.to_string(),
+                vendedor,
+            )
+        }
+
+        fn orden(id: u32, comprador: AccountId, vendedor: AccountId, producto_id: u32, cantidad: u32, estado: EstadoOrden) -> Orden {
+            let mut orden = Orden::new(id, comprador, vendedor, producto_id, cantidad);
+            orden.estado = estado;
+            orden
+        }
+
         #[ink::test]
-        fn default_works() {
-            let reportes_view = ReportesView::default();
-            assert_eq!(reportes_view.get(), false);
+        fn new_guarda_direccion_del_marketplace() {
+            let reportes = ReportesView::new(cuenta(0xAA));
+            assert_eq!(reportes.obtener_marketplace(), cuenta(0xAA));
         }
 
-        /// We test a simple use case of our contract.
         #[ink::test]
-        fn it_works() {
-            let mut reportes_view = ReportesView::new(false);
-            assert_eq!(reportes_view.get(), false);
-            reportes_view.flip();
-            assert_eq!(reportes_view.get(), true);
+        fn top_vendedores_cuenta_solo_ordenes_recibidas() {
+            let ordenes = vec![
+                orden(0, cuenta(10), cuenta(1), 0, 1, EstadoOrden::Recibida),
+                orden(1, cuenta(10), cuenta(1), 0, 1, EstadoOrden::Recibida),
+                orden(2, cuenta(10), cuenta(2), 1, 1, EstadoOrden::Recibida),
+                orden(3, cuenta(10), cuenta(2), 1, 1, EstadoOrden::Enviada),
+                orden(4, cuenta(10), cuenta(3), 2, 1, EstadoOrden::Pendiente),
+            ];
+
+            let top = top_vendedores_por_ordenes_completadas(&ordenes);
+            assert_eq!(top, vec![(cuenta(1), 2), (cuenta(2), 1)]);
         }
-    }
 
+        #[ink::test]
+        fn top_vendedores_desempata_por_direccion() {
+            let ordenes: Vec<Orden> = (1..=7u8)
+                .rev()
+                .map(|n| orden(n as u32, cuenta(99), cuenta(n), 0, 1, EstadoOrden::Recibida))
+                .collect();
 
-    /// This is how you'd write end-to-end (E2E) or integration tests for ink! contracts.
-    ///
-    /// When running these you need to make sure that you:
-    /// - Compile the tests with the `e2e-tests` feature flag enabled (`--features e2e-tests`)
-    /// - Are running a Substrate node which contains `pallet-contracts` in the background
-    #[cfg(all(test, feature = "e2e-tests"))]
-    mod e2e_tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
+            let top = top_vendedores_por_ordenes_completadas(&ordenes);
+            assert_eq!(top.len(), 7);
+            // Con el mismo conteo se desempata por dirección
+            assert_eq!(top[0], (cuenta(1), 1));
+            assert_eq!(top[6], (cuenta(7), 1));
+        }
+
+        #[ink::test]
+        fn top_compradores_ignora_canceladas() {
+            let ordenes = vec![
+                orden(0, cuenta(10), cuenta(1), 0, 1, EstadoOrden::Pendiente),
+                orden(1, cuenta(11), cuenta(1), 0, 1, EstadoOrden::Enviada),
+                orden(2, cuenta(11), cuenta(1), 0, 1, EstadoOrden::Recibida),
+                orden(3, cuenta(12), cuenta(1), 0, 1, EstadoOrden::Cancelada),
+            ];
+
+            let top = top_compradores_por_cantidad_de_ordenes(&ordenes);
+            assert_eq!(top, vec![(cuenta(11), 2), (cuenta(10), 1)]);
+        }
+
+        #[ink::test]
+        fn unidades_por_categoria_agrupa_por_producto() {
+            let productos = vec![
+                producto(0, "Tecnología", cuenta(1)),
+                producto(1, "Hogar", cuenta(1)),
+                producto(2, "Tecnología", cuenta(2)),
+            ];
+            let ordenes = vec![
+                orden(0, cuenta(10), cuenta(1), 0, 3, EstadoOrden::Recibida),
+                orden(1, cuenta(10), cuenta(1), 1, 2, EstadoOrden::Pendiente),
+                orden(2, cuenta(11), cuenta(2), 2, 4, EstadoOrden::Enviada),
+                orden(3, cuenta(11), cuenta(2), 2, 9, EstadoOrden::Cancelada),
+            ];
+
+            let unidades = unidades_por_categoria(&productos, &ordenes);
+            assert_eq!(
+                unidades,
+                vec![("Hogar".to_string(), 2), ("Tecnología".to_string(), 7)]
+            );
+        }
+
+        #[ink::test]
+        fn ordenes_por_estado_incluye_todos_los_estados() {
+            let ordenes = vec![
+                orden(0, cuenta(10), cuenta(1), 0, 1, EstadoOrden::Pendiente),
+                orden(1, cuenta(10), cuenta(1), 0, 1, EstadoOrden::Pendiente),
+                orden(2, cuenta(10), cuenta(1), 0, 1, EstadoOrden::Recibida),
+            ];
 
-        /// A helper function used for calling contract messages.
-        use ink_e2e::ContractsBackend;
-
-        /// The End-to-End test `Result` type.
-        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
-
-        /// We test that we can upload and instantiate the contract using its default constructor.
-        #[ink_e2e::test]
-        async fn default_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Given
-            let mut constructor = ReportesViewRef::default();
-
-            // When
-            let contract = client
-                .instantiate("reportes_view", &ink_e2e::alice(), &mut constructor)
-                .submit()
-                .await
-                .expect("instantiate failed");
-            let call_builder = contract.call_builder::<ReportesView>();
-
-            // Then
-            let get = call_builder.get();
-            let get_result = client.call(&ink_e2e::alice(), &get).dry_run().await?;
-            assert!(matches!(get_result.return_value(), false));
-
-            Ok(())
-        }
-
-        /// We test that we can read and write a value from the on-chain contract.
-        #[ink_e2e::test]
-        async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Given
-            let mut constructor = ReportesViewRef::new(false);
-            let contract = client
-                .instantiate("reportes_view", &ink_e2e::bob(), &mut constructor)
-                .submit()
-                .await
-                .expect("instantiate failed");
-            let mut call_builder = contract.call_builder::<ReportesView>();
-
-            let get = call_builder.get();
-            let get_result = client.call(&ink_e2e::bob(), &get).dry_run().await?;
-            assert!(matches!(get_result.return_value(), false));
-
-            // When
-            let flip = call_builder.flip();
-            let _flip_result = client
-                .call(&ink_e2e::bob(), &flip)
-                .submit()
-                .await
-                .expect("flip failed");
-
-            // Then
-            let get = call_builder.get();
-            let get_result = client.call(&ink_e2e::bob(), &get).dry_run().await?;
-            assert!(matches!(get_result.return_value(), true));
-
-            Ok(())
+            let conteo = contar_ordenes_por_estado(&ordenes);
+            assert_eq!(
+                conteo,
+                vec![
+                    (EstadoOrden::Pendiente, 2),
+                    (EstadoOrden::Enviada, 0),
+                    (EstadoOrden::Recibida, 1),
+                    (EstadoOrden::Cancelada, 0),
+                ]
+            );
         }
     }
 }
@@ -0,0 +1,889 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Lógica común del marketplace descentralizado.
+//!
+//! Define las estructuras del dominio (usuarios, productos, publicaciones, órdenes y categorías)
+//! y los traits `Gestion*` que describen qué puede hacer un marketplace, sin atarse a dónde se guardan
+//! los datos. El contrato `Sistema` los implementa sobre el storage de ink! y, con la feature `std`,
+//! `SistemaEnMemoria` los implementa sobre `HashMap` para simular escenarios fuera de la cadena.
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+pub use ink::primitives::AccountId;
+#[cfg(feature = "std")]
+use ink::storage::traits::StorageLayout;
+
+#[cfg(feature = "std")]
+mod memoria;
+#[cfg(feature = "std")]
+pub use memoria::SistemaEnMemoria;
+
+#[cfg(any(test, feature = "pruebas"))]
+pub mod pruebas;
+
+/// Tipo de los montos, igual al `Balance` del entorno por defecto de ink!.
+pub type Balance = u128;
+
+/// Marca de tiempo en milisegundos, igual al `Timestamp` del entorno por defecto de ink!.
+pub type Timestamp = u64;
+
+/// Tiempo que tiene la contraparte para confirmar una cancelación (7 días en milisegundos).
+/// Pasado ese plazo la solicitud vence y cualquiera de las partes puede volver a pedirla.
+pub const PLAZO_CANCELACION: Timestamp = 7 * 24 * 60 * 60 * 1000;
+
+/// Hash Blake2x256 de un mail normalizado; es la clave del índice de mails.
+pub type HashMail = [u8; 32];
+
+/// Cantidad máxima de atributos que puede declarar una categoría, sin contar los heredados.
+pub const MAX_ATRIBUTOS_POR_CATEGORIA: usize = 10;
+
+pub const COMPRADOR: Rol = Rol::Comprador;
+pub const VENDEDOR: Rol = Rol::Vendedor;
+
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[derive(Debug, PartialEq)]
+
+pub enum ErroresContrato {
+    UsuarioSinRoles,
+    UsuarioYaExistente,
+    UsuarioNoEsComprador,
+    UsuarioYaEsComprador,
+    UsuarioYaEsVendedor,
+    UsuarioNoEsVendedor,
+    UsuarioNoExiste,
+    UsuarioNoTieneRol,
+    OrdenNoPendiente,
+    OrdenNoEnviada,
+    OrdenYaCancelada,
+    OrdenInexistente,
+    StockPublicacionInsuficiente,
+    StockProductoInsuficiente,
+    StockInsuficiente,
+    CuentaNoRegistrada,
+    MailYaExistente,
+    MailInexistente,
+    ProductoInexistente,
+    ProductoYaExistente,
+    PublicacionNoExiste,
+    CategoriaYaExistente,
+    CategoriaInexistente,
+    ErrorMultiplicacion,
+    RolNoApropiado,
+    AccountIdInvalida,
+    IndiceInvalido,
+    AlreadyHasRol,
+    CantidadEnCarritoMenorAUno,
+    NombreCategoriaVacio,
+    MaxCategoriasAlcanzado,
+    ListaSinProductos,
+    OrdenYaRecibida,
+    UsuarioNoParticipaDeLaOrden,
+    CancelacionYaSolicitada,
+    StockDesbordado,
+    CategoriaPadreInexistente,
+    DemasiadosAtributos,
+    AtributoDuplicado,
+    AtributoFaltante,
+    AtributoDesconocido,
+    TipoDeAtributoInvalido,
+    NoEsAdmin,
+    NoEsVerificador,
+    MailNoCoincide,
+    CompradorNoVerificado,
+}
+
+/// Cada trait declara su propio tipo de error, así cada implementación puede usar el que le sirva.
+/// Tanto `Sistema` como `SistemaEnMemoria` usan `ErroresContrato`.
+pub trait GestionProducto {
+    type Error;
+
+    fn _crear_producto(
+        &mut self,
+        id_vendedor: AccountId,
+        nombre: String,
+        descripcion: String,
+        categoria: String,
+        stock: u32,
+    ) -> Result<(), Self::Error> {
+        self._crear_producto_con_atributos(
+            id_vendedor,
+            nombre,
+            descripcion,
+            categoria,
+            stock,
+            Vec::new(),
+        )
+    }
+
+    /// Crea un producto completando los atributos que exige su categoría (incluidos los que
+    /// hereda de sus categorías padre).
+    fn _crear_producto_con_atributos(
+        &mut self,
+        id_vendedor: AccountId,
+        nombre: String,
+        descripcion: String,
+        categoria: String,
+        stock: u32,
+        atributos: Vec<Atributo>,
+    ) -> Result<(), Self::Error>;
+
+    /// Devuelve los productos de la categoría y de todas sus subcategorías.
+    fn _listar_productos_por_categoria(
+        &self,
+        categoria: &str,
+    ) -> Result<Vec<Producto>, Self::Error>;
+
+    fn descontar_stock_producto(&mut self, id: u32, cantidad: u32) -> Result<(), Self::Error>;
+
+    fn producto_existe(&self, p: &Producto) -> bool;
+
+    fn _listar_productos(&self) -> Vec<Producto>;
+}
+
+pub trait GestionUsuario {
+    type Error;
+
+    fn _registrar_usuario(
+        &mut self,
+        id: AccountId,
+        nombre: String,
+        mail: String,
+    ) -> Result<String, Self::Error>;
+
+    fn get_user(&mut self, id: &AccountId) -> Result<Usuario, Self::Error>;
+
+    fn _listar_usuarios(&self) -> Vec<Usuario>;
+
+    fn get_usuario_by_mail(&self, mail: &str) -> Result<Usuario, Self::Error>;
+
+    fn _asignar_rol(&mut self, id: AccountId, rol: Rol) -> Result<String, Self::Error>;
+
+    /// Marca el mail del usuario como verificado, si `hash_mail` es el de su mail registrado.
+    /// Quién puede dar fe de eso lo decide cada implementación.
+    fn _verificar_mail(&mut self, id: AccountId, hash_mail: HashMail) -> Result<(), Self::Error>;
+
+    /// Permite a un vendedor exigir (o dejar de exigir) compradores con mail verificado.
+    fn _exigir_compradores_verificados(
+        &mut self,
+        id: AccountId,
+        exigir: bool,
+    ) -> Result<(), Self::Error>;
+}
+
+pub trait GestionOrden {
+    type Error;
+
+    fn _crear_orden(
+        &mut self,
+        id_pub: u32,
+        id_comprador: AccountId,
+        cantidad: u32,
+    ) -> Result<(), Self::Error>;
+
+    fn _listar_ordenes(&self) -> Vec<Orden>;
+
+    fn _enviar_orden(&mut self, id_orden: u32) -> Result<(), Self::Error>;
+
+    fn _recibir_orden(&mut self, id_orden: u32) -> Result<(), Self::Error>;
+
+    /// Solicita o confirma la cancelación de una orden en nombre de `id_usuario`.
+    /// Devuelve el estado en el que queda la orden: `Cancelada` si la contraparte ya lo había
+    /// pedido dentro del plazo (y el stock vuelve a la publicación), o el mismo
+    /// estado que tenía si recién se registra la solicitud.
+    fn _cancelar_orden(
+        &mut self,
+        id_orden: u32,
+        id_usuario: AccountId,
+        ahora: Timestamp,
+    ) -> Result<EstadoOrden, Self::Error>;
+}
+
+pub trait GestionPublicacion {
+    type Error;
+
+    fn _crear_publicacion(
+        &mut self,
+        id_producto: u32,
+        id_usuario: AccountId,
+        stock: u32,
+        precio: Balance,
+    ) -> Result<(), Self::Error>;
+
+    fn descontar_stock_publicacion(
+        &mut self,
+        id_pub: u32,
+        cantidad: u32,
+    ) -> Result<(), Self::Error>;
+
+    fn reponer_stock_publicacion(&mut self, id_pub: u32, cantidad: u32) -> Result<(), Self::Error>;
+
+    fn get_precio_unitario(&self, id_pub: u32) -> Result<Balance, Self::Error>;
+
+    fn get_id_vendedor(&self, id_pub: u32) -> Result<AccountId, Self::Error>;
+
+    fn _listar_publicaciones(&self) -> Vec<Publicacion>;
+}
+
+pub trait GestionCategoria {
+    type Error;
+
+    /// Registra una categoría raíz sin atributos.
+    fn _registrar_categoria(&mut self, nombre: String) -> Result<String, Self::Error> {
+        self._registrar_subcategoria(nombre, None, Vec::new())
+    }
+
+    /// Registra una categoría debajo de `padre` (o como raíz si es `None`), declarando los
+    /// atributos que deben completar sus productos.
+    fn _registrar_subcategoria(
+        &mut self,
+        nombre: String,
+        padre: Option<String>,
+        atributos: Vec<DefinicionAtributo>,
+    ) -> Result<String, Self::Error>;
+
+    fn _listar_categorias(&self) -> Vec<Categoria>;
+
+    fn get_categoria(&self, id: u32) -> Result<Categoria, Self::Error>;
+
+    /// Devuelve los atributos que exige la categoría: los propios y los de sus ancestros.
+    fn esquema_de_atributos(&self, id: u32) -> Result<Vec<DefinicionAtributo>, Self::Error> {
+        let mut esquema = Vec::new();
+        let mut actual = Some(id);
+        while let Some(id_cat) = actual {
+            let categoria = self.get_categoria(id_cat)?;
+            esquema.extend(categoria.atributos);
+            actual = categoria.padre;
+        }
+        Ok(esquema)
+    }
+
+    /// Devuelve el id de la categoría junto con los de todas sus descendientes.
+    fn categorias_descendientes(&self, id: u32) -> Vec<u32> {
+        let categorias = self._listar_categorias();
+        let mut resultado = Vec::from([id]);
+        let mut i = 0;
+        while let Some(&actual) = resultado.get(i) {
+            for categoria in &categorias {
+                if categoria.padre == Some(actual) {
+                    resultado.push(categoria.id);
+                }
+            }
+            i += 1;
+        }
+        resultado
+    }
+
+    fn get_categoria_by_name(&self, nombre: &str) -> Result<u32, Self::Error>;
+
+    fn clean_cat_name(&self, nombre: &str) -> Result<String, Self::Error>;
+}
+
+/// Normaliza el nombre de una categoría: minúsculas, sin espacios en los extremos y hasta 100 caracteres.
+/// Es la regla que usan todas las implementaciones de `GestionCategoria::clean_cat_name`.
+pub fn limpiar_nombre_categoria(nombre: &str) -> Result<String, ErroresContrato> {
+    let mut limpio = String::from(nombre.to_lowercase().trim());
+    limpio.truncate(100);
+    if !limpio.is_empty() {
+        Ok(limpio)
+    } else {
+        Err(ErroresContrato::NombreCategoriaVacio)
+    }
+}
+
+/// Normaliza un mail para compararlo: sin espacios en los extremos y en minúsculas.
+pub fn normalizar_mail(mail: &str) -> String {
+    mail.trim().to_lowercase()
+}
+
+/// Devuelve el hash del mail normalizado, así `Ana@Mail.com` y `ana@mail.com` son el mismo mail.
+pub fn hash_mail(mail: &str) -> HashMail {
+    let mut hash = HashMail::default();
+    ink::env::hash_bytes::<ink::env::hash::Blake2x256>(normalizar_mail(mail).as_bytes(), &mut hash);
+    hash
+}
+
+/// Verifica que los atributos de la nueva categoría no se repitan entre sí ni con los que hereda.
+pub fn validar_definiciones(
+    heredados: &[DefinicionAtributo],
+    nuevos: &[DefinicionAtributo],
+) -> Result<(), ErroresContrato> {
+    if nuevos.len() > MAX_ATRIBUTOS_POR_CATEGORIA {
+        return Err(ErroresContrato::DemasiadosAtributos);
+    }
+    for (i, definicion) in nuevos.iter().enumerate() {
+        let repetido = heredados
+            .iter()
+            .chain(&nuevos[..i])
+            .any(|otra| otra.nombre == definicion.nombre);
+        if repetido {
+            return Err(ErroresContrato::AtributoDuplicado);
+        }
+    }
+    Ok(())
+}
+
+/// Verifica que `valores` complete exactamente el esquema de una categoría: cada atributo una
+/// sola vez, con el tipo declarado y sin atributos que la categoría no conozca.
+pub fn validar_atributos(
+    esquema: &[DefinicionAtributo],
+    valores: &[Atributo],
+) -> Result<(), ErroresContrato> {
+    for (i, atributo) in valores.iter().enumerate() {
+        if valores[..i]
+            .iter()
+            .any(|otro| otro.nombre == atributo.nombre)
+        {
+            return Err(ErroresContrato::AtributoDuplicado);
+        }
+        let definicion = esquema
+            .iter()
+            .find(|definicion| definicion.nombre == atributo.nombre)
+            .ok_or(ErroresContrato::AtributoDesconocido)?;
+        if definicion.tipo != atributo.valor.tipo() {
+            return Err(ErroresContrato::TipoDeAtributoInvalido);
+        }
+    }
+    if esquema
+        .iter()
+        .any(|definicion| !valores.iter().any(|a| a.nombre == definicion.nombre))
+    {
+        return Err(ErroresContrato::AtributoFaltante);
+    }
+    Ok(())
+}
+
+// Estructuras relacionadas a Usuario
+
+/// Roles existentes
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[derive(PartialEq, Clone)]
+pub enum Rol {
+    Comprador,
+    Vendedor,
+}
+
+/// Estructura que define al Usuario
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[derive(Clone)]
+pub struct Usuario {
+    id: AccountId,
+    nombre: String,
+    mail: String,
+    rating: Rating,
+    roles: Vec<Rol>,
+    verificado: bool,
+    exige_verificados: bool, //como vendedor, solo acepta compradores verificados
+}
+
+impl Usuario {
+    ///Crea un nuevo Usuario
+    pub fn new(id: AccountId, nombre: String, mail: String) -> Usuario {
+        Usuario {
+            id,
+            nombre,
+            mail,
+            rating: Rating::new(),
+            roles: Vec::new(),
+            verificado: false,
+            exige_verificados: false,
+        }
+    }
+
+    /// Devuelve true si el usuario contiene el rol pasado por parametro
+    pub fn has_role(&self, rol: Rol) -> bool {
+        self.roles.contains(&rol)
+    }
+
+    /// Agrega el rol al usuario, o devuelve `AlreadyHasRol` si ya lo tenía
+    pub fn agregar_rol(&mut self, rol: Rol) -> Result<(), ErroresContrato> {
+        if self.has_role(rol.clone()) {
+            return Err(ErroresContrato::AlreadyHasRol);
+        }
+        self.roles.push(rol);
+        Ok(())
+    }
+
+    /// Devuelve el nombre del usuario
+    pub fn get_name(&self) -> String {
+        self.nombre.clone()
+    }
+
+    /// Devuelve el email del usuario
+    pub fn get_mail(&self) -> String {
+        self.mail.clone()
+    }
+
+    /// Devuelve el AccountId del usuario
+    pub fn get_id(&self) -> AccountId {
+        self.id
+    }
+
+    /// Devuelve true si un verificador dio fe de que el mail pertenece al usuario
+    pub fn esta_verificado(&self) -> bool {
+        self.verificado
+    }
+
+    /// Marca el mail del usuario como verificado
+    pub fn marcar_verificado(&mut self) {
+        self.verificado = true;
+    }
+
+    /// Devuelve true si, como vendedor, solo acepta ordenes de compradores verificados
+    pub fn exige_verificados(&self) -> bool {
+        self.exige_verificados
+    }
+
+    /// Cambia la exigencia de compradores verificados; solo la puede tener un vendedor
+    pub fn set_exige_verificados(&mut self, exigir: bool) -> Result<(), ErroresContrato> {
+        if !self.has_role(Rol::Vendedor) {
+            return Err(ErroresContrato::UsuarioNoEsVendedor);
+        }
+        self.exige_verificados = exigir;
+        Ok(())
+    }
+
+    /// Verifica que el usuario pueda comprarle al vendedor dado
+    pub fn puede_comprarle_a(&self, vendedor: &Usuario) -> Result<(), ErroresContrato> {
+        if vendedor.exige_verificados && !self.verificado {
+            return Err(ErroresContrato::CompradorNoVerificado);
+        }
+        Ok(())
+    }
+}
+
+/// Estructura correspondiente al rating de un usuario
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[derive(Clone)]
+struct Rating {
+    calificacion_comprador: (u32, u32), //cant de compras, valor cumulativo de todas las calificaciones
+    calificacion_vendedor: (u32, u32),
+}
+
+///Métodos de usuario
+impl Rating {
+    ///crea un rating
+    fn new() -> Rating {
+        Rating {
+            calificacion_comprador: (0, 0),
+            calificacion_vendedor: (0, 0),
+        }
+    }
+}
+
+// Estructuras relacionadas a producto
+
+/// Categorias
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[derive(Clone)]
+pub struct Categoria {
+    id: u32,
+    nombre: String,
+    padre: Option<u32>,
+    atributos: Vec<DefinicionAtributo>,
+}
+
+impl Categoria {
+    pub fn new(id: u32, nombre: String) -> Self {
+        Self {
+            id,
+            nombre,
+            padre: None,
+            atributos: Vec::new(),
+        }
+    }
+
+    /// Ubica la categoria debajo de `padre`
+    pub fn con_padre(mut self, padre: Option<u32>) -> Self {
+        self.padre = padre;
+        self
+    }
+
+    /// Declara los atributos que deben completar los productos de la categoria
+    pub fn con_atributos(mut self, atributos: Vec<DefinicionAtributo>) -> Self {
+        self.atributos = atributos;
+        self
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    /// Devuelve el id de la categoria padre, o `None` si es una categoria raiz
+    pub fn get_padre(&self) -> Option<u32> {
+        self.padre
+    }
+
+    /// Devuelve los atributos declarados por la categoria (sin los heredados)
+    pub fn get_atributos(&self) -> &[DefinicionAtributo] {
+        &self.atributos
+    }
+
+    /// Devuelve el nombre (ya normalizado) de la categoria
+    pub fn get_nombre(&self) -> &str {
+        &self.nombre
+    }
+}
+
+/// Tipos de valor que puede tener un atributo
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum TipoAtributo {
+    Texto,
+    Numero,
+    Booleano,
+}
+
+/// Atributo que una categoria exige a sus productos, por ejemplo marca (Texto) o talle (Numero)
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct DefinicionAtributo {
+    nombre: String,
+    tipo: TipoAtributo,
+}
+
+impl DefinicionAtributo {
+    pub fn new(nombre: String, tipo: TipoAtributo) -> Self {
+        Self { nombre, tipo }
+    }
+
+    pub fn get_nombre(&self) -> &str {
+        &self.nombre
+    }
+
+    pub fn get_tipo(&self) -> TipoAtributo {
+        self.tipo
+    }
+}
+
+/// Valor que un producto le da a un atributo
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum ValorAtributo {
+    Texto(String),
+    Numero(i64),
+    Booleano(bool),
+}
+
+impl ValorAtributo {
+    pub fn tipo(&self) -> TipoAtributo {
+        match self {
+            ValorAtributo::Texto(_) => TipoAtributo::Texto,
+            ValorAtributo::Numero(_) => TipoAtributo::Numero,
+            ValorAtributo::Booleano(_) => TipoAtributo::Booleano,
+        }
+    }
+}
+
+/// Atributo completado en un producto
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct Atributo {
+    nombre: String,
+    valor: ValorAtributo,
+}
+
+impl Atributo {
+    pub fn new(nombre: String, valor: ValorAtributo) -> Self {
+        Self { nombre, valor }
+    }
+
+    pub fn get_nombre(&self) -> &str {
+        &self.nombre
+    }
+
+    pub fn get_valor(&self) -> &ValorAtributo {
+        &self.valor
+    }
+}
+
+///Estructura de un producto
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[derive(PartialEq, Debug, Clone)]
+pub struct Producto {
+    id: u32,
+    id_vendedor: AccountId,
+    nombre: String,
+    descripcion: String,
+    categoria: u32,
+    stock: u32,
+    atributos: Vec<Atributo>,
+}
+
+impl Producto {
+    ///Crea un producto nuevo dado los parametros
+    pub fn new(
+        id: u32,
+        id_vendedor: AccountId,
+        nombre: String,
+        descripcion: String,
+        categoria: u32,
+        stock: u32,
+    ) -> Producto {
+        //TODO: verificar que stock>0 y precio>0 y nombre y desc sean validos
+        Producto {
+            id,
+            id_vendedor,
+            nombre,
+            descripcion,
+            categoria,
+            stock,
+            atributos: Vec::new(),
+        }
+    }
+
+    /// Completa los atributos que pide la categoria del producto
+    pub fn con_atributos(mut self, atributos: Vec<Atributo>) -> Producto {
+        self.atributos = atributos;
+        self
+    }
+
+    pub fn get_categoria(&self) -> u32 {
+        self.categoria
+    }
+
+    pub fn get_atributos(&self) -> &[Atributo] {
+        &self.atributos
+    }
+
+    ///Compara un producto self con un producto pasado por parametro
+    #[allow(clippy::should_implement_trait)]
+    pub fn eq(&self, p: &Producto) -> bool {
+        self.nombre == p.nombre && self.categoria == p.categoria
+    }
+
+    /// Descuenta `cantidad` del stock del producto
+    pub fn descontar_stock(&mut self, cantidad: u32) -> Result<(), ErroresContrato> {
+        self.stock = self
+            .stock
+            .checked_sub(cantidad)
+            .ok_or(ErroresContrato::StockProductoInsuficiente)?;
+        Ok(())
+    }
+}
+
+// LOGICA DE PUBLICACION
+
+///Estructura de publicacion
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[derive(PartialEq, Debug, Clone)]
+pub struct Publicacion {
+    id: u32,
+    id_prod: u32,       //id del producto que contiene
+    id_user: AccountId, //id del user que publica
+    stock: u32,
+    precio_unitario: Balance,
+    activa: bool,
+}
+
+impl Publicacion {
+    pub fn stock(&self) -> u32 {
+        self.stock
+    }
+
+    pub fn new(
+        id: u32,
+        id_producto: u32,
+        id_user: AccountId,
+        stock: u32,
+        precio_unitario: Balance,
+    ) -> Publicacion {
+        Publicacion {
+            id,
+            id_prod: id_producto,
+            id_user,
+            stock,
+            precio_unitario,
+            activa: true,
+        }
+    }
+
+    /// Devuelve el AccountId del usuario que publica
+    pub fn get_id_vendedor(&self) -> AccountId {
+        self.id_user
+    }
+
+    /// Devuelve el precio de una unidad
+    pub fn get_precio_unitario(&self) -> Balance {
+        self.precio_unitario
+    }
+
+    /// Descuenta `cantidad` del stock de la publicacion
+    pub fn descontar_stock(&mut self, cantidad: u32) -> Result<(), ErroresContrato> {
+        self.stock = self
+            .stock
+            .checked_sub(cantidad)
+            .ok_or(ErroresContrato::StockPublicacionInsuficiente)?;
+        Ok(())
+    }
+
+    /// Devuelve `cantidad` unidades al stock de la publicacion
+    pub fn reponer_stock(&mut self, cantidad: u32) -> Result<(), ErroresContrato> {
+        self.stock = self
+            .stock
+            .checked_add(cantidad)
+            .ok_or(ErroresContrato::StockDesbordado)?;
+        Ok(())
+    }
+}
+
+///Estructuras y logica de Orden
+///Posibles estados de una Ordem
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum EstadoOrden {
+    Pendiente,
+    Enviada,   //solo lo puede modificar el vendedor
+    Recibida,  //solo lo puede modificar el comprador
+    Cancelada, //tienen que estar ambos de acuerdo y no tiene que haber sido recibida
+}
+
+/// Pedido de cancelacion de una de las partes, a la espera de que la otra lo confirme
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub struct SolicitudCancelacion {
+    solicitante: AccountId,
+    fecha: Timestamp,
+}
+
+impl SolicitudCancelacion {
+    pub fn get_solicitante(&self) -> AccountId {
+        self.solicitante
+    }
+
+    pub fn get_fecha(&self) -> Timestamp {
+        self.fecha
+    }
+
+    /// Devuelve true si la solicitud ya no se puede confirmar
+    pub fn vencida(&self, ahora: Timestamp) -> bool {
+        ahora.saturating_sub(self.fecha) > PLAZO_CANCELACION
+    }
+}
+
+///Estructura de orden
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[derive(Clone)]
+pub struct Orden {
+    //info de la orden
+    id: u32,
+    id_publicacion: u32,
+    id_vendedor: AccountId,
+    id_comprador: AccountId,
+    status: EstadoOrden,
+    cantidad: u32,
+    precio_total: Balance,
+    cal_vendedor: Option<u8>,  //calificacion que recibe el vendedor
+    cal_comprador: Option<u8>, //calificacion que recibe el comprador
+    cancelacion: Option<SolicitudCancelacion>,
+}
+
+impl Orden {
+    ///crea una nueva orden
+    pub fn new(
+        id: u32,
+        id_publicacion: u32,
+        id_vendedor: AccountId,
+        id_comprador: AccountId,
+        cantidad: u32,
+        precio_total: Balance,
+    ) -> Orden {
+        Orden {
+            id,
+            id_publicacion,
+            id_vendedor,
+            id_comprador,
+            status: EstadoOrden::Pendiente,
+            cantidad,
+            precio_total,
+            cal_vendedor: None,
+            cal_comprador: None,
+            cancelacion: None,
+        }
+    }
+    pub fn get_cantidad(&self) -> u32 {
+        self.cantidad
+    }
+
+    pub fn get_status(&self) -> EstadoOrden {
+        self.status
+    }
+
+    pub fn get_id_publicacion(&self) -> u32 {
+        self.id_publicacion
+    }
+
+    /// Devuelve la solicitud de cancelacion pendiente, si la hay
+    pub fn get_cancelacion(&self) -> Option<SolicitudCancelacion> {
+        self.cancelacion.clone()
+    }
+
+    /// Registra el pedido de cancelacion de `usuario`, o lo confirma si la contraparte
+    /// ya lo habia pedido y la solicitud no vencio. Devuelve true si la orden quedo cancelada.
+    pub fn cancelar(
+        &mut self,
+        usuario: AccountId,
+        ahora: Timestamp,
+    ) -> Result<bool, ErroresContrato> {
+        if usuario != self.id_comprador && usuario != self.id_vendedor {
+            return Err(ErroresContrato::UsuarioNoParticipaDeLaOrden);
+        }
+        match self.status {
+            EstadoOrden::Recibida => return Err(ErroresContrato::OrdenYaRecibida),
+            EstadoOrden::Cancelada => return Err(ErroresContrato::OrdenYaCancelada),
+            EstadoOrden::Pendiente | EstadoOrden::Enviada => {}
+        }
+        match &self.cancelacion {
+            Some(solicitud) if !solicitud.vencida(ahora) => {
+                if solicitud.solicitante == usuario {
+                    return Err(ErroresContrato::CancelacionYaSolicitada);
+                }
+                self.status = EstadoOrden::Cancelada;
+                self.cancelacion = None;
+                Ok(true)
+            }
+            _ => {
+                self.cancelacion = Some(SolicitudCancelacion {
+                    solicitante: usuario,
+                    fecha: ahora,
+                });
+                Ok(false)
+            }
+        }
+    }
+
+    /// Pasa la orden de `Pendiente` a `Enviada`
+    pub fn enviar(&mut self) -> Result<(), ErroresContrato> {
+        match self.status {
+            EstadoOrden::Pendiente => {
+                self.status = EstadoOrden::Enviada;
+                Ok(())
+            }
+            _ => Err(ErroresContrato::OrdenNoPendiente),
+        }
+    }
+
+    /// Pasa la orden de `Enviada` a `Recibida`
+    pub fn recibir(&mut self) -> Result<(), ErroresContrato> {
+        match self.status {
+            EstadoOrden::Enviada => {
+                self.status = EstadoOrden::Recibida;
+                Ok(())
+            }
+            _ => Err(ErroresContrato::OrdenNoEnviada),
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! Implementación de los traits `Gestion*` sobre colecciones de la biblioteca estándar.
+//!
+//! Sigue las mismas reglas que el contrato `Sistema`, pero guarda todo en `HashMap`, así el backend
+//! puede simular escenarios completos del marketplace sin levantar un nodo ni el entorno de ink!.
+
+use std::collections::HashMap;
+
+use crate::*;
+
+/// Marketplace en memoria. Los ids se asignan en orden, igual que en el contrato.
+#[derive(Default)]
+pub struct SistemaEnMemoria {
+    usuarios: HashMap<AccountId, Usuario>,
+    orden_registro: Vec<AccountId>,
+    mails: HashMap<HashMail, AccountId>,
+    productos: HashMap<u32, Producto>,
+    ordenes: HashMap<u32, Orden>,
+    publicaciones: HashMap<u32, Publicacion>,
+    categorias: HashMap<u32, Categoria>,
+}
+
+impl SistemaEnMemoria {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Devuelve el id que le corresponde al próximo elemento de una colección.
+    fn proximo_id<T>(coleccion: &HashMap<u32, T>) -> Result<u32, ErroresContrato> {
+        u32::try_from(coleccion.len()).map_err(|_| ErroresContrato::IndiceInvalido)
+    }
+
+    /// Devuelve los elementos de una colección ordenados por id.
+    fn listar<T: Clone>(coleccion: &HashMap<u32, T>) -> Vec<T> {
+        let mut ids: Vec<&u32> = coleccion.keys().collect();
+        ids.sort();
+        ids.into_iter().map(|id| coleccion[id].clone()).collect()
+    }
+}
+
+impl GestionProducto for SistemaEnMemoria {
+    type Error = ErroresContrato;
+
+    fn _crear_producto_con_atributos(
+        &mut self,
+        id_vendedor: AccountId,
+        nombre: String,
+        descripcion: String,
+        categoria: String,
+        stock: u32,
+        atributos: Vec<Atributo>,
+    ) -> Result<(), ErroresContrato> {
+        let id = Self::proximo_id(&self.productos)?;
+        let usuario = self.get_user(&id_vendedor)?;
+        if !usuario.has_role(VENDEDOR) {
+            return Err(ErroresContrato::UsuarioNoEsVendedor);
+        }
+        let id_cat = self.get_categoria_by_name(&categoria)?;
+        validar_atributos(&self.esquema_de_atributos(id_cat)?, &atributos)?;
+        let producto = Producto::new(id, id_vendedor, nombre, descripcion, id_cat, stock)
+            .con_atributos(atributos);
+        if self.producto_existe(&producto) {
+            return Err(ErroresContrato::ProductoYaExistente);
+        }
+        self.productos.insert(id, producto);
+        Ok(())
+    }
+
+    fn _listar_productos_por_categoria(
+        &self,
+        categoria: &str,
+    ) -> Result<Vec<Producto>, ErroresContrato> {
+        let categorias = self.categorias_descendientes(self.get_categoria_by_name(categoria)?);
+        Ok(Self::listar(&self.productos)
+            .into_iter()
+            .filter(|producto| categorias.contains(&producto.get_categoria()))
+            .collect())
+    }
+
+    fn producto_existe(&self, p: &Producto) -> bool {
+        self.productos.values().any(|prod| prod.eq(p))
+    }
+
+    fn descontar_stock_producto(&mut self, id: u32, cantidad: u32) -> Result<(), ErroresContrato> {
+        self.productos
+            .get_mut(&id)
+            .ok_or(ErroresContrato::ProductoInexistente)?
+            .descontar_stock(cantidad)
+    }
+
+    fn _listar_productos(&self) -> Vec<Producto> {
+        Self::listar(&self.productos)
+    }
+}
+
+impl GestionUsuario for SistemaEnMemoria {
+    type Error = ErroresContrato;
+
+    fn _registrar_usuario(
+        &mut self,
+        id: AccountId,
+        nombre: String,
+        mail: String,
+    ) -> Result<String, ErroresContrato> {
+        if self.usuarios.contains_key(&id) {
+            return Err(ErroresContrato::UsuarioYaExistente);
+        }
+        let hash = hash_mail(&mail);
+        if self.mails.contains_key(&hash) {
+            return Err(ErroresContrato::MailYaExistente);
+        }
+        self.usuarios.insert(id, Usuario::new(id, nombre, mail));
+        self.mails.insert(hash, id);
+        self.orden_registro.push(id);
+        Ok(String::from("El usuario fue registrado correctamente"))
+    }
+
+    fn get_user(&mut self, id: &AccountId) -> Result<Usuario, ErroresContrato> {
+        self.usuarios
+            .get(id)
+            .cloned()
+            .ok_or(ErroresContrato::UsuarioNoExiste)
+    }
+
+    fn _listar_usuarios(&self) -> Vec<Usuario> {
+        self.orden_registro
+            .iter()
+            .filter_map(|id| self.usuarios.get(id).cloned())
+            .collect()
+    }
+
+    fn get_usuario_by_mail(&self, mail: &str) -> Result<Usuario, ErroresContrato> {
+        self.mails
+            .get(&hash_mail(mail))
+            .and_then(|id| self.usuarios.get(id))
+            .cloned()
+            .ok_or(ErroresContrato::MailInexistente)
+    }
+
+    fn _asignar_rol(&mut self, id: AccountId, rol: Rol) -> Result<String, ErroresContrato> {
+        self.usuarios
+            .get_mut(&id)
+            .ok_or(ErroresContrato::UsuarioNoExiste)?
+            .agregar_rol(rol)?;
+        Ok(String::from("rol agregado correctamente"))
+    }
+
+    fn _verificar_mail(
+        &mut self,
+        id: AccountId,
+        hash_mail: HashMail,
+    ) -> Result<(), ErroresContrato> {
+        let usuario = self
+            .usuarios
+            .get_mut(&id)
+            .ok_or(ErroresContrato::UsuarioNoExiste)?;
+        if self.mails.get(&hash_mail) != Some(&id) {
+            return Err(ErroresContrato::MailNoCoincide);
+        }
+        usuario.marcar_verificado();
+        Ok(())
+    }
+
+    fn _exigir_compradores_verificados(
+        &mut self,
+        id: AccountId,
+        exigir: bool,
+    ) -> Result<(), ErroresContrato> {
+        self.usuarios
+            .get_mut(&id)
+            .ok_or(ErroresContrato::UsuarioNoExiste)?
+            .set_exige_verificados(exigir)
+    }
+}
+
+impl GestionOrden for SistemaEnMemoria {
+    type Error = ErroresContrato;
+
+    fn _crear_orden(
+        &mut self,
+        id_pub: u32,
+        id_comprador: AccountId,
+        cantidad: u32,
+    ) -> Result<(), ErroresContrato> {
+        let id_orden = Self::proximo_id(&self.ordenes)?;
+        let comprador = self.get_user(&id_comprador)?;
+        let id_vendedor = self.get_id_vendedor(id_pub)?;
+        let vendedor = self.get_user(&id_vendedor)?;
+        let precio_total = self
+            .get_precio_unitario(id_pub)?
+            .checked_mul(cantidad as u128)
+            .ok_or(ErroresContrato::ErrorMultiplicacion)?;
+        if !(comprador.has_role(COMPRADOR) && vendedor.has_role(VENDEDOR)) {
+            return Err(ErroresContrato::RolNoApropiado);
+        }
+        comprador.puede_comprarle_a(&vendedor)?;
+        if cantidad == 0 {
+            return Err(ErroresContrato::CantidadEnCarritoMenorAUno);
+        }
+        self.descontar_stock_publicacion(id_pub, cantidad)?;
+        let orden = Orden::new(
+            id_orden,
+            id_pub,
+            id_vendedor,
+            id_comprador,
+            cantidad,
+            precio_total,
+        );
+        self.ordenes.insert(id_orden, orden);
+        Ok(())
+    }
+
+    fn _listar_ordenes(&self) -> Vec<Orden> {
+        Self::listar(&self.ordenes)
+    }
+
+    fn _enviar_orden(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
+        self.ordenes
+            .get_mut(&id_orden)
+            .ok_or(ErroresContrato::OrdenInexistente)?
+            .enviar()
+    }
+
+    fn _recibir_orden(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
+        self.ordenes
+            .get_mut(&id_orden)
+            .ok_or(ErroresContrato::OrdenInexistente)?
+            .recibir()
+    }
+
+    fn _cancelar_orden(
+        &mut self,
+        id_orden: u32,
+        id_usuario: AccountId,
+        ahora: Timestamp,
+    ) -> Result<EstadoOrden, ErroresContrato> {
+        let mut orden = self
+            .ordenes
+            .get(&id_orden)
+            .cloned()
+            .ok_or(ErroresContrato::OrdenInexistente)?;
+        if orden.cancelar(id_usuario, ahora)? {
+            self.reponer_stock_publicacion(orden.get_id_publicacion(), orden.get_cantidad())?;
+        }
+        let estado = orden.get_status();
+        self.ordenes.insert(id_orden, orden);
+        Ok(estado)
+    }
+}
+
+impl GestionPublicacion for SistemaEnMemoria {
+    type Error = ErroresContrato;
+
+    fn _crear_publicacion(
+        &mut self,
+        id_producto: u32,
+        id_usuario: AccountId,
+        stock: u32,
+        precio: Balance,
+    ) -> Result<(), ErroresContrato> {
+        let id = Self::proximo_id(&self.publicaciones)?;
+        let usuario = self.get_user(&id_usuario)?;
+        if !usuario.has_role(VENDEDOR) {
+            return Err(ErroresContrato::RolNoApropiado);
+        }
+        self.descontar_stock_producto(id_producto, stock)?;
+        let p = Publicacion::new(id, id_producto, id_usuario, stock, precio);
+        self.publicaciones.insert(id, p);
+        Ok(())
+    }
+
+    fn _listar_publicaciones(&self) -> Vec<Publicacion> {
+        Self::listar(&self.publicaciones)
+    }
+
+    fn descontar_stock_publicacion(
+        &mut self,
+        id_pub: u32,
+        cantidad: u32,
+    ) -> Result<(), ErroresContrato> {
+        self.publicaciones
+            .get_mut(&id_pub)
+            .ok_or(ErroresContrato::PublicacionNoExiste)?
+            .descontar_stock(cantidad)
+    }
+
+    fn reponer_stock_publicacion(
+        &mut self,
+        id_pub: u32,
+        cantidad: u32,
+    ) -> Result<(), ErroresContrato> {
+        self.publicaciones
+            .get_mut(&id_pub)
+            .ok_or(ErroresContrato::PublicacionNoExiste)?
+            .reponer_stock(cantidad)
+    }
+
+    fn get_id_vendedor(&self, id_pub: u32) -> Result<AccountId, ErroresContrato> {
+        self.publicaciones
+            .get(&id_pub)
+            .map(Publicacion::get_id_vendedor)
+            .ok_or(ErroresContrato::PublicacionNoExiste)
+    }
+
+    fn get_precio_unitario(&self, id_pub: u32) -> Result<Balance, ErroresContrato> {
+        self.publicaciones
+            .get(&id_pub)
+            .map(Publicacion::get_precio_unitario)
+            .ok_or(ErroresContrato::PublicacionNoExiste)
+    }
+}
+
+impl GestionCategoria for SistemaEnMemoria {
+    type Error = ErroresContrato;
+
+    fn _registrar_subcategoria(
+        &mut self,
+        nombre: String,
+        padre: Option<String>,
+        atributos: Vec<DefinicionAtributo>,
+    ) -> Result<String, ErroresContrato> {
+        if self.get_categoria_by_name(&nombre).is_ok() {
+            return Err(ErroresContrato::CategoriaYaExistente);
+        }
+        let (id_padre, heredados) = match padre {
+            Some(padre) => {
+                let id_padre = self
+                    .get_categoria_by_name(&padre)
+                    .map_err(|_| ErroresContrato::CategoriaPadreInexistente)?;
+                (Some(id_padre), self.esquema_de_atributos(id_padre)?)
+            }
+            None => (None, Vec::new()),
+        };
+        validar_definiciones(&heredados, &atributos)?;
+        let id = Self::proximo_id(&self.categorias)
+            .map_err(|_| ErroresContrato::MaxCategoriasAlcanzado)?;
+        if id == u32::MAX {
+            return Err(ErroresContrato::MaxCategoriasAlcanzado);
+        }
+        let nueva_categoria = Categoria::new(id, self.clean_cat_name(&nombre)?)
+            .con_padre(id_padre)
+            .con_atributos(atributos);
+        self.categorias.insert(id, nueva_categoria);
+        Ok(String::from("la categoria fue registrada correctamente"))
+    }
+
+    fn _listar_categorias(&self) -> Vec<Categoria> {
+        Self::listar(&self.categorias)
+    }
+
+    fn get_categoria(&self, id: u32) -> Result<Categoria, ErroresContrato> {
+        self.categorias
+            .get(&id)
+            .cloned()
+            .ok_or(ErroresContrato::CategoriaInexistente)
+    }
+
+    fn get_categoria_by_name(&self, nombre: &str) -> Result<u32, ErroresContrato> {
+        let nombre_limpio = self.clean_cat_name(nombre)?;
+        self.categorias
+            .iter()
+            .find(|(_, categoria)| categoria.nombre == nombre_limpio)
+            .map(|(id, _)| *id)
+            .ok_or(ErroresContrato::CategoriaInexistente)
+    }
+
+    fn clean_cat_name(&self, nombre: &str) -> Result<String, ErroresContrato> {
+        limpiar_nombre_categoria(nombre)
+    }
+}
@@ -0,0 +1,624 @@
+//! Suite de tests genérica sobre los traits `Gestion*`.
+//!
+//! Cada función recibe un marketplace vacío y verifica una parte del comportamiento esperado.
+//! Las implementaciones la corren con la macro `suite_gestion!`, así el contrato y el backend
+//! en memoria quedan obligados a responder igual ante los mismos escenarios.
+
+use core::fmt::Debug;
+
+use crate::*;
+
+/// Un marketplace completo: implementa todos los traits `Gestion*` con el mismo tipo de error.
+pub trait Marketplace<E>:
+    GestionUsuario<Error = E>
+    + GestionCategoria<Error = E>
+    + GestionProducto<Error = E>
+    + GestionPublicacion<Error = E>
+    + GestionOrden<Error = E>
+{
+}
+
+impl<S, E> Marketplace<E> for S where
+    S: GestionUsuario<Error = E>
+        + GestionCategoria<Error = E>
+        + GestionProducto<Error = E>
+        + GestionPublicacion<Error = E>
+        + GestionOrden<Error = E>
+{
+}
+
+/// Errores que la suite puede comparar: cualquiera que se pueda construir desde `ErroresContrato`.
+pub trait ErrorComparable: From<ErroresContrato> + PartialEq + Debug {}
+
+impl<E: From<ErroresContrato> + PartialEq + Debug> ErrorComparable for E {}
+
+pub fn cuenta(n: u8) -> AccountId {
+    AccountId::from([n; 32])
+}
+
+fn error<E: ErrorComparable>(e: ErroresContrato) -> E {
+    E::from(e)
+}
+
+/// Registra un vendedor (1) y un comprador (2), la categoria "ropa" y un producto con 10 unidades.
+fn escenario_con_producto<S: Marketplace<E>, E: ErrorComparable>(s: &mut S) {
+    s._registrar_usuario(cuenta(1), "vendedor".into(), "vendedor@mail.com".into())
+        .unwrap();
+    s._asignar_rol(cuenta(1), VENDEDOR).unwrap();
+    s._registrar_usuario(cuenta(2), "comprador".into(), "comprador@mail.com".into())
+        .unwrap();
+    s._asignar_rol(cuenta(2), COMPRADOR).unwrap();
+    s._registrar_categoria("Ropa".into()).unwrap();
+    s._crear_producto(cuenta(1), "Remera".into(), "desc".into(), "Ropa".into(), 10)
+        .unwrap();
+}
+
+pub fn registro_de_usuarios<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    assert!(s
+        ._registrar_usuario(cuenta(1), "Ana".into(), "ana@mail.com".into())
+        .is_ok());
+    assert_eq!(
+        s._registrar_usuario(cuenta(1), "Otra".into(), "otra@mail.com".into()),
+        Err(error(ErroresContrato::UsuarioYaExistente))
+    );
+    assert_eq!(
+        s._registrar_usuario(cuenta(2), "Beto".into(), "ana@mail.com".into()),
+        Err(error(ErroresContrato::MailYaExistente))
+    );
+    assert!(s
+        ._registrar_usuario(cuenta(2), "Beto".into(), "beto@mail.com".into())
+        .is_ok());
+
+    let usuarios = s._listar_usuarios();
+    assert_eq!(usuarios.len(), 2);
+    assert_eq!(usuarios[0].get_id(), cuenta(1));
+    assert_eq!(usuarios[1].get_name(), "Beto");
+    assert_eq!(
+        s.get_usuario_by_mail("beto@mail.com").unwrap().get_id(),
+        cuenta(2)
+    );
+    assert!(s.get_usuario_by_mail("nadie@mail.com").is_err());
+    assert!(s.get_user(&cuenta(3)).is_err());
+}
+
+pub fn mails_unicos_normalizados<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    s._registrar_usuario(cuenta(1), "Ana".into(), "Ana@Mail.com".into())
+        .unwrap();
+    assert_eq!(
+        s._registrar_usuario(cuenta(2), "Beto".into(), "  ana@mail.COM ".into()),
+        Err(error(ErroresContrato::MailYaExistente))
+    );
+    assert_eq!(
+        s.get_usuario_by_mail("ana@mail.com").unwrap().get_id(),
+        cuenta(1)
+    );
+    // Se guarda el mail tal como se registró
+    assert_eq!(s.get_user(&cuenta(1)).unwrap().get_mail(), "Ana@Mail.com");
+    assert!(s.get_user(&cuenta(2)).is_err());
+}
+
+pub fn verificacion_de_mail<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_producto(&mut s);
+    s._crear_publicacion(0, cuenta(1), 5, 100).unwrap();
+
+    assert_eq!(
+        s._verificar_mail(cuenta(2), hash_mail("vendedor@mail.com")),
+        Err(error(ErroresContrato::MailNoCoincide))
+    );
+    assert_eq!(
+        s._verificar_mail(cuenta(9), hash_mail("comprador@mail.com")),
+        Err(error(ErroresContrato::UsuarioNoExiste))
+    );
+    assert!(!s.get_user(&cuenta(2)).unwrap().esta_verificado());
+
+    // Solo un vendedor puede exigir compradores verificados
+    assert_eq!(
+        s._exigir_compradores_verificados(cuenta(2), true),
+        Err(error(ErroresContrato::UsuarioNoEsVendedor))
+    );
+    assert!(s._exigir_compradores_verificados(cuenta(1), true).is_ok());
+    assert_eq!(
+        s._crear_orden(0, cuenta(2), 1),
+        Err(error(ErroresContrato::CompradorNoVerificado))
+    );
+
+    assert!(s
+        ._verificar_mail(cuenta(2), hash_mail(" Comprador@mail.com"))
+        .is_ok());
+    assert!(s.get_user(&cuenta(2)).unwrap().esta_verificado());
+    assert!(s._crear_orden(0, cuenta(2), 1).is_ok());
+
+    assert!(s._exigir_compradores_verificados(cuenta(1), false).is_ok());
+    assert!(!s.get_user(&cuenta(1)).unwrap().exige_verificados());
+}
+
+pub fn asignacion_de_roles<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    s._registrar_usuario(cuenta(1), "Ana".into(), "ana@mail.com".into())
+        .unwrap();
+    assert!(!s.get_user(&cuenta(1)).unwrap().has_role(VENDEDOR));
+
+    assert!(s._asignar_rol(cuenta(1), VENDEDOR).is_ok());
+    assert!(s._asignar_rol(cuenta(1), COMPRADOR).is_ok());
+    assert_eq!(
+        s._asignar_rol(cuenta(1), VENDEDOR),
+        Err(error(ErroresContrato::AlreadyHasRol))
+    );
+    let usuario = s.get_user(&cuenta(1)).unwrap();
+    assert!(usuario.has_role(VENDEDOR) && usuario.has_role(COMPRADOR));
+
+    assert_eq!(
+        s._asignar_rol(cuenta(9), VENDEDOR),
+        Err(error(ErroresContrato::UsuarioNoExiste))
+    );
+}
+
+pub fn registro_de_categorias<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    assert!(s._registrar_categoria("  Ropa ".into()).is_ok());
+    assert!(s._registrar_categoria("Libros".into()).is_ok());
+    assert_eq!(
+        s._registrar_categoria("ROPA".into()),
+        Err(error(ErroresContrato::CategoriaYaExistente))
+    );
+    assert_eq!(
+        s._registrar_categoria("   ".into()),
+        Err(error(ErroresContrato::NombreCategoriaVacio))
+    );
+
+    assert_eq!(s._listar_categorias().len(), 2);
+    assert_eq!(s._listar_categorias()[0].get_nombre(), "ropa");
+    assert_eq!(s.get_categoria_by_name("libros"), Ok(1));
+    assert_eq!(
+        s.get_categoria_by_name("muebles"),
+        Err(error(ErroresContrato::CategoriaInexistente))
+    );
+}
+
+pub fn creacion_de_productos<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_producto(&mut s);
+
+    assert_eq!(
+        s._listar_productos(),
+        vec![Producto::new(
+            0,
+            cuenta(1),
+            "Remera".into(),
+            "desc".into(),
+            0,
+            10
+        )]
+    );
+    assert_eq!(
+        s._crear_producto(cuenta(1), "Remera".into(), "otra".into(), "ropa".into(), 1),
+        Err(error(ErroresContrato::ProductoYaExistente))
+    );
+    assert_eq!(
+        s._crear_producto(cuenta(2), "Buzo".into(), "desc".into(), "Ropa".into(), 1),
+        Err(error(ErroresContrato::UsuarioNoEsVendedor))
+    );
+    assert_eq!(
+        s._crear_producto(
+            cuenta(1),
+            "Silla".into(),
+            "desc".into(),
+            "Muebles".into(),
+            1
+        ),
+        Err(error(ErroresContrato::CategoriaInexistente))
+    );
+
+    assert!(s.descontar_stock_producto(0, 4).is_ok());
+    assert_eq!(
+        s.descontar_stock_producto(0, 7),
+        Err(error(ErroresContrato::StockProductoInsuficiente))
+    );
+    assert_eq!(
+        s.descontar_stock_producto(5, 1),
+        Err(error(ErroresContrato::ProductoInexistente))
+    );
+    assert_eq!(
+        s._listar_productos()[0],
+        Producto::new(0, cuenta(1), "Remera".into(), "desc".into(), 0, 6)
+    );
+}
+
+pub fn creacion_de_publicaciones<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_producto(&mut s);
+
+    assert_eq!(
+        s._crear_publicacion(0, cuenta(2), 5, 100),
+        Err(error(ErroresContrato::RolNoApropiado))
+    );
+    assert_eq!(
+        s._crear_publicacion(0, cuenta(1), 11, 100),
+        Err(error(ErroresContrato::StockProductoInsuficiente))
+    );
+    assert!(s._crear_publicacion(0, cuenta(1), 6, 100).is_ok());
+
+    // La publicación se lleva el stock del producto
+    assert_eq!(
+        s._listar_productos()[0].clone().descontar_stock(5),
+        Err(ErroresContrato::StockProductoInsuficiente)
+    );
+    assert_eq!(
+        s._listar_publicaciones(),
+        vec![Publicacion::new(0, 0, cuenta(1), 6, 100)]
+    );
+    assert_eq!(s.get_precio_unitario(0), Ok(100));
+    assert_eq!(s.get_id_vendedor(0), Ok(cuenta(1)));
+    assert_eq!(
+        s.get_id_vendedor(1),
+        Err(error(ErroresContrato::PublicacionNoExiste))
+    );
+
+    assert!(s.descontar_stock_publicacion(0, 2).is_ok());
+    assert_eq!(s._listar_publicaciones()[0].stock(), 4);
+    assert_eq!(
+        s.descontar_stock_publicacion(0, 5),
+        Err(error(ErroresContrato::StockPublicacionInsuficiente))
+    );
+}
+
+pub fn creacion_de_ordenes<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_producto(&mut s);
+    s._crear_publicacion(0, cuenta(1), 5, 100).unwrap();
+
+    assert_eq!(
+        s._crear_orden(0, cuenta(1), 1),
+        Err(error(ErroresContrato::RolNoApropiado))
+    );
+    assert_eq!(
+        s._crear_orden(0, cuenta(2), 0),
+        Err(error(ErroresContrato::CantidadEnCarritoMenorAUno))
+    );
+    assert_eq!(
+        s._crear_orden(3, cuenta(2), 1),
+        Err(error(ErroresContrato::PublicacionNoExiste))
+    );
+    assert_eq!(
+        s._crear_orden(0, cuenta(2), 6),
+        Err(error(ErroresContrato::StockPublicacionInsuficiente))
+    );
+    assert!(s._listar_ordenes().is_empty());
+
+    assert!(s._crear_orden(0, cuenta(2), 2).is_ok());
+    let ordenes = s._listar_ordenes();
+    assert_eq!(ordenes.len(), 1);
+    assert_eq!(ordenes[0].get_cantidad(), 2);
+    assert_eq!(ordenes[0].get_status(), EstadoOrden::Pendiente);
+    assert_eq!(s._listar_publicaciones()[0].stock(), 3);
+}
+
+pub fn ciclo_de_vida_de_ordenes<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_producto(&mut s);
+    s._crear_publicacion(0, cuenta(1), 5, 100).unwrap();
+    s._crear_orden(0, cuenta(2), 1).unwrap();
+
+    assert_eq!(
+        s._recibir_orden(0),
+        Err(error(ErroresContrato::OrdenNoEnviada))
+    );
+    assert!(s._enviar_orden(0).is_ok());
+    assert_eq!(s._listar_ordenes()[0].get_status(), EstadoOrden::Enviada);
+    assert_eq!(
+        s._enviar_orden(0),
+        Err(error(ErroresContrato::OrdenNoPendiente))
+    );
+    assert!(s._recibir_orden(0).is_ok());
+    assert_eq!(s._listar_ordenes()[0].get_status(), EstadoOrden::Recibida);
+
+    assert_eq!(
+        s._enviar_orden(7),
+        Err(error(ErroresContrato::OrdenInexistente))
+    );
+    assert_eq!(
+        s._recibir_orden(7),
+        Err(error(ErroresContrato::OrdenInexistente))
+    );
+}
+
+pub fn cancelacion_de_ordenes<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_producto(&mut s);
+    s._registrar_usuario(cuenta(3), "otro".into(), "otro@mail.com".into())
+        .unwrap();
+    s._crear_publicacion(0, cuenta(1), 5, 100).unwrap();
+    s._crear_orden(0, cuenta(2), 2).unwrap();
+    assert_eq!(s._listar_publicaciones()[0].stock(), 3);
+
+    assert_eq!(
+        s._cancelar_orden(0, cuenta(3), 0),
+        Err(error(ErroresContrato::UsuarioNoParticipaDeLaOrden))
+    );
+    assert_eq!(
+        s._cancelar_orden(9, cuenta(2), 0),
+        Err(error(ErroresContrato::OrdenInexistente))
+    );
+
+    // El comprador la pide: la orden sigue igual hasta que confirme el vendedor
+    assert_eq!(
+        s._cancelar_orden(0, cuenta(2), 1_000),
+        Ok(EstadoOrden::Pendiente)
+    );
+    assert_eq!(
+        s._cancelar_orden(0, cuenta(2), 2_000),
+        Err(error(ErroresContrato::CancelacionYaSolicitada))
+    );
+    let solicitud = s._listar_ordenes()[0].get_cancelacion().unwrap();
+    assert_eq!(solicitud.get_solicitante(), cuenta(2));
+    assert_eq!(solicitud.get_fecha(), 1_000);
+
+    // Se puede cancelar aunque ya este enviada
+    s._enviar_orden(0).unwrap();
+    assert_eq!(
+        s._cancelar_orden(0, cuenta(1), 3_000),
+        Ok(EstadoOrden::Cancelada)
+    );
+    assert_eq!(s._listar_ordenes()[0].get_status(), EstadoOrden::Cancelada);
+    assert_eq!(s._listar_ordenes()[0].get_cancelacion(), None);
+
+    // Las unidades vuelven solo a la publicacion, el producto no las recupera
+    assert_eq!(s._listar_publicaciones()[0].stock(), 5);
+    assert!(s.descontar_stock_producto(0, 5).is_ok());
+    assert_eq!(
+        s.descontar_stock_producto(0, 1),
+        Err(error(ErroresContrato::StockProductoInsuficiente))
+    );
+
+    assert_eq!(
+        s._cancelar_orden(0, cuenta(2), 4_000),
+        Err(error(ErroresContrato::OrdenYaCancelada))
+    );
+}
+
+pub fn cancelacion_vencida_o_recibida<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_producto(&mut s);
+    s._crear_publicacion(0, cuenta(1), 5, 100).unwrap();
+    s._crear_orden(0, cuenta(2), 1).unwrap();
+    s._crear_orden(0, cuenta(2), 1).unwrap();
+
+    // Si la contraparte no confirma a tiempo, su respuesta cuenta como un pedido nuevo
+    assert_eq!(
+        s._cancelar_orden(0, cuenta(1), 0),
+        Ok(EstadoOrden::Pendiente)
+    );
+    let vencimiento = PLAZO_CANCELACION + 1;
+    assert_eq!(
+        s._cancelar_orden(0, cuenta(2), vencimiento),
+        Ok(EstadoOrden::Pendiente)
+    );
+    assert_eq!(
+        s._listar_ordenes()[0]
+            .get_cancelacion()
+            .unwrap()
+            .get_solicitante(),
+        cuenta(2)
+    );
+    assert_eq!(
+        s._cancelar_orden(0, cuenta(1), vencimiento + PLAZO_CANCELACION),
+        Ok(EstadoOrden::Cancelada)
+    );
+
+    // Una vez recibida ya no se puede cancelar
+    s._enviar_orden(1).unwrap();
+    s._recibir_orden(1).unwrap();
+    assert_eq!(
+        s._cancelar_orden(1, cuenta(2), 0),
+        Err(error(ErroresContrato::OrdenYaRecibida))
+    );
+    assert_eq!(s._listar_publicaciones()[0].stock(), 4);
+}
+
+fn definicion(nombre: &str, tipo: TipoAtributo) -> DefinicionAtributo {
+    DefinicionAtributo::new(nombre.into(), tipo)
+}
+
+fn atributo(nombre: &str, valor: ValorAtributo) -> Atributo {
+    Atributo::new(nombre.into(), valor)
+}
+
+/// Arma Electronica (marca) > Audio > Auriculares (inalambricos) y registra un vendedor (1).
+fn escenario_con_jerarquia<S: Marketplace<E>, E: ErrorComparable>(s: &mut S) {
+    s._registrar_usuario(cuenta(1), "vendedor".into(), "vendedor@mail.com".into())
+        .unwrap();
+    s._asignar_rol(cuenta(1), VENDEDOR).unwrap();
+    s._registrar_subcategoria(
+        "Electronica".into(),
+        None,
+        vec![definicion("marca", TipoAtributo::Texto)],
+    )
+    .unwrap();
+    s._registrar_subcategoria("Audio".into(), Some("electronica".into()), Vec::new())
+        .unwrap();
+    s._registrar_subcategoria(
+        "Auriculares".into(),
+        Some("Audio".into()),
+        vec![definicion("inalambricos", TipoAtributo::Booleano)],
+    )
+    .unwrap();
+}
+
+pub fn categorias_jerarquicas<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_jerarquia(&mut s);
+
+    let categorias = s._listar_categorias();
+    assert_eq!(categorias[0].get_padre(), None);
+    assert_eq!(categorias[1].get_padre(), Some(0));
+    assert_eq!(categorias[2].get_padre(), Some(1));
+    assert_eq!(
+        s.esquema_de_atributos(2),
+        Ok(vec![
+            definicion("inalambricos", TipoAtributo::Booleano),
+            definicion("marca", TipoAtributo::Texto),
+        ])
+    );
+    assert_eq!(s.categorias_descendientes(0), vec![0, 1, 2]);
+    assert_eq!(s.categorias_descendientes(2), vec![2]);
+
+    assert_eq!(
+        s._registrar_subcategoria("Parlantes".into(), Some("Muebles".into()), Vec::new()),
+        Err(error(ErroresContrato::CategoriaPadreInexistente))
+    );
+    // No se puede redeclarar un atributo heredado ni repetir uno propio
+    assert_eq!(
+        s._registrar_subcategoria(
+            "Parlantes".into(),
+            Some("Audio".into()),
+            vec![definicion("marca", TipoAtributo::Texto)],
+        ),
+        Err(error(ErroresContrato::AtributoDuplicado))
+    );
+    assert_eq!(
+        s._registrar_subcategoria(
+            "Parlantes".into(),
+            None,
+            vec![
+                definicion("potencia", TipoAtributo::Numero),
+                definicion("potencia", TipoAtributo::Numero),
+            ],
+        ),
+        Err(error(ErroresContrato::AtributoDuplicado))
+    );
+    let demasiados = (0..=MAX_ATRIBUTOS_POR_CATEGORIA)
+        .map(|i| definicion(&format!("atributo {}", i), TipoAtributo::Texto))
+        .collect();
+    assert_eq!(
+        s._registrar_subcategoria("Parlantes".into(), None, demasiados),
+        Err(error(ErroresContrato::DemasiadosAtributos))
+    );
+    assert_eq!(s._listar_categorias().len(), 3);
+}
+
+pub fn productos_con_atributos<S: Marketplace<E>, E: ErrorComparable>(mut s: S) {
+    escenario_con_jerarquia(&mut s);
+    let marca = || atributo("marca", ValorAtributo::Texto("Sony".into()));
+    let inalambricos = || atributo("inalambricos", ValorAtributo::Booleano(true));
+    let crear = |s: &mut S, nombre: &str, categoria: &str, atributos: Vec<Atributo>| {
+        s._crear_producto_con_atributos(
+            cuenta(1),
+            nombre.into(),
+            "desc".into(),
+            categoria.into(),
+            1,
+            atributos,
+        )
+    };
+
+    assert_eq!(
+        crear(&mut s, "WH-1000", "Auriculares", vec![marca()]),
+        Err(error(ErroresContrato::AtributoFaltante))
+    );
+    assert_eq!(
+        crear(
+            &mut s,
+            "WH-1000",
+            "Auriculares",
+            vec![marca(), atributo("inalambricos", ValorAtributo::Numero(1))]
+        ),
+        Err(error(ErroresContrato::TipoDeAtributoInvalido))
+    );
+    assert_eq!(
+        crear(
+            &mut s,
+            "WH-1000",
+            "Auriculares",
+            vec![
+                marca(),
+                inalambricos(),
+                atributo("color", ValorAtributo::Texto("negro".into()))
+            ]
+        ),
+        Err(error(ErroresContrato::AtributoDesconocido))
+    );
+    assert_eq!(
+        crear(
+            &mut s,
+            "WH-1000",
+            "Auriculares",
+            vec![marca(), marca(), inalambricos()]
+        ),
+        Err(error(ErroresContrato::AtributoDuplicado))
+    );
+    // Sin atributos solo se puede crear en una categoria que no los pida
+    assert_eq!(
+        s._crear_producto(cuenta(1), "Cable".into(), "desc".into(), "Audio".into(), 1),
+        Err(error(ErroresContrato::AtributoFaltante))
+    );
+    assert!(s._listar_productos().is_empty());
+
+    assert!(crear(
+        &mut s,
+        "WH-1000",
+        "Auriculares",
+        vec![inalambricos(), marca()]
+    )
+    .is_ok());
+    assert!(crear(&mut s, "Cable", "Audio", vec![marca()]).is_ok());
+    assert!(crear(&mut s, "Tele", "Electronica", vec![marca()]).is_ok());
+    assert_eq!(
+        s._listar_productos()[0].get_atributos(),
+        &[inalambricos(), marca()][..]
+    );
+
+    let categorias = |productos: Vec<Producto>| -> Vec<u32> {
+        productos.iter().map(Producto::get_categoria).collect()
+    };
+    assert_eq!(
+        s._listar_productos_por_categoria("electronica")
+            .map(categorias),
+        Ok(vec![2, 1, 0])
+    );
+    assert_eq!(
+        s._listar_productos_por_categoria("Audio").map(categorias),
+        Ok(vec![2, 1])
+    );
+    assert_eq!(
+        s._listar_productos_por_categoria("auriculares")
+            .map(categorias),
+        Ok(vec![2])
+    );
+    assert_eq!(
+        s._listar_productos_por_categoria("Muebles"),
+        Err(error(ErroresContrato::CategoriaInexistente))
+    );
+}
+
+/// Genera un test por cada función de la suite, construyendo el marketplace con la expresión dada.
+/// El primer argumento es el atributo de cada test, por ejemplo `ink::test` para correrla sobre el contrato.
+///
+/// ```ignore
+/// marketplace_core::suite_gestion!(test, SistemaEnMemoria::new());
+/// ```
+#[macro_export]
+macro_rules! suite_gestion {
+    ($atributo:meta, $nuevo:expr) => {
+        $crate::suite_gestion!(@tests $atributo, $nuevo;
+            registro_de_usuarios,
+            mails_unicos_normalizados,
+            verificacion_de_mail,
+            asignacion_de_roles,
+            registro_de_categorias,
+            creacion_de_productos,
+            creacion_de_publicaciones,
+            creacion_de_ordenes,
+            ciclo_de_vida_de_ordenes,
+            cancelacion_de_ordenes,
+            cancelacion_vencida_o_recibida,
+            categorias_jerarquicas,
+            productos_con_atributos,
+        );
+    };
+    (@tests $atributo:meta, $nuevo:expr; $($test:ident,)*) => {
+        $(
+            #[$atributo]
+            fn $test() {
+                $crate::pruebas::$test($nuevo);
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SistemaEnMemoria;
+
+    crate::suite_gestion!(test, SistemaEnMemoria::new());
+}
@@ -4,141 +4,18 @@
 mod contract {
     use ink::{
         prelude::{string::String, vec::Vec},
-        storage::{traits::StorageLayout, Mapping, StorageVec},
+        storage::{Mapping, StorageVec},
     };
-    use scale::{Decode, Encode};
-    use scale_info::TypeInfo;
     //use scale_info::prelude::vec::Vec;
 
-    pub const COMPRADOR: Rol = Rol::Comprador;
-    pub const VENDEDOR: Rol = Rol::Vendedor;
-
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(Debug, PartialEq)]
-
-    pub enum ErroresContrato {
-        UsuarioSinRoles,
-        UsuarioYaExistente,
-        UsuarioNoEsComprador,
-        UsuarioYaEsComprador,
-        UsuarioYaEsVendedor,
-        UsuarioNoEsVendedor,
-        UsuarioNoExiste,
-        UsuarioNoTieneRol,
-        OrdenNoPendiente,
-        OrdenNoEnviada,
-        OrdenYaCancelada,
-        OrdenInexistente,
-        StockPublicacionInsuficiente,
-        StockProductoInsuficiente,
-        StockInsuficiente,
-        CuentaNoRegistrada,
-        MailYaExistente,
-        MailInexistente,
-        ProductoInexistente,
-        ProductoYaExistente,
-        PublicacionNoExiste,
-        CategoriaYaExistente,
-        CategoriaInexistente,
-        ErrorMultiplicacion,
-        RolNoApropiado,
-        AccountIdInvalida,
-        IndiceInvalido,
-        AlreadyHasRol,
-        CantidadEnCarritoMenorAUno,
-        NombreCategoriaVacio,
-        MaxCategoriasAlcanzado,
-        ListaSinProductos,
-    }
-
-    pub trait GestionProducto {
-        fn _crear_producto(
-            &mut self,
-            id_vendedor: AccountId,
-            nombre: String,
-            descripcion: String,
-            categoria: String,
-            stock: u32,
-        ) -> Result<(), ErroresContrato>;
-
-        fn descontar_stock_producto(
-            &mut self,
-            id: u32,
-            cantidad: u32,
-        ) -> Result<(), ErroresContrato>;
-
-        fn producto_existe(&self, p: &Producto) -> bool;
-
-        fn _listar_productos(&self) -> Vec<Producto>;
-    }
-
-    pub trait GestionUsuario {
-        fn _registrar_usuario(
-            &mut self,
-            id: AccountId,
-            nombre: String,
-            mail: String,
-        ) -> Result<String, ErroresContrato>;
-
-        fn get_user(&mut self, id: &AccountId) -> Result<Usuario, ErroresContrato>;
-
-        fn _listar_usuarios(&self) -> Vec<Usuario>;
-
-        fn get_usuario_by_mail(&self, mail: &str) -> Result<Usuario, ErroresContrato>;
-
-        //fn _usuario_con_rol(&self, rol: Rol) -> Result<(), ErroresContrato>;
-
-        fn _asignar_rol(&mut self, id: AccountId, rol: Rol) -> Result<String, ErroresContrato>;
-    }
-
-    pub trait GestionOrden {
-        fn _crear_orden(
-            &mut self,
-            id_pub: u32,
-            id_comprador: AccountId,
-            cantidad: u32,
-        ) -> Result<(), ErroresContrato>;
-
-        fn _listar_ordenes(&self) -> Vec<Orden>;
-
-        fn _enviar_orden(&mut self, id_orden: u32) -> Result<(), ErroresContrato>;
-
-        fn _recibir_orden(&mut self, id_orden: u32) -> Result<(), ErroresContrato>;
-
-        //fn _cancelar_orden(&mut self, id_orden: u32) -> Result<(), ErroresContrato>;
-    }
-
-    pub trait GestionPublicacion {
-        fn _crear_publicacion(
-            &mut self,
-            id_producto: u32,
-            id_usuario: AccountId,
-            stock: u32,
-            precio: Balance,
-        ) -> Result<(), ErroresContrato>;
-
-        fn descontar_stock_publicacion(
-            &mut self,
-            id_pub: u32,
-            cantidad: u32,
-        ) -> Result<(), ErroresContrato>;
-
-        fn get_precio_unitario(&self, id_pub: u32) -> Result<Balance, ErroresContrato>;
-
-        fn get_id_vendedor(&self, id_pub: u32) -> Result<AccountId, ErroresContrato>; // HAY QUE VOLARLO A LA MIERDA EN LA 2DA ENTREGA
-
-        fn _listar_publicaciones(&self) -> Vec<Publicacion>;
-    }
-
-    pub trait GestionCategoria {
-        fn _registrar_categoria(&mut self, nombre: String) -> Result<String, ErroresContrato>;
-
-        fn _listar_categorias(&self) -> Vec<Categoria>;
-
-        fn get_categoria_by_name(&self, nombre: &String) -> Result<u32, ErroresContrato>;
-
-        fn clean_cat_name(&self, nombre: &String) -> Result<String, ErroresContrato>;
-    }
+    // Las estructuras del dominio y los traits de gestion viven en `marketplace-core`,
+    // el contrato solo los implementa sobre el storage de ink!.
+    pub use marketplace_core::{
+        hash_mail, limpiar_nombre_categoria, validar_atributos, validar_definiciones, Atributo,
+        Categoria, DefinicionAtributo, ErroresContrato, EstadoOrden, GestionCategoria,
+        GestionOrden, GestionProducto, GestionPublicacion, GestionUsuario, HashMail, Orden,
+        Producto, Publicacion, Rol, Usuario, COMPRADOR, VENDEDOR,
+    };
 
     // pub trait ControlStock {
     //     fn get_cantidad(&self) -> u32;
@@ -171,6 +48,9 @@ mod contract {
     pub struct Sistema {
         m_usuarios: Mapping<AccountId, Usuario>,
         v_usuarios: StorageVec<AccountId>,
+        m_mails: Mapping<HashMail, AccountId>, // hash del mail normalizado -> dueño
+        admin: AccountId,
+        verificador: Option<AccountId>,
         productos: StorageVec<Producto>,
         ordenes: StorageVec<Orden>,
         publicaciones: StorageVec<Publicacion>,
@@ -183,7 +63,7 @@ mod contract {
         /// Inicializa todas las estructuras de almacenamiento (`Mapping` y `Vec`) vacías.
         ///
         /// Se ejecuta una única vez al desplegar el contrato en la blockchain.
-        /// No realiza ninguna lógica adicional.
+        /// Quien lo despliega queda como admin y es el único que puede configurar el verificador de mails.
         ///
         /// Retorna una instancia del contrato lista para ser utilizada.
         #[ink(constructor)]
@@ -191,6 +71,9 @@ mod contract {
             Sistema {
                 m_usuarios: Mapping::default(),
                 v_usuarios: StorageVec::new(),
+                m_mails: Mapping::default(),
+                admin: Self::env().caller(),
+                verificador: None,
                 productos: StorageVec::default(),
                 ordenes: StorageVec::default(),
                 publicaciones: StorageVec::default(),
@@ -263,19 +146,53 @@ mod contract {
             self._registrar_categoria(nombre)
         }
 
-        /// Publica un producto previamente registrado en el contrato, generando una publicación activa.
+        /// Registra una categoría debajo de otra ya existente, por ejemplo Electrónica > Audio.
         ///
         /// # Parámetros
-        /// - `id_producto`: ID del producto a publicar.
-        /// - `cantidad`: Cantidad disponible para la publicación.
+        /// - `nombre`: Nombre de la categoría a registrar.
+        /// - `padre`: Nombre de la categoría padre, o `None` para registrarla como raíz.
+        /// - `atributos`: Atributos que deben completar sus productos. Se suman a los que
+        ///   declaran las categorías padre.
+        ///
+        /// # Requisitos
+        /// - El caller debe estar previamente registrado como usuario.
+        ///
+        /// # Errores
+        /// - `UsuarioNoExiste`: Si el caller no está registrado.
+        /// - `CategoriaYaExistente`: Si la categoria ya existe actualmente.
+        /// - `CategoriaPadreInexistente`: Si no existe la categoría padre.
+        /// - `AtributoDuplicado`: Si un atributo se repite o ya lo declara una categoría padre.
+        /// - `DemasiadosAtributos`: Si declara más de `MAX_ATRIBUTOS_POR_CATEGORIA` atributos.
+        #[ink(message)]
+        pub fn registrar_subcategoria(
+            &mut self,
+            nombre: String,
+            padre: Option<String>,
+            atributos: Vec<DefinicionAtributo>,
+        ) -> Result<String, ErroresContrato> {
+            self.get_user(&self.env().caller())?;
+            self._registrar_subcategoria(nombre, padre, atributos)
+        }
+
+        /// Registra un producto del caller dentro de una categoría.
+        ///
+        /// # Parámetros
+        /// - `nombre`: Nombre del producto.
+        /// - `descripcion`: Descripción del producto.
+        /// - `categoria`: Nombre de la categoría.
+        /// - `stock`: Cantidad disponible.
+        /// - `atributos`: Valores para los atributos de la categoría y de sus categorías padre.
         ///
         /// # Requisitos
         /// - El caller debe estar registrado y tener rol de `Vendedor`.
         ///
         /// # Errores
-        /// - `CuentaNoRegistrada` si el caller no está registrado.
-        /// - `UsuarioSinRoles` si el caller no tiene el rol adecuado.
-        /// - `ProductoInexistente` si el producto no existe.
+        /// - `UsuarioNoExiste` si el caller no está registrado.
+        /// - `UsuarioNoEsVendedor` si el caller no tiene el rol adecuado.
+        /// - `CategoriaInexistente` si la categoría no existe.
+        /// - `AtributoFaltante`, `AtributoDesconocido`, `AtributoDuplicado` o
+        ///   `TipoDeAtributoInvalido` si los atributos no coinciden con los de la categoría.
+        /// - `ProductoYaExistente` si ya existe un producto con ese nombre y categoría.
         #[ink(message)]
         pub fn crear_producto(
             &mut self,
@@ -283,9 +200,17 @@ mod contract {
             descripcion: String,
             categoria: String,
             stock: u32,
+            atributos: Vec<Atributo>,
         ) -> Result<(), ErroresContrato> {
             let id_vendedor = self.env().caller();
-            self._crear_producto(id_vendedor, nombre, descripcion, categoria, stock)
+            self._crear_producto_con_atributos(
+                id_vendedor,
+                nombre,
+                descripcion,
+                categoria,
+                stock,
+                atributos,
+            )
         }
 
         /// Crea una orden de compra sobre una publicación activa.
@@ -355,27 +280,95 @@ mod contract {
             Ok(String::from("La orden fue recibida correctamente"))
         }
 
-        /// Cancela una orden pendiente o aún no enviada.
+        /// Pide o confirma la cancelación de una orden que todavía no fue recibida.
+        ///
+        /// La cancelación necesita a las dos partes: la primera que la llama deja registrada
+        /// la solicitud y la orden queda cancelada cuando la contraparte la llama dentro de
+        /// `PLAZO_CANCELACION`. Si la solicitud vence, la llamada cuenta como un pedido nuevo.
+        /// Al cancelarse, las unidades vuelven a la publicación.
         ///
         /// # Parámetros
         /// - `id_orden`: ID de la orden a cancelar.
         ///
         /// # Requisitos
-        /// - El caller debe estar registrado y tener rol de `Comprador`.
+        /// - El caller debe ser el comprador o el vendedor de la orden.
         ///
         /// # Errores
         /// - `OrdenInexistente` si la orden no existe.
+        /// - `UsuarioNoParticipaDeLaOrden` si el caller no es comprador ni vendedor de la orden.
+        /// - `OrdenYaRecibida` si la orden ya fue recibida.
         /// - `OrdenYaCancelada` si ya fue cancelada previamente.
-        /// - `CuentaNoRegistrada` si el caller no está registrado.
-        /// - `UsuarioSinRoles` si no tiene el rol correspondiente.
+        /// - `CancelacionYaSolicitada` si el caller ya pidió la cancelación y sigue vigente.
+        #[ink(message)]
+        pub fn cancelar_producto(&mut self, id_orden: u32) -> Result<String, ErroresContrato> {
+            let caller = self.env().caller();
+            let ahora = self.env().block_timestamp();
+            match self._cancelar_orden(id_orden, caller, ahora)? {
+                EstadoOrden::Cancelada => Ok(String::from("La orden fue cancelada correctamente")),
+                _ => Ok(String::from(
+                    "La cancelacion fue solicitada, falta la confirmacion de la otra parte",
+                )),
+            }
+        }
+
+        /// Configura la cuenta que da fe de los mails de los usuarios, o la quita con `None`.
         ///
-        // #[ink(message)]
-        // pub fn cancelar_producto(&mut self, id_orden: u32) -> Result<String, ErroresContrato> {
-        //     // Compruebo que el usuario existe y posee rol de vendedor
-        //     self._usuario_con_rol(COMPRADOR)?; //TODO: me parece que habia una logica adicional en el cancelar... CHEQUEAR
-        //     self._cancelar_orden(id_orden)?;
-        //     Ok(String::from("La orden fue cancelada correctamente"))
-        // }
+        /// # Errores
+        /// - `NoEsAdmin` si el caller no es quien desplegó el contrato.
+        #[ink(message)]
+        pub fn configurar_verificador(
+            &mut self,
+            verificador: Option<AccountId>,
+        ) -> Result<(), ErroresContrato> {
+            if self.env().caller() != self.admin {
+                return Err(ErroresContrato::NoEsAdmin);
+            }
+            self.verificador = verificador;
+            Ok(())
+        }
+
+        /// Devuelve la cuenta verificadora de mails, si hay una configurada.
+        #[ink(message)]
+        pub fn get_verificador(&self) -> Option<AccountId> {
+            self.verificador
+        }
+
+        /// Atestación del verificador: da fe de que el mail con hash `hash_mail` pertenece a `cuenta`.
+        /// La transacción firmada por la cuenta verificadora es la atestación; el usuario queda verificado.
+        ///
+        /// # Parámetros
+        /// - `cuenta`: AccountId del usuario a verificar.
+        /// - `hash_mail`: `hash_mail` del mail que el verificador comprobó fuera de la cadena.
+        ///
+        /// # Errores
+        /// - `NoEsVerificador` si el caller no es el verificador configurado.
+        /// - `UsuarioNoExiste` si la cuenta no está registrada.
+        /// - `MailNoCoincide` si el hash no corresponde al mail registrado por la cuenta.
+        #[ink(message)]
+        pub fn verificar_mail(
+            &mut self,
+            cuenta: AccountId,
+            hash_mail: HashMail,
+        ) -> Result<(), ErroresContrato> {
+            if Some(self.env().caller()) != self.verificador {
+                return Err(ErroresContrato::NoEsVerificador);
+            }
+            self._verificar_mail(cuenta, hash_mail)
+        }
+
+        /// Como vendedor, exige (o deja de exigir) que los compradores tengan el mail verificado
+        /// para poder crear órdenes sobre sus publicaciones.
+        ///
+        /// # Errores
+        /// - `UsuarioNoExiste` si el caller no está registrado.
+        /// - `UsuarioNoEsVendedor` si el caller no tiene rol de `Vendedor`.
+        #[ink(message)]
+        pub fn exigir_compradores_verificados(
+            &mut self,
+            exigir: bool,
+        ) -> Result<(), ErroresContrato> {
+            self._exigir_compradores_verificados(self.env().caller(), exigir)
+        }
 
         ///FALTA DOCUMENTAR PARA ROL
         #[ink(message)]
@@ -395,6 +388,18 @@ mod contract {
             self._listar_productos()
         }
 
+        /// Devuelve los productos de una categoría, incluidos los de sus subcategorías.
+        ///
+        /// # Errores
+        /// - `CategoriaInexistente` si la categoría no existe.
+        #[ink(message)]
+        pub fn listar_productos_por_categoria(
+            &self,
+            categoria: String,
+        ) -> Result<Vec<Producto>, ErroresContrato> {
+            self._listar_productos_por_categoria(&categoria)
+        }
+
         /// Devuelve una lista de todas las publicaciones en el contrato.
         #[ink(message)]
         pub fn listar_publicaciones(&self) -> Vec<Publicacion> {
@@ -427,19 +432,24 @@ mod contract {
     }
 
     impl GestionProducto for Sistema {
-        fn _crear_producto(
+        type Error = ErroresContrato;
+
+        fn _crear_producto_con_atributos(
             &mut self,
             id_vendedor: AccountId,
             nombre: String,
             descripcion: String,
             categoria: String,
             stock: u32,
+            atributos: Vec<Atributo>,
         ) -> Result<(), ErroresContrato> {
             let id = self.productos.len();
             let usuario = self.get_user(&id_vendedor)?;
             if usuario.has_role(VENDEDOR) {
                 let id_cat = self.get_categoria_by_name(&categoria)?;
-                let producto = Producto::new(id, id_vendedor, nombre, descripcion, id_cat, stock);
+                validar_atributos(&self.esquema_de_atributos(id_cat)?, &atributos)?;
+                let producto = Producto::new(id, id_vendedor, nombre, descripcion, id_cat, stock)
+                    .con_atributos(atributos);
                 if !self.producto_existe(&producto) {
                     self.productos.push(&producto);
                     Ok(())
@@ -451,6 +461,18 @@ mod contract {
             }
         }
 
+        fn _listar_productos_por_categoria(
+            &self,
+            categoria: &str,
+        ) -> Result<Vec<Producto>, ErroresContrato> {
+            let categorias = self.categorias_descendientes(self.get_categoria_by_name(categoria)?);
+            Ok(self
+                ._listar_productos()
+                .into_iter()
+                .filter(|producto| categorias.contains(&producto.get_categoria()))
+                .collect())
+        }
+
         fn producto_existe(&self, p: &Producto) -> bool {
             for i in 0..self.productos.len() {
                 if let Some(prod) = self.productos.get(i) {
@@ -471,10 +493,7 @@ mod contract {
                 .productos
                 .get(id)
                 .ok_or(ErroresContrato::ProductoInexistente)?; //misma duda que en get_id_vendedor
-            producto.stock = producto
-                .stock
-                .checked_sub(cantidad)
-                .ok_or(ErroresContrato::StockProductoInsuficiente)?;
+            producto.descontar_stock(cantidad)?;
             self.productos.set(id, &producto);
             Ok(())
         }
@@ -491,6 +510,8 @@ mod contract {
     }
 
     impl GestionUsuario for Sistema {
+        type Error = ErroresContrato;
+
         fn _registrar_usuario(
             &mut self,
             id: AccountId,
@@ -501,17 +522,18 @@ mod contract {
             if self.get_user(&id).is_ok() {
                 return Err(ErroresContrato::UsuarioYaExistente);
             };
-            //self.get_usuario_by_mail(&mail)?;
-            if self.get_usuario_by_mail(&mail).is_ok() {
+            let hash = hash_mail(&mail);
+            if self.m_mails.contains(hash) {
                 return Err(ErroresContrato::MailYaExistente);
             };
 
             // Instancio nuevo usuario
             let usuario = Usuario::new(id, nombre, mail);
 
-            // Inserto el usuario tanto en el Mapping como en el Vec
+            // Inserto el usuario tanto en el Mapping como en el Vec, y reservo su mail
             self.m_usuarios.insert(id, &usuario);
             self.v_usuarios.push(&id);
+            self.m_mails.insert(hash, &id);
 
             Ok(String::from("El usuario fue registrado correctamente"))
         }
@@ -535,37 +557,53 @@ mod contract {
             resultado
         }
 
-        /// Verifica si ya existe un usuario con el mail dado
+        /// Busca al usuario dueño del mail dado a través del índice de mails
         fn get_usuario_by_mail(&self, mail: &str) -> Result<Usuario, ErroresContrato> {
-            for i in 0..self.v_usuarios.len() {
-                let account_id = self
-                    .v_usuarios
-                    .get(i)
-                    .ok_or(ErroresContrato::IndiceInvalido)?;
-
-                let usuario = self
-                    .m_usuarios
-                    .get(account_id)
-                    .ok_or(ErroresContrato::AccountIdInvalida)?;
-                if usuario.mail == mail {
-                    return Ok(usuario);
-                };
-            }
-            Err(ErroresContrato::MailInexistente)
+            let account_id = self
+                .m_mails
+                .get(hash_mail(mail))
+                .ok_or(ErroresContrato::MailInexistente)?;
+            self.m_usuarios
+                .get(account_id)
+                .ok_or(ErroresContrato::AccountIdInvalida)
         }
 
         fn _asignar_rol(&mut self, id: AccountId, rol: Rol) -> Result<String, ErroresContrato> {
             let mut usuario = self.get_user(&id)?;
-            if usuario.has_role(rol.clone()) {
-                return Err(ErroresContrato::AlreadyHasRol);
-            }
-            usuario.roles.push(rol);
+            usuario.agregar_rol(rol)?;
             self.m_usuarios.insert(id, &usuario);
             Ok(String::from("rol agregado correctamente"))
         }
+
+        fn _verificar_mail(
+            &mut self,
+            id: AccountId,
+            hash_mail: HashMail,
+        ) -> Result<(), ErroresContrato> {
+            let mut usuario = self.get_user(&id)?;
+            if self.m_mails.get(hash_mail) != Some(id) {
+                return Err(ErroresContrato::MailNoCoincide);
+            }
+            usuario.marcar_verificado();
+            self.m_usuarios.insert(id, &usuario);
+            Ok(())
+        }
+
+        fn _exigir_compradores_verificados(
+            &mut self,
+            id: AccountId,
+            exigir: bool,
+        ) -> Result<(), ErroresContrato> {
+            let mut usuario = self.get_user(&id)?;
+            usuario.set_exige_verificados(exigir)?;
+            self.m_usuarios.insert(id, &usuario);
+            Ok(())
+        }
     }
 
     impl GestionOrden for Sistema {
+        type Error = ErroresContrato;
+
         fn _crear_orden(
             &mut self,
             id_pub: u32,
@@ -581,6 +619,7 @@ mod contract {
                 .checked_mul(cantidad as u128)
                 .ok_or(ErroresContrato::ErrorMultiplicacion)?;
             if comprador.has_role(COMPRADOR) && vendedor.has_role(VENDEDOR) {
+                comprador.puede_comprarle_a(&vendedor)?;
                 if cantidad != 0 {
                     self.descontar_stock_publicacion(id_pub, cantidad)?;
                     let orden = Orden::new(
@@ -617,14 +656,9 @@ mod contract {
                 .get(id_orden)
                 .ok_or(ErroresContrato::OrdenInexistente)?;
 
-            match orden.status {
-                EstadoOrden::Pendiente => {
-                    orden.status = EstadoOrden::Enviada;
-                    self.ordenes.set(id_orden, &orden);
-                    Ok(())
-                }
-                _ => Err(ErroresContrato::OrdenNoPendiente),
-            }
+            orden.enviar()?;
+            self.ordenes.set(id_orden, &orden);
+            Ok(())
         }
 
         fn _recibir_orden(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
@@ -633,34 +667,34 @@ mod contract {
                 .get(id_orden)
                 .ok_or(ErroresContrato::OrdenInexistente)?;
 
-            match orden.status {
-                EstadoOrden::Enviada => {
-                    orden.status = EstadoOrden::Recibida;
-                    self.ordenes.set(id_orden, &orden);
-                    Ok(())
-                }
-                _ => Err(ErroresContrato::OrdenNoEnviada),
-            }
+            orden.recibir()?;
+            self.ordenes.set(id_orden, &orden);
+            Ok(())
         }
 
-        // fn _cancelar_orden(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
-        //     let mut orden = self
-        //         .ordenes
-        //         .get(id_orden)
-        //         .ok_or(ErroresContrato::OrdenInexistente)?;
+        fn _cancelar_orden(
+            &mut self,
+            id_orden: u32,
+            id_usuario: AccountId,
+            ahora: Timestamp,
+        ) -> Result<EstadoOrden, ErroresContrato> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
 
-        //     match orden.status {
-        //         EstadoOrden::Enviada => Err(ErroresContrato::OrdenNoEnviada),
-        //         _ => {
-        //             orden.status = EstadoOrden::Recibida;
-        //             self.ordenes.set(id_orden, &orden);
-        //             Ok(())
-        //         }
-        //     }
-        // }
+            if orden.cancelar(id_usuario, ahora)? {
+                // Las unidades ya habian salido del producto al publicarse, solo vuelven a la publicacion
+                self.reponer_stock_publicacion(orden.get_id_publicacion(), orden.get_cantidad())?;
+            }
+            self.ordenes.set(id_orden, &orden);
+            Ok(orden.get_status())
+        }
     }
 
     impl GestionPublicacion for Sistema {
+        type Error = ErroresContrato;
+
         fn _crear_publicacion(
             &mut self,
             id_producto: u32,
@@ -700,10 +734,21 @@ mod contract {
                 .publicaciones
                 .get(id_pub)
                 .ok_or(ErroresContrato::PublicacionNoExiste)?;
-            publicacion.stock = publicacion
-                .stock
-                .checked_sub(cantidad)
-                .ok_or(ErroresContrato::StockPublicacionInsuficiente)?;
+            publicacion.descontar_stock(cantidad)?;
+            self.publicaciones.set(id_pub, &publicacion);
+            Ok(())
+        }
+
+        fn reponer_stock_publicacion(
+            &mut self,
+            id_pub: u32,
+            cantidad: u32,
+        ) -> Result<(), ErroresContrato> {
+            let mut publicacion = self
+                .publicaciones
+                .get(id_pub)
+                .ok_or(ErroresContrato::PublicacionNoExiste)?;
+            publicacion.reponer_stock(cantidad)?;
             self.publicaciones.set(id_pub, &publicacion);
             Ok(())
         }
@@ -712,7 +757,7 @@ mod contract {
         fn get_id_vendedor(&self, id_pub: u32) -> Result<AccountId, ErroresContrato> {
             if let Some(publicacion) = self.publicaciones.get(id_pub) {
                 //get saca el elemento del vector (hay que volver a insertarlo o no?)
-                Ok(publicacion.id_user)
+                Ok(publicacion.get_id_vendedor())
             } else {
                 Err(ErroresContrato::PublicacionNoExiste)
             }
@@ -721,7 +766,7 @@ mod contract {
         /// Recibe un ID de una publicacion y devuelve su stock
         fn get_precio_unitario(&self, id_pub: u32) -> Result<Balance, ErroresContrato> {
             if let Some(publicacion) = self.publicaciones.get(id_pub) {
-                Ok(publicacion.precio_unitario)
+                Ok(publicacion.get_precio_unitario())
             } else {
                 Err(ErroresContrato::PublicacionNoExiste)
             }
@@ -729,17 +774,38 @@ mod contract {
     }
 
     impl GestionCategoria for Sistema {
-        fn _registrar_categoria(&mut self, nombre: String) -> Result<String, ErroresContrato> {
+        type Error = ErroresContrato;
+
+        fn _registrar_subcategoria(
+            &mut self,
+            nombre: String,
+            padre: Option<String>,
+            atributos: Vec<DefinicionAtributo>,
+        ) -> Result<String, ErroresContrato> {
             if self.get_categoria_by_name(&nombre).is_ok() {
                 return Err(ErroresContrato::CategoriaYaExistente);
             }
 
+            // La categoria padre tiene que existir y no puede repetir sus atributos
+            let (id_padre, heredados) = match padre {
+                Some(padre) => {
+                    let id_padre = self
+                        .get_categoria_by_name(&padre)
+                        .map_err(|_| ErroresContrato::CategoriaPadreInexistente)?;
+                    (Some(id_padre), self.esquema_de_atributos(id_padre)?)
+                }
+                None => (None, Vec::new()),
+            };
+            validar_definiciones(&heredados, &atributos)?;
+
             // Agregar categoria
             if self.categorias.len() == u32::MAX {
                 return Err(ErroresContrato::MaxCategoriasAlcanzado);
             }
             let id = self.categorias.len();
-            let nueva_categoria = Categoria::new(id, self.clean_cat_name(&nombre)?);
+            let nueva_categoria = Categoria::new(id, self.clean_cat_name(&nombre)?)
+                .con_padre(id_padre)
+                .con_atributos(atributos);
             self.categorias.push(&nueva_categoria);
 
             Ok(String::from("la categoria fue registrada correctamente"))
@@ -755,11 +821,17 @@ mod contract {
             resultado
         }
 
-        fn get_categoria_by_name(&self, nombre: &String) -> Result<u32, ErroresContrato> {
+        fn get_categoria(&self, id: u32) -> Result<Categoria, ErroresContrato> {
+            self.categorias
+                .get(id)
+                .ok_or(ErroresContrato::CategoriaInexistente)
+        }
+
+        fn get_categoria_by_name(&self, nombre: &str) -> Result<u32, ErroresContrato> {
             let nombre_limpio = self.clean_cat_name(nombre)?;
             for i in 0..self.categorias.len() {
                 if let Some(categoria) = self.categorias.get(i) {
-                    if categoria.nombre == nombre_limpio {
+                    if categoria.get_nombre() == nombre_limpio {
                         return Ok(i);
                     }
                 }
@@ -767,290 +839,16 @@ mod contract {
             Err(ErroresContrato::CategoriaInexistente)
         }
 
-        fn clean_cat_name(&self, nombre: &String) -> Result<String, ErroresContrato> {
-            let mut limpio = String::from(nombre.to_lowercase().trim());
-            limpio.truncate(100);
-            if !limpio.is_empty() {
-                Ok(limpio)
-            } else {
-                Err(ErroresContrato::NombreCategoriaVacio)
-            }
-        }
-    }
-
-    /// Estructuras relacionadas a Usuario
-
-    /// Roles existentes
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[cfg_attr(feature = "std", derive(StorageLayout))]
-    #[derive(PartialEq, Clone)]
-    pub enum Rol {
-        Comprador,
-        Vendedor,
-    }
-
-    /// Estructura que define al Usuario
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(Clone)]
-    pub struct Usuario {
-        id: AccountId,
-        nombre: String,
-        mail: String,
-        rating: Rating,
-        roles: Vec<Rol>,
-    }
-
-    impl Usuario {
-        ///Crea un nuevo Usuario
-        pub fn new(id: AccountId, nombre: String, mail: String) -> Usuario {
-            Usuario {
-                id,
-                nombre,
-                mail,
-                rating: Rating::new(),
-                roles: Vec::new(),
-            }
-        }
-
-        /// Remueve el rol del usuario si existe
-        // pub fn remover_rol(&mut self, rol: Rol) -> Result<(), ErroresContrato> {
-        //     if self.has_role(rol) {
-        //         self.roles.retain(|rol| *rol != COMPRADOR);
-        //         Ok(())
-        //     } else {
-        //         Err(ErroresContrato::UsuarioNoTieneRol)
-        //     }
-        // }
-
-        /// Devuelve true si el usuario contiene el rol pasado por parametro
-        pub fn has_role(&self, rol: Rol) -> bool {
-            self.roles.contains(&rol)
-        }
-
-        /// Devuelve el nombre del usuario
-        pub fn get_name(&self) -> String {
-            self.nombre.clone()
-        }
-
-        /// Devuelve el email del usuario
-        pub fn get_mail(&self) -> String {
-            self.mail.clone()
+        fn clean_cat_name(&self, nombre: &str) -> Result<String, ErroresContrato> {
+            limpiar_nombre_categoria(nombre)
         }
-
-        /// Devuelve el AccountId del usuario
-        pub fn get_id(&self) -> AccountId {
-            self.id.clone()
-        }
-    }
-
-    /// Estructura correspondiente al rating de un usuario
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(Clone)]
-    struct Rating {
-        calificacion_comprador: (u32, u32), //cant de compras, valor cumulativo de todas las calificaciones
-        calificacion_vendedor: (u32, u32),
-    }
-
-    ///Métodos de usuario
-    impl Rating {
-        ///crea un rating
-        fn new() -> Rating {
-            Rating {
-                calificacion_comprador: (0, 0),
-                calificacion_vendedor: (0, 0),
-            }
-        }
-    }
-
-    /// Estructuras relacionadas a producto
-
-    /// Categorias
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    pub struct Categoria {
-        id: u32,
-        nombre: String,
-    }
-
-    impl Categoria {
-        pub fn new(id: u32, nombre: String) -> Self {
-            Self { id, nombre }
-        }
-    }
-
-    ///Estructura de un producto
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(PartialEq, Debug)]
-    pub struct Producto {
-        id: u32,
-        id_vendedor: AccountId,
-        nombre: String,
-        descripcion: String,
-        categoria: u32,
-        stock: u32,
-    }
-
-    impl Producto {
-        ///Crea un producto nuevo dado los parametros
-        pub fn new(
-            id: u32,
-            id_vendedor: AccountId,
-            nombre: String,
-            descripcion: String,
-            categoria: u32,
-            stock: u32,
-        ) -> Producto {
-            //TODO: verificar que stock>0 y precio>0 y nombre y desc sean validos
-            Producto {
-                id,
-                id_vendedor,
-                nombre,
-                descripcion,
-                categoria,
-                stock,
-            }
-        }
-
-        ///Compara un producto self con un producto pasado por parametro
-        pub fn eq(&self, p: &Producto) -> bool {
-            if self.nombre == p.nombre && self.categoria == p.categoria {
-                return true;
-            }
-            false
-        }
-    }
-
-    // impl ControlStock for Producto {
-    //     fn get_cantidad(&self) -> u32 {
-    //         self.stock
-    //     }
-
-    //     fn set_cantidad(&mut self, nueva: u32) {
-    //         self.stock = nueva;
-    //     }
-    // }
-
-    ///LOGICA DE PUBLICACION
-
-    ///Estructura de publicacion
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(PartialEq, Debug, Clone)]
-    pub struct Publicacion {
-        id: u32,
-        id_prod: u32,       //id del producto que contiene
-        id_user: AccountId, //id del user que publica
-        stock: u32,
-        precio_unitario: Balance,
-        activa: bool,
-    }
-
-    impl Publicacion {
-        pub fn stock(&self) -> u32 {
-            self.stock
-        }
-
-        // pub fn actualizar_stock(&mut self, delta: i32) -> Result<(), ErroresContrato> {}
-
-        pub fn new(
-            id: u32,
-            id_producto: u32,
-            id_user: AccountId,
-            stock: u32,
-            precio_unitario: Balance,
-        ) -> Publicacion {
-            Publicacion {
-                id,
-                id_prod: id_producto,
-                id_user,
-                stock,
-                precio_unitario,
-                activa: true,
-            }
-        }
-    }
-
-    // impl ControlStock for Publicacion {
-    //     fn get_cantidad(&self) -> u32 {
-    //         self.stock
-    //     }
-
-    //     fn set_cantidad(&mut self, nueva: u32) {
-    //         self.stock = nueva;
-    //     }
-    // }
-
-    ///Estructuras y logica de Orden
-    ///Posibles estados de una Ordem
-    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    pub enum EstadoOrden {
-        Pendiente,
-        Enviada,   //solo lo puede modificar el vendedor
-        Recibida,  //solo lo puede modificar el comprador
-        Cancelada, //tienen que estar ambos de acuerdo y tiene que estar en estado pendiente
-    }
-
-    ///Estructura de orden
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(Clone)]
-    pub struct Orden {
-        //info de la orden
-        id: u32,
-        id_publicacion: u32,
-        id_vendedor: AccountId,
-        id_comprador: AccountId,
-        status: EstadoOrden,
-        cantidad: u32,
-        precio_total: Balance,
-        cal_vendedor: Option<u8>,  //calificacion que recibe el vendedor
-        cal_comprador: Option<u8>, //calificacion que recibe el comprador
-    }
-
-    impl Orden {
-        ///crea una nueva orden
-        pub fn new(
-            id: u32,
-            id_publicacion: u32,
-            id_vendedor: AccountId,
-            id_comprador: AccountId,
-            cantidad: u32,
-            precio_total: Balance,
-        ) -> Orden {
-            Orden {
-                id,
-                id_publicacion,
-                id_vendedor,
-                id_comprador,
-                status: EstadoOrden::Pendiente,
-                cantidad,
-                precio_total,
-                cal_vendedor: None,
-                cal_comprador: None,
-            }
-        }
-        pub fn get_cantidad(&self) -> u32 {
-            self.cantidad
-        }
-
-        pub fn get_status(&self) -> EstadoOrden {
-            self.status
-        }
-        //pub fn cambiar_estado
-        //fn set_enviada() //solamente puede ser modificada por el vendedor
-        //fn set_recibida() //solamente puede ser modificada por el comprador
-        //fn cancelar() //necesitan estar de acuerdo ambos
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::contract::*;
+    use marketplace_core::{TipoAtributo, ValorAtributo, PLAZO_CANCELACION};
 
     use ink::{
         env::{test::set_callee, DefaultEnvironment},
@@ -1058,6 +856,9 @@ mod tests {
     };
     use ink_e2e::{account_id, AccountKeyring};
 
+    // Suite compartida con `SistemaEnMemoria`: el contrato tiene que comportarse igual.
+    marketplace_core::suite_gestion!(ink::test, Sistema::new());
+
     fn setup_sistema() -> Sistema {
         Sistema::new()
     }
@@ -1518,28 +1319,28 @@ mod tests {
     fn test_categoria_indice_correcto_por_nombre() {
         let sist = contrato_con_categorias_cargada();
         assert_eq!(
-            sist.get_categoria_by_name(&"categoria 9".to_string()),
+            sist.get_categoria_by_name("categoria 9"),
             Ok(9),
             "deberia devolver el indice correcto"
         );
         assert_eq!(
-            sist.get_categoria_by_name(&"categoria 3".to_string()),
+            sist.get_categoria_by_name("categoria 3"),
             Ok(3),
             "deberia devolver el indice correcto"
         );
         assert_eq!(
-            sist.get_categoria_by_name(&"      categoria 4       ".to_string()),
+            sist.get_categoria_by_name("      categoria 4       "),
             Ok(4),
             "deberia devolver el indice correcto incluso con whitespace"
         );
         assert_eq!(
-            sist.get_categoria_by_name(&"cAtEGoRiA 5".to_string()),
+            sist.get_categoria_by_name("cAtEGoRiA 5"),
             Ok(5),
             "deberia devolver el indice correcto incluso con mayusculas"
         );
 
         assert_eq!(
-            sist.get_categoria_by_name(&"Electrodomesticos".to_string()),
+            sist.get_categoria_by_name("Electrodomesticos"),
             Err(ErroresContrato::CategoriaInexistente),
             "deberia devolver que no encuentra la categoria"
         );
@@ -1549,7 +1350,7 @@ mod tests {
     fn test_categoria_get_categoria_whitespaces() {
         let sist = contrato_con_categorias_cargada();
         assert_eq!(
-            sist.get_categoria_by_name(&"      categoria 4       ".to_string()),
+            sist.get_categoria_by_name("      categoria 4       "),
             Ok(4),
             "deberia devolver el indice correcto incluso con whitespace"
         );
@@ -1559,7 +1360,7 @@ mod tests {
     fn test_categoria_get_categoria_case_sensitivity() {
         let sist = contrato_con_categorias_cargada();
         assert_eq!(
-            sist.get_categoria_by_name(&"cAtEGoRiA 5".to_string()),
+            sist.get_categoria_by_name("cAtEGoRiA 5"),
             Ok(5),
             "deberia devolver el indice correcto incluso con mayusculas"
         );
@@ -1569,7 +1370,7 @@ mod tests {
     fn test_categoria_get_categoria_inexistente() {
         let sist = contrato_con_categorias_cargada();
         assert_eq!(
-            sist.get_categoria_by_name(&"Electrodomesticos".to_string()),
+            sist.get_categoria_by_name("Electrodomesticos"),
             Err(ErroresContrato::CategoriaInexistente),
             "deberia devolver que no encuentra la categoria"
         );
@@ -1579,7 +1380,7 @@ mod tests {
     fn test_categoria_clean_name() {
         let sist = setup_sistema();
         assert_eq!(
-            sist.clean_cat_name(&"Electrodomésticos".to_string()),
+            sist.clean_cat_name("Electrodomésticos"),
             Ok("electrodomésticos".to_string())
         );
     }
@@ -1588,7 +1389,7 @@ mod tests {
     fn test_categoria_clean_name_whitespaces() {
         let sist = setup_sistema();
         assert_eq!(
-            sist.clean_cat_name(&"      cocina        ".to_string()),
+            sist.clean_cat_name("      cocina        "),
             Ok("cocina".to_string())
         );
     }
@@ -1597,7 +1398,7 @@ mod tests {
     fn test_categoria_clean_name_empty() {
         let sist = setup_sistema();
         assert_eq!(
-            sist.clean_cat_name(&"".to_string()),
+            sist.clean_cat_name(""),
             Err(ErroresContrato::NombreCategoriaVacio)
         );
     }
@@ -1605,7 +1406,7 @@ mod tests {
     #[ink::test]
     fn test_categoria_clean_name_max_characters() {
         let sist = setup_sistema();
-        assert_eq!(sist.clean_cat_name(&"
+        assert_eq!(sist.clean_cat_name("
             You know what they call a  Quarter Pounder with Cheese in Paris?
 
             [JULES]
@@ -1630,7 +1431,7 @@ mod tests {
             Le big Mac! Ahhaha, what do they call a Whopper?
 
             [VINCENT]
-            I dunno, I didn't go into a Burger King.".to_string()
+            I dunno, I didn't go into a Burger King."
         ),
             Ok("you know what they call a  quarter pounder with cheese in paris?
 
@@ -2153,25 +1954,38 @@ mod tests {
         let (comprador, vendedor) = build_testing_accounts();
 
         // Registro usuarios y roles
-        contrato._registrar_usuario(vendedor, "Santiago".to_string(), "ST96@mail.com".to_string()).unwrap();
+        contrato
+            ._registrar_usuario(
+                vendedor,
+                "Santiago".to_string(),
+                "ST96@mail.com".to_string(),
+            )
+            .unwrap();
         contrato._asignar_rol(vendedor, Rol::Vendedor).unwrap();
-        contrato._registrar_usuario(comprador, "Juan".to_string(), "JT11@mail.com".to_string()).unwrap();
+        contrato
+            ._registrar_usuario(comprador, "Juan".to_string(), "JT11@mail.com".to_string())
+            .unwrap();
         contrato._asignar_rol(comprador, Rol::Comprador).unwrap();
 
         //Creo publicación
         contrato._registrar_categoria("Libros".to_string()).unwrap();
-        contrato._crear_producto(vendedor, "Rust".to_string(), "Desc".to_string(), "Libros".to_string(), 5).unwrap();
+        contrato
+            ._crear_producto(
+                vendedor,
+                "Rust".to_string(),
+                "Desc".to_string(),
+                "Libros".to_string(),
+                5,
+            )
+            .unwrap();
         contrato._crear_publicacion(0, vendedor, 5, 100).unwrap();
 
-
         ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
         contrato.crear_orden(0, 1).unwrap();
 
- 
         ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
         contrato.enviar_producto(0).unwrap();
 
-
         ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
         let res = contrato.recibir_producto(0);
 
@@ -2179,4 +1993,140 @@ mod tests {
         let orden = contrato.listar_ordenes()[0].clone();
         assert_eq!(orden.get_status(), EstadoOrden::Recibida);
     }
+
+    #[ink::test]
+    fn cancelar_producto_con_confirmacion_de_ambas_partes() {
+        let mut contrato = setup_sistema();
+        let (comprador, vendedor) = build_testing_accounts();
+
+        contrato
+            ._registrar_usuario(vendedor, "Ven".into(), "v@mail.com".into())
+            .unwrap();
+        contrato._asignar_rol(vendedor, VENDEDOR).unwrap();
+        contrato
+            ._registrar_usuario(comprador, "Com".into(), "c@mail.com".into())
+            .unwrap();
+        contrato._asignar_rol(comprador, COMPRADOR).unwrap();
+        contrato._registrar_categoria("Libros".into()).unwrap();
+        contrato
+            ._crear_producto(vendedor, "Rust".into(), "Desc".into(), "Libros".into(), 5)
+            .unwrap();
+        contrato._crear_publicacion(0, vendedor, 5, 100).unwrap();
+
+        set_caller(comprador);
+        contrato.crear_orden(0, 3).unwrap();
+
+        // El vendedor pide la cancelacion, el comprador la confirma antes de que venza
+        ink::env::test::set_block_timestamp::<DefaultEnvironment>(1_000);
+        set_caller(vendedor);
+        assert!(contrato.cancelar_producto(0).is_ok());
+        assert_eq!(
+            contrato.listar_ordenes()[0].get_status(),
+            EstadoOrden::Pendiente
+        );
+        assert_eq!(
+            contrato.cancelar_producto(0),
+            Err(ErroresContrato::CancelacionYaSolicitada)
+        );
+
+        ink::env::test::set_block_timestamp::<DefaultEnvironment>(1_000 + PLAZO_CANCELACION);
+        set_caller(comprador);
+        assert_eq!(
+            contrato.cancelar_producto(0),
+            Ok(String::from("La orden fue cancelada correctamente"))
+        );
+        assert_eq!(
+            contrato.listar_ordenes()[0].get_status(),
+            EstadoOrden::Cancelada
+        );
+        assert_eq!(contrato.listar_publicaciones()[0].stock(), 5);
+        assert_eq!(
+            contrato.listar_productos()[0],
+            Producto::new(0, vendedor, "Rust".into(), "Desc".into(), 0, 0)
+        );
+    }
+
+    #[ink::test]
+    fn crear_producto_en_subcategoria_con_atributos() {
+        let mut contrato = setup_sistema();
+        let (_, vendedor) = build_testing_accounts();
+        registrar_vendedor(&mut contrato, vendedor);
+        set_caller(vendedor);
+
+        let talle = DefinicionAtributo::new("talle".into(), TipoAtributo::Numero);
+        contrato
+            .registrar_subcategoria("Ropa".into(), None, Vec::new())
+            .unwrap();
+        contrato
+            .registrar_subcategoria("Calzado".into(), Some("Ropa".into()), vec![talle])
+            .unwrap();
+
+        assert_eq!(
+            contrato.crear_producto(
+                "Zapatilla".into(),
+                "Desc".into(),
+                "Calzado".into(),
+                3,
+                Vec::new()
+            ),
+            Err(ErroresContrato::AtributoFaltante)
+        );
+        let atributos = vec![Atributo::new("talle".into(), ValorAtributo::Numero(42))];
+        assert!(contrato
+            .crear_producto(
+                "Zapatilla".into(),
+                "Desc".into(),
+                "Calzado".into(),
+                3,
+                atributos
+            )
+            .is_ok());
+        assert!(contrato
+            .crear_producto("Remera".into(), "Desc".into(), "Ropa".into(), 3, Vec::new())
+            .is_ok());
+
+        assert_eq!(
+            contrato
+                .listar_productos_por_categoria("ropa".into())
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            contrato
+                .listar_productos_por_categoria("calzado".into())
+                .unwrap()[0]
+                .get_atributos()[0]
+                .get_valor(),
+            &ValorAtributo::Numero(42)
+        );
+    }
+
+    #[ink::test]
+    fn solo_el_verificador_configurado_verifica_mails() {
+        let admin = account_id(AccountKeyring::Charlie);
+        set_caller(admin);
+        let mut contrato = setup_sistema();
+        let (comprador, verificador) = build_testing_accounts();
+        registrar_comprador(&mut contrato, comprador);
+        let hash = hash_mail("comprador@gmail.com");
+
+        set_caller(verificador);
+        assert_eq!(
+            contrato.configurar_verificador(Some(verificador)),
+            Err(ErroresContrato::NoEsAdmin)
+        );
+        assert_eq!(
+            contrato.verificar_mail(comprador, hash),
+            Err(ErroresContrato::NoEsVerificador)
+        );
+
+        set_caller(admin);
+        assert!(contrato.configurar_verificador(Some(verificador)).is_ok());
+        assert_eq!(contrato.get_verificador(), Some(verificador));
+
+        set_caller(verificador);
+        assert!(contrato.verificar_mail(comprador, hash).is_ok());
+        assert!(contrato.get_user(&comprador).unwrap().esta_verificado());
+    }
 }
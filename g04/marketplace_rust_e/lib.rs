@@ -5,7 +5,7 @@ mod market_place {
     // use core::char::CharTryFromError;
     use ink::prelude::string::String;
     use ink::prelude::string::ToString;
-    // use ink::prelude::vec::Vec;
+    use ink::prelude::vec::Vec;
     use ink::storage::Mapping;
     //use ink_e2e::sr25519::PublicKey;
     //use ink_e2e::subxt_signer::bip39::serde::de::value::Error;
@@ -51,6 +51,12 @@ mod market_place {
     /// - `Enviado`: Vendedor ha enviado el producto
     /// - `Recibido`: Comprador ha recibido y confirmado el producto
     /// - `Cancelada`: Orden cancelada por alguna de las partes
+    /// - `EnviadoParcialmente`: Vendedor despachó parte de las unidades en uno o más envíos
+    ///
+    /// Las variantes nuevas van al final para que las existentes conserven su índice SCALE
+    /// (el que ven los clientes que decodifican el estado). Esto no hace compatibles a las
+    /// `Orden` guardadas antes de que se agregaran `almacenes` y `envios`: esas no se pueden
+    /// decodificar sin migrar el storage.
     #[derive(Debug, Clone, PartialEq, Eq, ink::scale::Encode, ink::scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
@@ -59,6 +65,7 @@ mod market_place {
         Enviado,
         Recibido,
         Cancelada,
+        EnviadoParcialmente,
     }
 
     /// ## Errores del Marketplace
@@ -96,7 +103,41 @@ mod market_place {
         ProductoYaPoseeDeposito,
         Overflow, // Error para manejar overflow en cálculos aritméticos
         DepositoNoEncontrado,
+        AlmacenNoExiste,
+        AlmacenYaExiste,
+        StockAlmacenInsuficiente,
+        TransferenciaInvalida,
+        EnvioNoExiste,
+        EnvioYaRecibido,
+        CantidadEnvioInvalida,
+        DatosEnvioInvalidos,
+        LimiteAlmacenesAlcanzado,
     }
+
+    /// Nombre del almacén que todo vendedor tiene por defecto.
+    ///
+    /// El stock inicial de un depósito se carga en este almacén y no hace falta registrarlo.
+    pub const ALMACEN_PRINCIPAL: &str = "principal";
+
+    /// Cantidad máxima de almacenes que puede registrar un vendedor, sin contar el principal.
+    pub const MAX_ALMACENES_POR_VENDEDOR: u32 = 10;
+
+    /// Cantidad máxima de publicaciones que devuelve una página de `buscar_publicaciones`.
+    pub const MAX_RESULTADOS_BUSQUEDA: u32 = 50;
+
+    /// Cantidad máxima de productos que se leen del índice por cada palabra de la consulta.
+    ///
+    /// El índice guarda todos los productos de cada palabra; al buscar se toman los
+    /// más recientes, así una palabra muy común no hace recorrer todo el catálogo.
+    pub const MAX_PRODUCTOS_POR_PALABRA: u32 = 100;
+
+    /// Cantidad máxima de publicaciones que revisa `buscar_publicaciones` con una consulta vacía.
+    pub const MAX_PUBLICACIONES_EXAMINADAS: u32 = 500;
+
+    /// Palabras demasiado comunes que no se indexan ni se usan al buscar.
+    pub const PALABRAS_IGNORADAS: [&str; 14] = [
+        "a", "al", "con", "de", "del", "el", "en", "la", "las", "los", "para", "por", "un", "una",
+    ];
     // Structs
 
     /// Representa a un usuario registrado en el marketplace.
@@ -245,6 +286,31 @@ mod market_place {
             nombre.to_lowercase().trim().to_string()
         }
 
+        // Helper tokenizar nombre de producto
+        /// Separa un texto en las palabras clave que usa el índice de búsqueda.
+        ///
+        /// El texto se normaliza y se corta en todo carácter que no sea alfanumérico;
+        /// las palabras repetidas se devuelven una sola vez y las de `PALABRAS_IGNORADAS` se descartan.
+        ///
+        /// # Parámetros
+        /// - `texto`: Nombre de producto o consulta a tokenizar.
+        ///
+        /// # Retorna
+        /// Las palabras clave, en el orden en que aparecen.
+        fn tokenizar_nombre(texto: &String) -> Vec<String> {
+            let normalizado = Producto::normalizar_nombre_producto(texto);
+            let mut tokens: Vec<String> = Vec::new();
+            for token in normalizado.split(|c: char| !c.is_alphanumeric()) {
+                if !token.is_empty()
+                    && !PALABRAS_IGNORADAS.contains(&token)
+                    && !tokens.iter().any(|t| t == token)
+                {
+                    tokens.push(token.to_string());
+                }
+            }
+            tokens
+        }
+
         // Helper validar descripcion de producto
         /// Valida si la descripción del producto es adecuada.
         ///
@@ -285,6 +351,7 @@ mod market_place {
     /// - `vendedor`: AccountId del vendedor
     /// - `stock_publicacion`: Cantidad disponible en esta publicación
     /// - `precio`: Precio del producto en la moneda nativa
+    /// - `reservas`: Unidades apartadas de cada almacén del vendedor para esta publicación
     #[derive(Debug, Clone, PartialEq, Eq, ink::scale::Encode, ink::scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
@@ -294,6 +361,7 @@ mod market_place {
         id_producto: u32,
         precio: u128,
         stock_a_vender: u32,
+        reservas: Vec<StockAlmacen>,
     }
 
     /// Crea una nueva instancia de una publicación.
@@ -305,6 +373,8 @@ mod market_place {
     /// - `precio`: Precio por unidad del producto.
     /// - `stock_a_vender`: Cantidad disponible para la venta.
     ///
+    /// Por defecto todo el stock queda reservado del almacén principal.
+    ///
     /// # Retorna
     /// Una nueva instancia de `Publicacion`.
     impl Publicacion {
@@ -321,6 +391,10 @@ mod market_place {
                 id_producto,
                 precio,
                 stock_a_vender,
+                reservas: Vec::from([StockAlmacen::new(
+                    ALMACEN_PRINCIPAL.to_string(),
+                    stock_a_vender,
+                )]),
             }
         }
 
@@ -338,6 +412,48 @@ mod market_place {
             }
             Ok(())
         }
+
+        /// Descuenta unidades de la publicación y de sus reservas por almacén.
+        ///
+        /// Si un único almacén alcanza para cubrir la cantidad pedida, se despacha todo desde ahí;
+        /// si no, se van consumiendo las reservas en orden hasta completar el pedido.
+        ///
+        /// # Parámetros
+        /// - `cantidad`: Unidades a descontar.
+        ///
+        /// # Retorna
+        /// - `Ok(despacho)` con las unidades tomadas de cada almacén.
+        /// - `Err(ErrorMarketplace::StockInsuficiente)` si la publicación no tiene stock suficiente.
+        fn consumir_reservas(
+            &mut self,
+            cantidad: u32,
+        ) -> Result<Vec<StockAlmacen>, ErrorMarketplace> {
+            self.verificar_stock(cantidad)?;
+            let mut despacho = Vec::new();
+            if let Some(reserva) = self.reservas.iter_mut().find(|r| r.cantidad >= cantidad) {
+                reserva.cantidad = reserva.cantidad.saturating_sub(cantidad);
+                despacho.push(StockAlmacen::new(reserva.almacen.clone(), cantidad));
+            } else {
+                let mut restante = cantidad;
+                for reserva in self.reservas.iter_mut() {
+                    if restante == 0 {
+                        break;
+                    }
+                    let tomado = reserva.cantidad.min(restante);
+                    if tomado > 0 {
+                        reserva.cantidad = reserva.cantidad.saturating_sub(tomado);
+                        restante = restante.saturating_sub(tomado);
+                        despacho.push(StockAlmacen::new(reserva.almacen.clone(), tomado));
+                    }
+                }
+                if restante > 0 {
+                    return Err(ErrorMarketplace::StockInsuficiente);
+                }
+            }
+            self.reservas.retain(|r| r.cantidad > 0);
+            self.stock_a_vender = self.stock_a_vender.saturating_sub(cantidad);
+            Ok(despacho)
+        }
         // Helper para validar el precio de un producto.
         /// Valida que el precio ingresado sea mayor a cero.
         ///
@@ -368,6 +484,8 @@ mod market_place {
     /// - `cant_producto`: Cantidad solicitada del producto.
     /// - `estado`: Estado actual de la orden (Pendiente, Enviado, Recibido, Cancelada).
     /// - `total`: Monto total de la orden (precio * cantidad)
+    /// - `almacenes`: Almacenes desde los que se despachó la orden y cuántas unidades salieron de cada uno.
+    /// - `envios`: Paquetes en los que el vendedor despachó la orden.
     #[derive(Debug, PartialEq, Eq, ink::scale::Encode, ink::scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
@@ -379,17 +497,40 @@ mod market_place {
         cant_producto: u16, //cantidad de producto que se ordena
         estado: EstadoOrden,
         total: u128,
+        almacenes: Vec<StockAlmacen>,
+        envios: Vec<Envio>,
+    }
+
+    /// Representa un paquete despachado como parte de una orden.
+    ///
+    /// # Campos
+    /// - `id`: Identificador del envío dentro de la orden (empieza en 1).
+    /// - `cantidad`: Unidades incluidas en el paquete.
+    /// - `transportista`: Empresa o medio que transporta el paquete.
+    /// - `seguimiento`: Código de seguimiento del transportista.
+    /// - `recibido`: Si el comprador confirmó la recepción del paquete.
+    #[derive(Debug, Clone, PartialEq, Eq, ink::scale::Encode, ink::scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Envio {
+        id: u32,
+        cantidad: u16,
+        transportista: String,
+        seguimiento: String,
+        recibido: bool,
     }
 
     /// Representa el depósito de un vendedor para un producto específico.
     ///
     /// Cada vendedor tiene un depósito individual por producto, donde se almacena el stock disponible.
     /// El depósito se inicializa al registrar un nuevo producto o agregar stock de uno ya existente.
+    /// El stock se reparte entre los almacenes del vendedor; `stock` siempre es la suma de todos ellos.
     ///
     /// # Campos
     /// - `id_producto`: Identificador único del producto asociado al depósito.
     /// - `id_vendedor`: `AccountId` del vendedor dueño del depósito.
-    /// - `stock`: Cantidad de unidades disponibles en el depósito.
+    /// - `stock`: Cantidad total de unidades disponibles en el depósito.
+    /// - `almacenes`: Stock disponible en cada almacén.
     #[derive(Debug, PartialEq, Eq, ink::scale::Encode, ink::scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
@@ -397,6 +538,7 @@ mod market_place {
         id_producto: u32,
         id_vendedor: AccountId,
         stock: u32,
+        almacenes: Vec<StockAlmacen>,
     }
     impl Deposito {
         /// Crea un nuevo depósito para un producto y un vendedor determinado.
@@ -404,7 +546,7 @@ mod market_place {
         /// # Parámetros
         /// - `id_producto`: ID del producto asociado.
         /// - `id_vendedor`: Cuenta del vendedor dueño del depósito.
-        /// - `stock`: Cantidad inicial disponible.
+        /// - `stock`: Cantidad inicial disponible, que se carga en el almacén principal.
         ///
         /// # Retorna
         /// Una nueva instancia de `Deposito`.
@@ -413,12 +555,179 @@ mod market_place {
                 id_producto,
                 id_vendedor,
                 stock,
+                almacenes: Vec::from([StockAlmacen::new(ALMACEN_PRINCIPAL.to_string(), stock)]),
             }
         }
 
-        pub fn actualizar_stock(&mut self, stock: u32) {
-            self.stock = stock;
+        /// Fija el stock del almacén principal.
+        ///
+        /// # Retorna
+        /// - `Ok(())` si se actualizó el stock.
+        /// - `Err(ErrorMarketplace::Overflow)` si el total del depósito no entra en un `u32`.
+        pub fn actualizar_stock(&mut self, stock: u32) -> Result<(), ErrorMarketplace> {
+            self.actualizar_stock_almacen(ALMACEN_PRINCIPAL, stock)
+        }
+
+        /// Devuelve el stock disponible en un almacén (cero si el almacén no tiene stock cargado).
+        pub fn stock_en(&self, almacen: &str) -> u32 {
+            self.almacenes
+                .iter()
+                .find(|a| a.almacen == almacen)
+                .map_or(0, |a| a.cantidad)
         }
+
+        /// Fija el stock de un almacén y recalcula el total del depósito.
+        ///
+        /// # Parámetros
+        /// - `almacen`: Nombre normalizado del almacén.
+        /// - `stock`: Nueva cantidad para ese almacén.
+        ///
+        /// # Retorna
+        /// - `Ok(())` si se actualizó el stock.
+        /// - `Err(ErrorMarketplace::Overflow)` si el total del depósito no entra en un `u32`.
+        pub fn actualizar_stock_almacen(
+            &mut self,
+            almacen: &str,
+            stock: u32,
+        ) -> Result<(), ErrorMarketplace> {
+            let mut almacenes = self.almacenes.clone();
+            match almacenes.iter_mut().find(|a| a.almacen == almacen) {
+                Some(existente) => existente.cantidad = stock,
+                None => almacenes.push(StockAlmacen::new(almacen.to_string(), stock)),
+            }
+            let total = almacenes
+                .iter()
+                .try_fold(0u32, |total, a| total.checked_add(a.cantidad))
+                .ok_or(ErrorMarketplace::Overflow)?;
+            self.almacenes = almacenes;
+            self.stock = total;
+            Ok(())
+        }
+
+        /// Descuenta unidades del depósito.
+        ///
+        /// Con un almacén elegido se descuenta sólo de ese almacén; sin almacén se toma stock
+        /// de todos los almacenes, en el orden en que fueron cargados.
+        ///
+        /// # Parámetros
+        /// - `almacen`: Almacén del que se toma el stock, o `None` para usar todos.
+        /// - `cantidad`: Unidades a descontar.
+        ///
+        /// # Retorna
+        /// - `Ok(reservas)` con las unidades tomadas de cada almacén.
+        /// - `Err(ErrorMarketplace::StockAlmacenInsuficiente)` si el almacén elegido no alcanza.
+        /// - `Err(ErrorMarketplace::StockDepositoInsuficiente)` si el total del depósito no alcanza.
+        pub fn descontar(
+            &mut self,
+            almacen: Option<&str>,
+            cantidad: u32,
+        ) -> Result<Vec<StockAlmacen>, ErrorMarketplace> {
+            if self.stock < cantidad {
+                return Err(ErrorMarketplace::StockDepositoInsuficiente);
+            }
+            let mut reservas = Vec::new();
+            match almacen {
+                Some(nombre) => {
+                    let disponible = self.stock_en(nombre);
+                    if disponible < cantidad {
+                        return Err(ErrorMarketplace::StockAlmacenInsuficiente);
+                    }
+                    self.actualizar_stock_almacen(nombre, disponible.saturating_sub(cantidad))?;
+                    reservas.push(StockAlmacen::new(nombre.to_string(), cantidad));
+                }
+                None => {
+                    let mut restante = cantidad;
+                    for actual in self.almacenes.iter_mut() {
+                        if restante == 0 {
+                            break;
+                        }
+                        let tomado = actual.cantidad.min(restante);
+                        if tomado > 0 {
+                            actual.cantidad = actual.cantidad.saturating_sub(tomado);
+                            restante = restante.saturating_sub(tomado);
+                            reservas.push(StockAlmacen::new(actual.almacen.clone(), tomado));
+                        }
+                    }
+                    self.stock = self.stock.saturating_sub(cantidad);
+                }
+            }
+            Ok(reservas)
+        }
+
+        /// Mueve unidades de un almacén a otro dentro del mismo depósito.
+        ///
+        /// # Retorna
+        /// - `Ok(())` si la transferencia se realizó.
+        /// - `Err(ErrorMarketplace::TransferenciaInvalida)` si origen y destino coinciden o la cantidad es cero.
+        /// - `Err(ErrorMarketplace::StockAlmacenInsuficiente)` si el almacén de origen no alcanza.
+        pub fn transferir(
+            &mut self,
+            origen: &str,
+            destino: &str,
+            cantidad: u32,
+        ) -> Result<(), ErrorMarketplace> {
+            if origen == destino || cantidad == 0 {
+                return Err(ErrorMarketplace::TransferenciaInvalida);
+            }
+            let en_origen = self.stock_en(origen);
+            if en_origen < cantidad {
+                return Err(ErrorMarketplace::StockAlmacenInsuficiente);
+            }
+            let en_destino = self
+                .stock_en(destino)
+                .checked_add(cantidad)
+                .ok_or(ErrorMarketplace::Overflow)?;
+            self.actualizar_stock_almacen(origen, en_origen.saturating_sub(cantidad))?;
+            self.actualizar_stock_almacen(destino, en_destino)?;
+            Ok(())
+        }
+    }
+
+    /// Cantidad de unidades de un producto asociada a un almacén.
+    ///
+    /// Se usa para el stock de cada almacén de un depósito, para las reservas de una publicación
+    /// y para registrar desde dónde se despachó una orden.
+    ///
+    /// # Campos
+    /// - `almacen`: Nombre normalizado del almacén.
+    /// - `cantidad`: Unidades en ese almacén.
+    #[derive(Debug, Clone, PartialEq, Eq, ink::scale::Encode, ink::scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct StockAlmacen {
+        almacen: String,
+        cantidad: u32,
+    }
+    impl StockAlmacen {
+        /// Crea una nueva entrada de stock para un almacén.
+        pub fn new(almacen: String, cantidad: u32) -> Self {
+            Self { almacen, cantidad }
+        }
+    }
+
+    /// Registro de un movimiento de stock entre dos almacenes de un mismo vendedor.
+    ///
+    /// Las transferencias no se modifican ni se borran: sirven como historial auditable.
+    ///
+    /// # Campos
+    /// - `id`: Identificador incremental de la transferencia.
+    /// - `id_vendedor`: Vendedor dueño de los almacenes.
+    /// - `id_producto`: Producto transferido.
+    /// - `origen`: Almacén del que salió el stock.
+    /// - `destino`: Almacén al que ingresó el stock.
+    /// - `cantidad`: Unidades transferidas.
+    /// - `fecha`: Timestamp del bloque en que se realizó la transferencia.
+    #[derive(Debug, Clone, PartialEq, Eq, ink::scale::Encode, ink::scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Transferencia {
+        id: u32,
+        id_vendedor: AccountId,
+        id_producto: u32,
+        origen: String,
+        destino: String,
+        cantidad: u32,
+        fecha: u64,
     }
 
     /// Contrato principal del marketplace descentralizado.
@@ -432,9 +741,17 @@ mod market_place {
     /// - `publicaciones`: Mapping de publicaciones activas.
     /// - `ordenes`: Mapping de órdenes de compra.
     /// - `stock_general`: Mapping de depósitos por producto y vendedor.
+    /// - `almacenes`: Mapping de almacenes registrados por cada vendedor (sin contar el principal).
+    /// - `indice_busqueda`: Índice de palabras clave de los nombres de producto.
+    /// - `cantidad_productos_por_palabra`: Cantidad de productos indexados por cada palabra clave.
+    /// - `publicaciones_por_producto`: Índice de las publicaciones de cada producto.
+    /// - `cantidad_publicaciones_por_producto`: Cantidad de publicaciones de cada producto.
+    /// - `transferencias`: Historial de transferencias de stock entre almacenes, por vendedor.
+    /// - `cantidad_transferencias`: Cantidad de transferencias registradas por cada vendedor.
     /// - `contador_ordenes`: ID incremental de órdenes.
     /// - `contador_publicacion`: ID incremental de publicaciones.
     /// - `contador_productos`: ID incremental de productos.
+    /// - `contador_transferencias`: ID incremental de transferencias.
     #[ink(storage)]
     pub struct MarketPlace {
         usuarios: Mapping<AccountId, Usuario>, //id_usuario -> Usuario, todos los usuarios
@@ -442,10 +759,18 @@ mod market_place {
         productos: Mapping<u32, Producto>,        //id_producto -> Producto
         ordenes: Mapping<u32, Orden>,             //id_orden -> Orden
         stock_general: Mapping<(AccountId, u32), Deposito>, // (id_vendedor, id_producto) -> Deposito
+        almacenes: Mapping<AccountId, Vec<String>>,         // id_vendedor -> nombres de almacenes
+        transferencias: Mapping<(AccountId, u32), Transferencia>, // (id_vendedor, posicion) -> Transferencia
+        cantidad_transferencias: Mapping<AccountId, u32>,   // id_vendedor -> cantidad de transferencias
+        indice_busqueda: Mapping<(String, u32), u32>, // (palabra clave, posicion) -> id_producto
+        cantidad_productos_por_palabra: Mapping<String, u32>, // palabra clave -> cantidad de productos
+        publicaciones_por_producto: Mapping<(u32, u32), u32>, // (id_producto, posicion) -> id_publicacion
+        cantidad_publicaciones_por_producto: Mapping<u32, u32>, // id_producto -> cantidad de publicaciones
         //Atributos auxiliares
         contador_ordenes: u32,
         contador_publicacion: u32,
         contador_productos: u32,
+        contador_transferencias: u32,
         //aca iria lo de reputacion, creo
     }
 
@@ -479,6 +804,8 @@ mod market_place {
                 cant_producto,
                 total,
                 estado: EstadoOrden::Pendiente,
+                almacenes: Vec::new(),
+                envios: Vec::new(),
             }
         }
         /// Marca la orden como enviada.
@@ -490,12 +817,14 @@ mod market_place {
         /// # Comportamiento
         /// 1. Verifica que el 'caller' sea el vendedor asignado a esta orden.
         /// 2. Verifica que la orden no esté en estado cancelado.
-        /// 3. Si las validaciones pasan, cambia el estado de la orden a 'Enviado'.
+        /// 3. Verifica que la orden no tenga envíos parciales (el resto se registra con 'registrar_envio').
+        /// 4. Si las validaciones pasan, cambia el estado de la orden a 'Enviado'.
         ///
         /// # Retorna
         /// - 'Ok(())' si la operación se realizó correctamente.
         /// - 'Err(ErrorMarketplace::NoEsVendedor)' si el 'caller' no es el vendedor.
         /// - 'ErrorMarketplace::OrdenCancelada' si el estado de la orden está como cancelada.
+        /// - 'Err(ErrorMarketplace::EstadoInvalido)' si la orden ya tiene envíos parciales.
         fn marcar_enviada(&mut self, caller: AccountId) -> Result<(), ErrorMarketplace> {
             //validar que quien llame sea vendedor
             if caller != self.vendedor {
@@ -505,6 +834,10 @@ mod market_place {
             if self.estado == EstadoOrden::Cancelada {
                 return Err(ErrorMarketplace::OrdenCancelada);
             }
+            //si se despachó por partes, el resto también se despacha con envíos
+            if !self.envios.is_empty() {
+                return Err(ErrorMarketplace::EstadoInvalido);
+            }
             //como la orden se pone por default en estado "Pendiente", no necesito preguntar si esta pendiente para cambiarla(?
 
             //cambiar el estado a "Enviado"
@@ -522,7 +855,7 @@ mod market_place {
         /// 1. Verifica que el 'caller' sea el comprador asignado a esta orden.
         /// 2. Verifica que la orden no esté cancelada.
         /// 3. Verifica que la orden esté en estado 'Enviado' (sólo puede marcarse como recibida si ya fue enviada).
-        /// 4. Si las validaciones pasan, cambia el estado de la orden a 'Recibido'.
+        /// 4. Si las validaciones pasan, cambia el estado de la orden a 'Recibido' y da por recibidos todos sus envíos.
         ///
         /// # Retorna
         /// - 'Ok(())' si la operación se realizó correctamente.
@@ -543,9 +876,123 @@ mod market_place {
                 return Err(ErrorMarketplace::EstadoInvalido);
             }
             //cambiar el estado a "Recibido"
+            for envio in self.envios.iter_mut() {
+                envio.recibido = true;
+            }
             self.estado = EstadoOrden::Recibido;
             Ok(())
         }
+
+        /// Devuelve la cantidad de unidades ya despachadas en envíos.
+        fn cantidad_enviada(&self) -> u16 {
+            self.envios
+                .iter()
+                .fold(0u16, |total, envio| total.saturating_add(envio.cantidad))
+        }
+
+        /// Registra un envío parcial o final de la orden.
+        ///
+        /// # Parámetros
+        /// - 'caller: AccountId': cuenta que despacha (debe ser el vendedor).
+        /// - 'cantidad: u16': unidades incluidas en el paquete.
+        /// - 'transportista: String': empresa que transporta el paquete.
+        /// - 'seguimiento: String': código de seguimiento.
+        ///
+        /// # Comportamiento
+        /// 1. Verifica que el 'caller' sea el vendedor y que la orden esté pendiente o enviada parcialmente.
+        /// 2. Verifica que la cantidad no supere las unidades que faltan despachar.
+        /// 3. Agrega el envío y pasa la orden a 'EnviadoParcialmente' o, si ya se despachó todo, a 'Enviado'.
+        ///
+        /// # Retorna
+        /// - 'Ok(id_envio)' con el identificador del envío dentro de la orden.
+        /// - 'Err(ErrorMarketplace::NoEsVendedor)' si el 'caller' no es el vendedor.
+        /// - 'Err(ErrorMarketplace::OrdenCancelada)' si la orden está cancelada.
+        /// - 'Err(ErrorMarketplace::EstadoInvalido)' si la orden ya fue enviada o recibida por completo.
+        /// - 'Err(ErrorMarketplace::CantidadEnvioInvalida)' si la cantidad es cero o excede lo pendiente.
+        /// - 'Err(ErrorMarketplace::DatosEnvioInvalidos)' si el transportista o el seguimiento están vacíos.
+        fn registrar_envio(
+            &mut self,
+            caller: AccountId,
+            cantidad: u16,
+            transportista: String,
+            seguimiento: String,
+        ) -> Result<u32, ErrorMarketplace> {
+            if caller != self.vendedor {
+                return Err(ErrorMarketplace::NoEsVendedor);
+            }
+            match self.estado {
+                EstadoOrden::Cancelada => return Err(ErrorMarketplace::OrdenCancelada),
+                EstadoOrden::Pendiente | EstadoOrden::EnviadoParcialmente => {}
+                _ => return Err(ErrorMarketplace::EstadoInvalido),
+            }
+            let pendiente = self.cant_producto.saturating_sub(self.cantidad_enviada());
+            if cantidad == 0 || cantidad > pendiente {
+                return Err(ErrorMarketplace::CantidadEnvioInvalida);
+            }
+            if transportista.trim().is_empty() || seguimiento.trim().is_empty() {
+                return Err(ErrorMarketplace::DatosEnvioInvalidos);
+            }
+            let id = (self.envios.len() as u32)
+                .checked_add(1)
+                .ok_or(ErrorMarketplace::Overflow)?;
+            self.envios.push(Envio {
+                id,
+                cantidad,
+                transportista,
+                seguimiento,
+                recibido: false,
+            });
+            self.estado = if cantidad == pendiente {
+                EstadoOrden::Enviado
+            } else {
+                EstadoOrden::EnviadoParcialmente
+            };
+            Ok(id)
+        }
+
+        /// Confirma la recepción de uno de los envíos de la orden.
+        ///
+        /// # Parámetros
+        /// - 'caller: AccountId': cuenta que confirma (debe ser el comprador).
+        /// - 'id_envio: u32': identificador del envío dentro de la orden.
+        ///
+        /// # Comportamiento
+        /// 1. Verifica que el 'caller' sea el comprador y que la orden no esté cancelada.
+        /// 2. Marca el envío como recibido.
+        /// 3. Si todas las unidades de la orden fueron despachadas y recibidas, la orden pasa a 'Recibido'.
+        ///
+        /// # Retorna
+        /// - 'Ok(())' si la recepción se registró correctamente.
+        /// - 'Err(ErrorMarketplace::NoEsComprador)' si el 'caller' no es el comprador.
+        /// - 'Err(ErrorMarketplace::OrdenCancelada)' si la orden está cancelada.
+        /// - 'Err(ErrorMarketplace::EnvioNoExiste)' si la orden no tiene un envío con ese ID.
+        /// - 'Err(ErrorMarketplace::EnvioYaRecibido)' si el envío ya fue confirmado.
+        fn confirmar_recepcion_envio(
+            &mut self,
+            caller: AccountId,
+            id_envio: u32,
+        ) -> Result<(), ErrorMarketplace> {
+            if caller != self.comprador {
+                return Err(ErrorMarketplace::NoEsComprador);
+            }
+            if self.estado == EstadoOrden::Cancelada {
+                return Err(ErrorMarketplace::OrdenCancelada);
+            }
+            let envio = self
+                .envios
+                .iter_mut()
+                .find(|envio| envio.id == id_envio)
+                .ok_or(ErrorMarketplace::EnvioNoExiste)?;
+            if envio.recibido {
+                return Err(ErrorMarketplace::EnvioYaRecibido);
+            }
+            envio.recibido = true;
+            if self.estado == EstadoOrden::Enviado && self.envios.iter().all(|envio| envio.recibido)
+            {
+                self.estado = EstadoOrden::Recibido;
+            }
+            Ok(())
+        }
     }
 
     impl MarketPlace {
@@ -558,9 +1005,17 @@ mod market_place {
                 ordenes: Mapping::default(),
                 publicaciones: Mapping::default(),
                 stock_general: Mapping::default(),
+                almacenes: Mapping::default(),
+                transferencias: Mapping::default(),
+                cantidad_transferencias: Mapping::default(),
+                indice_busqueda: Mapping::default(),
+                cantidad_productos_por_palabra: Mapping::default(),
+                publicaciones_por_producto: Mapping::default(),
+                cantidad_publicaciones_por_producto: Mapping::default(),
                 contador_ordenes: 0,
                 contador_publicacion: 0,
                 contador_productos: 0,
+                contador_transferencias: 0,
             }
         }
 
@@ -623,7 +1078,9 @@ mod market_place {
                         descripcion.clone(),
                         categoria.clone(),
                     );
+                    let palabras_clave = Producto::tokenizar_nombre(&nuevo_producto.nombre);
                     self.insertar_producto_en_catalogo(nuevo_producto)?;
+                    self.indexar_producto(id_producto, palabras_clave)?;
                     self.inicializar_deposito(id_vendedor, id_producto, stock)?;
                     Ok(())
                 }
@@ -827,6 +1284,44 @@ mod market_place {
             Ok(())
         }
 
+        //Helper para mantener el índice de búsqueda
+        /// Agrega el producto al final de la lista de cada palabra clave de su nombre.
+        ///
+        /// Se llama recién cuando el producto quedó en el catálogo.
+        ///
+        /// # Parámetros
+        /// - `id_producto`: ID del producto recién registrado.
+        /// - `palabras_clave`: Palabras clave de su nombre.
+        ///
+        /// # Retorna
+        /// - `Err(ErrorMarketplace::Overflow)` si una palabra ya no admite más productos.
+        fn indexar_producto(
+            &mut self,
+            id_producto: u32,
+            palabras_clave: Vec<String>,
+        ) -> Result<(), ErrorMarketplace> {
+            for token in palabras_clave {
+                let posicion = self.cantidad_productos_por_palabra.get(&token).unwrap_or(0);
+                let siguiente = posicion.checked_add(1).ok_or(ErrorMarketplace::Overflow)?;
+                self.indice_busqueda
+                    .insert((token.clone(), posicion), &id_producto);
+                self.cantidad_productos_por_palabra
+                    .insert(token, &siguiente);
+            }
+            Ok(())
+        }
+
+        /// Devuelve los IDs de producto de una palabra clave, del más reciente al más antiguo,
+        /// leyendo como máximo `MAX_PRODUCTOS_POR_PALABRA`.
+        fn productos_de_palabra(&self, token: &String) -> Vec<u32> {
+            let cantidad = self.cantidad_productos_por_palabra.get(token).unwrap_or(0);
+            let desde = cantidad.saturating_sub(MAX_PRODUCTOS_POR_PALABRA);
+            (desde..cantidad)
+                .rev()
+                .filter_map(|posicion| self.indice_busqueda.get((token.clone(), posicion)))
+                .collect()
+        }
+
         /// Helper para insertar una publicación en el sistema.
         /// /// Inserta una publicación nueva en el sistema.
         ///
@@ -847,6 +1342,16 @@ mod market_place {
             // Insertar la publicación en el mapping
             self.publicaciones
                 .insert(publicacion.id_publicacion, &publicacion);
+            // Registrarla en el índice de publicaciones de su producto
+            let posicion = self
+                .cantidad_publicaciones_por_producto
+                .get(publicacion.id_producto)
+                .unwrap_or(0);
+            let siguiente = posicion.checked_add(1).ok_or(ErrorMarketplace::Overflow)?;
+            self.publicaciones_por_producto
+                .insert((publicacion.id_producto, posicion), &publicacion.id_publicacion);
+            self.cantidad_publicaciones_por_producto
+                .insert(publicacion.id_producto, &siguiente);
             Ok(())
         }
 
@@ -904,44 +1409,41 @@ mod market_place {
         /// Helper para actualizar el stock de un producto en el depósito de un vendedor.
         /// /// Actualiza el stock de un producto en el depósito tras una venta.
         ///
-        /// Realiza validaciones y persiste el nuevo stock.
+        /// Realiza validaciones y persiste el nuevo stock. Se descuenta del almacén indicado
+        /// o, si no se indica ninguno, de todos los almacenes del vendedor en orden.
         ///
         /// # Parámetros
         /// - `id_vendedor`: Cuenta del vendedor.
         /// - `id_producto`: ID del producto.
+        /// - `almacen`: Almacén (ya normalizado) del que se descuenta, o `None` para usar todos.
         /// - `stock_a_vender`: Cantidad vendida a descontar.
         ///
         /// # Retorna
-        /// - `Ok(())` si la actualización fue exitosa.
-        /// - `Err(ErrorMarketplace::ProductoNoExiste)` si no existe el depósito.
+        /// - `Ok(reservas)` con las unidades descontadas de cada almacén.
+        /// - `Err(ErrorMarketplace::DepositoNoEncontrado)` si no existe el depósito.
         /// - `Err(ErrorMarketplace::StockDepositoInsuficiente)` si el stock es insuficiente.
+        /// - `Err(ErrorMarketplace::StockAlmacenInsuficiente)` si el almacén elegido no alcanza.
         fn actualizar_stock_producto(
             &mut self,
             id_vendedor: AccountId,
             id_producto: u32,
+            almacen: Option<&str>,
             stock_a_vender: u32,
-        ) -> Result<(), ErrorMarketplace> {
-            // Obtener el stock actual del depósito
-            let stock_actual = self.obtener_stock_deposito(id_vendedor, id_producto)?;
+        ) -> Result<Vec<StockAlmacen>, ErrorMarketplace> {
             // Verificar que el stock a vender no exceda el stock actual
             self.validar_stock_deposito(id_vendedor, id_producto, stock_a_vender)?;
-            // Actualizar el stock en el depósito
-
-            let nuevo_stock = stock_actual
-                .checked_sub(stock_a_vender)
-                .ok_or(ErrorMarketplace::StockInsuficiente)?;
 
             let mut deposito = self
                 .stock_general
                 .get(&(id_vendedor, id_producto))
-                .ok_or(ErrorMarketplace::ProductoNoExiste)?;
+                .ok_or(ErrorMarketplace::DepositoNoEncontrado)?;
 
-            deposito.actualizar_stock(nuevo_stock);
+            let reservas = deposito.descontar(almacen, stock_a_vender)?;
 
             //se debe volver a insertar para poder actualizar el stock
             self.stock_general
                 .insert((id_vendedor, id_producto), &deposito);
-            Ok(())
+            Ok(reservas)
         }
 
         ///Funcion que modifica el stock de un depósito de un vendedor.
@@ -949,6 +1451,10 @@ mod market_place {
         ///
         /// Llama al helper interno con el caller como vendedor.
         ///
+        /// Desde que existen los almacenes, este mensaje sólo fija el stock del almacén principal
+        /// (antes fijaba el stock total del depósito). Para cambiar el de otro almacén se usa
+        /// `modificar_stock_almacen`.
+        ///
         /// # Parámetros
         /// - `nombre_producto`: Nombre del producto.
         /// - `stock`: Nuevo stock a asignar.
@@ -970,6 +1476,7 @@ mod market_place {
         /// Helper interno para modificar stock de depósito.
         ///
         /// Verifica que el usuario sea vendedor y que el stock sea válido.
+        /// El stock indicado se asigna al almacén principal; el resto de los almacenes no cambia.
         ///
         /// Actualiza el depósito y persiste cambios.
         ///
@@ -1008,8 +1515,8 @@ mod market_place {
                 .stock_general
                 .get(&(id_vendedor, id_producto))
                 .ok_or(ErrorMarketplace::ProductoNoExiste)?;
-            // Actualizar el stock del depósito
-            deposito.actualizar_stock(stock);
+            // Actualizar el stock del almacén principal del depósito
+            deposito.actualizar_stock(stock)?;
             // Guardar el depósito actualizado en el mapping
             self.stock_general
                 .insert((id_vendedor, id_producto), &deposito);
@@ -1048,6 +1555,271 @@ mod market_place {
             Ok(())
         }
 
+        /// Registra un nuevo almacén para el vendedor que llama a la función.
+        ///
+        /// # Parámetros
+        /// - `nombre`: Nombre del almacén (se normaliza igual que los nombres de producto).
+        ///
+        /// # Retorna
+        /// - `Ok(())` si el almacén fue registrado.
+        /// - `Err(ErrorMarketplace)` si el usuario no es vendedor, el nombre es inválido o el almacén ya existe.
+        #[ink(message)]
+        pub fn registrar_almacen(&mut self, nombre: String) -> Result<(), ErrorMarketplace> {
+            let caller = self.env().caller();
+            self._registrar_almacen(caller, nombre)
+        }
+
+        /// Helper interno para registrar un almacén.
+        ///
+        /// # Parámetros
+        /// - `id_vendedor`: Cuenta del vendedor.
+        /// - `nombre`: Nombre del almacén.
+        ///
+        /// # Retorna
+        /// - `Ok(())` si el almacén fue registrado.
+        /// - `Err(ErrorMarketplace::RolInvalido)` si el usuario no es vendedor.
+        /// - `Err(ErrorMarketplace::NombreInvalido)` si el nombre está vacío.
+        /// - `Err(ErrorMarketplace::AlmacenYaExiste)` si el vendedor ya tiene un almacén con ese nombre.
+        /// - `Err(ErrorMarketplace::LimiteAlmacenesAlcanzado)` si ya tiene `MAX_ALMACENES_POR_VENDEDOR` almacenes.
+        fn _registrar_almacen(
+            &mut self,
+            id_vendedor: AccountId,
+            nombre: String,
+        ) -> Result<(), ErrorMarketplace> {
+            self.verificar_rol_vendedor(id_vendedor)?;
+            Producto::validar_nombre_producto(&nombre)?;
+            let nombre_normalizado = Producto::normalizar_nombre_producto(&nombre);
+            if self.vendedor_tiene_almacen(id_vendedor, &nombre_normalizado) {
+                return Err(ErrorMarketplace::AlmacenYaExiste);
+            }
+            let mut almacenes = self.almacenes.get(id_vendedor).unwrap_or_default();
+            if almacenes.len() >= MAX_ALMACENES_POR_VENDEDOR as usize {
+                return Err(ErrorMarketplace::LimiteAlmacenesAlcanzado);
+            }
+            almacenes.push(nombre_normalizado);
+            self.almacenes.insert(id_vendedor, &almacenes);
+            Ok(())
+        }
+
+        /// Devuelve los almacenes del vendedor que llama, empezando por el principal.
+        #[ink(message)]
+        pub fn ver_almacenes(&self) -> Vec<String> {
+            let caller = self.env().caller();
+            self._ver_almacenes(caller)
+        }
+
+        /// Helper interno que lista los almacenes de un vendedor, empezando por el principal.
+        fn _ver_almacenes(&self, id_vendedor: AccountId) -> Vec<String> {
+            let mut almacenes = Vec::from([ALMACEN_PRINCIPAL.to_string()]);
+            almacenes.extend(self.almacenes.get(id_vendedor).unwrap_or_default());
+            almacenes
+        }
+
+        //Helper para saber si un vendedor tiene un almacén
+        /// Indica si el vendedor tiene un almacén con el nombre (normalizado) dado.
+        ///
+        /// El almacén principal existe siempre.
+        fn vendedor_tiene_almacen(&self, id_vendedor: AccountId, nombre: &str) -> bool {
+            nombre == ALMACEN_PRINCIPAL
+                || self
+                    .almacenes
+                    .get(id_vendedor)
+                    .is_some_and(|almacenes| almacenes.iter().any(|a| a == nombre))
+        }
+
+        //Helper para validar un almacén
+        /// Normaliza el nombre de un almacén y verifica que pertenezca al vendedor.
+        ///
+        /// # Retorna
+        /// - `Ok(nombre)` con el nombre normalizado.
+        /// - `Err(ErrorMarketplace::AlmacenNoExiste)` si el vendedor no tiene ese almacén.
+        fn obtener_almacen(
+            &self,
+            id_vendedor: AccountId,
+            nombre: &String,
+        ) -> Result<String, ErrorMarketplace> {
+            let nombre_normalizado = Producto::normalizar_nombre_producto(nombre);
+            if !self.vendedor_tiene_almacen(id_vendedor, &nombre_normalizado) {
+                return Err(ErrorMarketplace::AlmacenNoExiste);
+            }
+            Ok(nombre_normalizado)
+        }
+
+        //Helper para obtener el depósito de un producto por nombre
+        /// Busca el producto por nombre y devuelve su ID junto con el depósito del vendedor.
+        ///
+        /// # Retorna
+        /// - `Err(ErrorMarketplace::ProductoNoExiste)` si el producto no está en el catálogo.
+        /// - `Err(ErrorMarketplace::DepositoNoEncontrado)` si el vendedor no tiene depósito del producto.
+        fn obtener_deposito_por_nombre(
+            &self,
+            id_vendedor: AccountId,
+            nombre_producto: &String,
+        ) -> Result<(u32, Deposito), ErrorMarketplace> {
+            let id_producto = self.buscar_producto_por_nombre(nombre_producto)?;
+            let deposito = self
+                .stock_general
+                .get((id_vendedor, id_producto))
+                .ok_or(ErrorMarketplace::DepositoNoEncontrado)?;
+            Ok((id_producto, deposito))
+        }
+
+        /// Fija el stock de un producto en uno de los almacenes del vendedor que llama.
+        ///
+        /// # Parámetros
+        /// - `nombre_producto`: Nombre del producto.
+        /// - `almacen`: Nombre del almacén.
+        /// - `stock`: Nuevo stock para ese almacén.
+        ///
+        /// # Retorna
+        /// - `Ok(())` si la modificación fue exitosa.
+        /// - `Err(ErrorMarketplace)` si ocurre un error de validación o permisos.
+        #[ink(message)]
+        pub fn modificar_stock_almacen(
+            &mut self,
+            nombre_producto: String,
+            almacen: String,
+            stock: u32,
+        ) -> Result<(), ErrorMarketplace> {
+            let caller = self.env().caller();
+            self._modificar_stock_almacen(caller, nombre_producto, almacen, stock)
+        }
+
+        /// Helper interno para fijar el stock de un almacén.
+        ///
+        /// A diferencia de `_modificar_stock_deposito`, acepta stock cero para poder vaciar un almacén.
+        ///
+        /// # Retorna
+        /// - `Ok(())` si la modificación fue exitosa.
+        /// - `Err(ErrorMarketplace::RolInvalido)` si el usuario no es vendedor.
+        /// - `Err(ErrorMarketplace::AlmacenNoExiste)` si el vendedor no tiene ese almacén.
+        /// - `Err(ErrorMarketplace::ProductoNoExiste)` o `DepositoNoEncontrado` si no hay depósito del producto.
+        fn _modificar_stock_almacen(
+            &mut self,
+            id_vendedor: AccountId,
+            nombre_producto: String,
+            almacen: String,
+            stock: u32,
+        ) -> Result<(), ErrorMarketplace> {
+            self.verificar_rol_vendedor(id_vendedor)?;
+            let almacen = self.obtener_almacen(id_vendedor, &almacen)?;
+            let (id_producto, mut deposito) =
+                self.obtener_deposito_por_nombre(id_vendedor, &nombre_producto)?;
+            deposito.actualizar_stock_almacen(&almacen, stock)?;
+            self.stock_general
+                .insert((id_vendedor, id_producto), &deposito);
+            Ok(())
+        }
+
+        /// Devuelve el stock de un producto en cada almacén del vendedor que llama.
+        ///
+        /// # Parámetros
+        /// - `nombre_producto`: Nombre del producto.
+        ///
+        /// # Retorna
+        /// - `Ok(stock)` con la cantidad disponible por almacén.
+        /// - `Err(ErrorMarketplace)` si el producto o el depósito no existen.
+        #[ink(message)]
+        pub fn ver_stock_por_almacen(
+            &self,
+            nombre_producto: String,
+        ) -> Result<Vec<StockAlmacen>, ErrorMarketplace> {
+            let caller = self.env().caller();
+            let (_, deposito) = self.obtener_deposito_por_nombre(caller, &nombre_producto)?;
+            Ok(deposito.almacenes)
+        }
+
+        /// Transfiere stock de un producto entre dos almacenes del vendedor que llama.
+        ///
+        /// Cada transferencia queda registrada con su fecha para poder auditarla.
+        ///
+        /// # Parámetros
+        /// - `nombre_producto`: Nombre del producto.
+        /// - `origen`: Almacén del que sale el stock.
+        /// - `destino`: Almacén al que ingresa el stock.
+        /// - `cantidad`: Unidades a transferir.
+        ///
+        /// # Retorna
+        /// - `Ok(id)` con el ID de la transferencia registrada.
+        /// - `Err(ErrorMarketplace)` si ocurre un error de validación o permisos.
+        #[ink(message)]
+        pub fn transferir_stock(
+            &mut self,
+            nombre_producto: String,
+            origen: String,
+            destino: String,
+            cantidad: u32,
+        ) -> Result<u32, ErrorMarketplace> {
+            let caller = self.env().caller();
+            let fecha = self.env().block_timestamp();
+            self._transferir_stock(caller, nombre_producto, origen, destino, cantidad, fecha)
+        }
+
+        /// Helper interno para transferir stock entre almacenes.
+        ///
+        /// # Retorna
+        /// - `Ok(id)` con el ID de la transferencia registrada.
+        /// - `Err(ErrorMarketplace::RolInvalido)` si el usuario no es vendedor.
+        /// - `Err(ErrorMarketplace::AlmacenNoExiste)` si alguno de los almacenes no es del vendedor.
+        /// - `Err(ErrorMarketplace::TransferenciaInvalida)` si origen y destino coinciden o la cantidad es cero.
+        /// - `Err(ErrorMarketplace::StockAlmacenInsuficiente)` si el origen no tiene stock suficiente.
+        fn _transferir_stock(
+            &mut self,
+            id_vendedor: AccountId,
+            nombre_producto: String,
+            origen: String,
+            destino: String,
+            cantidad: u32,
+            fecha: u64,
+        ) -> Result<u32, ErrorMarketplace> {
+            self.verificar_rol_vendedor(id_vendedor)?;
+            let origen = self.obtener_almacen(id_vendedor, &origen)?;
+            let destino = self.obtener_almacen(id_vendedor, &destino)?;
+            let (id_producto, mut deposito) =
+                self.obtener_deposito_por_nombre(id_vendedor, &nombre_producto)?;
+            deposito.transferir(&origen, &destino, cantidad)?;
+
+            let id = self
+                .contador_transferencias
+                .checked_add(1)
+                .ok_or(ErrorMarketplace::Overflow)?;
+            let posicion = self.cantidad_transferencias.get(id_vendedor).unwrap_or(0);
+            let siguiente = posicion.checked_add(1).ok_or(ErrorMarketplace::Overflow)?;
+            let transferencia = Transferencia {
+                id,
+                id_vendedor,
+                id_producto,
+                origen,
+                destino,
+                cantidad,
+                fecha,
+            };
+            self.stock_general
+                .insert((id_vendedor, id_producto), &deposito);
+            self.transferencias
+                .insert((id_vendedor, posicion), &transferencia);
+            self.cantidad_transferencias.insert(id_vendedor, &siguiente);
+            self.contador_transferencias = id;
+            Ok(id)
+        }
+
+        /// Devuelve el historial de transferencias del vendedor que llama, de la más antigua a la más reciente.
+        #[ink(message)]
+        pub fn ver_transferencias(&self) -> Vec<Transferencia> {
+            let caller = self.env().caller();
+            self._ver_transferencias(caller)
+        }
+
+        /// Helper interno que recorre el historial de transferencias de un vendedor.
+        ///
+        /// Sólo lee las transferencias de ese vendedor, no las del resto.
+        fn _ver_transferencias(&self, id_vendedor: AccountId) -> Vec<Transferencia> {
+            let cantidad = self.cantidad_transferencias.get(id_vendedor).unwrap_or(0);
+            (0..cantidad)
+                .filter_map(|posicion| self.transferencias.get((id_vendedor, posicion)))
+                .collect()
+        }
+
         /// Registra un nuevo usuario con un nombre de usuario y rol dado.
         ///
         /// El `caller` es la cuenta que llama a esta función y será usada como ID del usuario.
@@ -1141,6 +1913,7 @@ mod market_place {
         /// Crea una nueva publicación para un producto dado, con stock y precio.
         ///
         /// El caller debe ser un vendedor registrado.
+        /// El stock se reserva de todos los almacenes del vendedor, en orden.
         ///
         /// # Parámetros
         /// - `nombre_producto`: Nombre del producto a publicar.
@@ -1163,9 +1936,39 @@ mod market_place {
             Ok(())
         }
 
+        /// Crea una nueva publicación cuyo stock se toma de un almacén elegido.
+        ///
+        /// # Parámetros
+        /// - `nombre_producto`: Nombre del producto a publicar.
+        /// - `almacen`: Almacén del que se reserva el stock, o `None` para tomarlo de todos.
+        /// - `stock_a_vender`: Cantidad de producto a vender en esta publicación.
+        /// - `precio`: Precio unitario.
+        ///
+        /// # Retorna
+        /// - `Ok(())` si la publicación fue creada exitosamente.
+        /// - `Err(ErrorMarketplace)` en caso de errores de validación o permisos.
+        #[ink(message)]
+        pub fn crear_publicacion_desde_almacen(
+            &mut self,
+            nombre_producto: String,
+            almacen: Option<String>,
+            stock_a_vender: u32,
+            precio: u128,
+        ) -> Result<(), ErrorMarketplace> {
+            let caller = self.env().caller();
+            self._crear_publicacion_desde_almacen(
+                nombre_producto,
+                caller,
+                almacen,
+                stock_a_vender,
+                precio,
+            )
+        }
+
         /// Helper interno para crear una publicación.
         ///
         /// Realiza validaciones de existencia de usuario, rol, stock y precio.
+        /// El stock se reserva de todos los almacenes del vendedor.
         ///
         /// # Parámetros
         /// - `nombre_producto`: Nombre del producto.
@@ -1182,6 +1985,37 @@ mod market_place {
             id_vendedor: AccountId,
             stock_a_vender: u32,
             precio: u128,
+        ) -> Result<(), ErrorMarketplace> {
+            self._crear_publicacion_desde_almacen(
+                nombre_producto,
+                id_vendedor,
+                None,
+                stock_a_vender,
+                precio,
+            )
+        }
+
+        /// Helper interno para crear una publicación que reserva stock de un almacén o de todos.
+        ///
+        /// # Parámetros
+        /// - `nombre_producto`: Nombre del producto.
+        /// - `id_vendedor`: Cuenta del vendedor.
+        /// - `almacen`: Almacén del que se reserva el stock, o `None` para tomarlo de todos.
+        /// - `stock_a_vender`: Cantidad a vender.
+        /// - `precio`: Precio unitario.
+        ///
+        /// # Retorna
+        /// - `Ok(())` si la publicación fue creada exitosamente.
+        /// - `Err(ErrorMarketplace::AlmacenNoExiste)` si el almacén no es del vendedor.
+        /// - `Err(ErrorMarketplace::StockAlmacenInsuficiente)` si el almacén elegido no alcanza.
+        /// - `Err(ErrorMarketplace)` si ocurre algún otro error en las validaciones o inserciones.
+        fn _crear_publicacion_desde_almacen(
+            &mut self,
+            nombre_producto: String,
+            id_vendedor: AccountId,
+            almacen: Option<String>,
+            stock_a_vender: u32,
+            precio: u128,
         ) -> Result<(), ErrorMarketplace> {
             self.verificar_usuario_existe(id_vendedor)?;
             self.verificar_rol_vendedor(id_vendedor)?;
@@ -1196,32 +2030,46 @@ mod market_place {
                 .buscar_producto_por_nombre(&nombre_producto_normalizado)
                 .map_err(|_| ErrorMarketplace::ProductoNoExiste)?;
 
+            // Validar que el almacén elegido sea del vendedor
+            let almacen = match almacen {
+                Some(nombre) => Some(self.obtener_almacen(id_vendedor, &nombre)?),
+                None => None,
+            };
+
             //Validar deposito si existe y si cant a vender es menor al stock total del deposito
             self.validar_stock_deposito(id_vendedor, id_producto, stock_a_vender)?;
 
+            // Actualizamos el stock del producto del vendedor, reservando de los almacenes
+            let reservas = self.actualizar_stock_producto(
+                id_vendedor,
+                id_producto,
+                almacen.as_deref(),
+                stock_a_vender,
+            )?;
+
             // Generamos un nuevo ID para la publicación
             let id_publicacion = self.obtener_nuevo_id_publicacion()?;
 
             // Creamos una nueva publicación
-            let nueva_publicacion = Publicacion::new(
+            let mut nueva_publicacion = Publicacion::new(
                 id_publicacion,
                 id_vendedor, // id del vendedor
                 id_producto,
                 precio,
                 stock_a_vender,
             );
+            nueva_publicacion.reservas = reservas;
 
             //Guardamos la publicación en el mapping de publicaciones
-            self.insertar_publicacion(nueva_publicacion.clone())?;
-
-            // Actualizamos el stock del producto del vendedor
-            self.actualizar_stock_producto(id_vendedor, id_producto, stock_a_vender)?;
+            self.insertar_publicacion(nueva_publicacion)?;
             Ok(())
         }
 
         /// Crea una orden de compra para una publicación con la cantidad y monto dado.
         ///
         /// El caller debe ser un comprador registrado.
+        /// La cantidad se descuenta del stock de la publicación y la orden registra
+        /// desde qué almacén (o almacenes) del vendedor se despacha.
         ///
         /// # Parámetros
         /// - `id_publicacion`: ID de la publicación a comprar.
@@ -1258,7 +2106,7 @@ mod market_place {
             self.verificar_rol_comprador(id_comprador)?;
 
             // Verificar que la publicación exista
-            let publicacion = self.obtener_publicacion(id_publicacion)?;
+            let mut publicacion = self.obtener_publicacion(id_publicacion)?;
 
             // Verificar que el stock sea suficiente y asi poder crear la orden
             publicacion.verificar_stock(cant_producto as u32)?;
@@ -1274,7 +2122,12 @@ mod market_place {
 
             // Crear nueva orden
             let nueva_id = self.contador_ordenes;
-            let orden = Orden::new(
+            let siguiente_id = nueva_id.checked_add(1).ok_or(ErrorMarketplace::Overflow)?;
+
+            // Descontar la cantidad de la publicación, registrando desde qué almacén se despacha
+            let despacho = publicacion.consumir_reservas(cant_producto as u32)?;
+
+            let mut orden = Orden::new(
                 nueva_id,
                 id_comprador,
                 publicacion.id_vendedor,
@@ -1282,13 +2135,11 @@ mod market_place {
                 cant_producto,
                 tot_orden,
             );
+            orden.almacenes = despacho;
 
+            self.publicaciones.insert(id_publicacion, &publicacion);
             self.ordenes.insert(nueva_id, &orden);
-
-            self.contador_ordenes = self
-                .contador_ordenes
-                .checked_add(1)
-                .ok_or(ErrorMarketplace::Overflow)?;
+            self.contador_ordenes = siguiente_id;
 
             Ok(())
         }
@@ -1376,31 +2227,279 @@ mod market_place {
                     }
                     Err(e) => Err(e),
                 }
-            } else {
-                Err(ErrorMarketplace::OrdenNoExiste)
+            } else {
+                Err(ErrorMarketplace::OrdenNoExiste)
+            }
+        }
+
+        /// Método público para marcar una orden como recibida.
+        ///
+        /// # Parámetros
+        /// - '&mut self': referencia mutable al Marketplace.
+        /// - 'id_orden: u32': identificador único de la orden a modificar.
+        ///
+        /// # Comportamiento
+        /// 1. Obtiene automáticamente el 'caller' de la llamada.
+        /// 2. Llama a la función privada '_marcar_orden_como_recibida'.
+        ///
+        /// # Retorna
+        /// - 'Ok(())' si la orden fue marcada como recibida correctamente.
+        /// - Propaga errores desde '_marcar_orden_como_recibida'.
+        #[ink(message)]
+        pub fn marcar_orden_como_recibida(
+            &mut self,
+            id_orden: u32,
+        ) -> Result<(), ErrorMarketplace> {
+            let caller = self.env().caller();
+            self._marcar_orden_como_recibida(caller, id_orden)
+        }
+
+        /// Función privada que registra un envío (parcial o final) de una orden.
+        ///
+        /// # Parámetros
+        /// - 'caller: AccountId': cuenta que realiza la acción (debe ser vendedor).
+        /// - 'id_orden: u32': identificador único de la orden.
+        /// - 'cantidad: u16': unidades incluidas en el paquete.
+        /// - 'transportista: String': empresa que transporta el paquete.
+        /// - 'seguimiento: String': código de seguimiento.
+        ///
+        /// # Retorna
+        /// - 'Ok(id_envio)' con el identificador del envío dentro de la orden.
+        /// - 'Err(ErrorMarketplace::OrdenNoExiste)' si no existe la orden con el 'id_orden' dado.
+        /// - Propaga otros errores que retorne 'registrar_envio'.
+        fn _registrar_envio(
+            &mut self,
+            caller: AccountId,
+            id_orden: u32,
+            cantidad: u16,
+            transportista: String,
+            seguimiento: String,
+        ) -> Result<u32, ErrorMarketplace> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErrorMarketplace::OrdenNoExiste)?;
+            let id_envio = orden.registrar_envio(caller, cantidad, transportista, seguimiento)?;
+            self.ordenes.insert(id_orden, &orden);
+            Ok(id_envio)
+        }
+
+        /// Método público para registrar un envío de una orden.
+        ///
+        /// Permite despachar una orden en varios paquetes; mientras falten unidades por despachar
+        /// la orden queda en estado 'EnviadoParcialmente'.
+        ///
+        /// # Parámetros
+        /// - 'id_orden: u32': identificador único de la orden.
+        /// - 'cantidad: u16': unidades incluidas en el paquete.
+        /// - 'transportista: String': empresa que transporta el paquete.
+        /// - 'seguimiento: String': código de seguimiento.
+        ///
+        /// # Retorna
+        /// - 'Ok(id_envio)' con el identificador del envío dentro de la orden.
+        /// - Propaga errores desde '_registrar_envio'.
+        #[ink(message)]
+        pub fn registrar_envio(
+            &mut self,
+            id_orden: u32,
+            cantidad: u16,
+            transportista: String,
+            seguimiento: String,
+        ) -> Result<u32, ErrorMarketplace> {
+            let caller = self.env().caller();
+            self._registrar_envio(caller, id_orden, cantidad, transportista, seguimiento)
+        }
+
+        /// Función privada que confirma la recepción de un envío.
+        ///
+        /// # Parámetros
+        /// - 'caller: AccountId': cuenta que realiza la acción (debe ser comprador).
+        /// - 'id_orden: u32': identificador único de la orden.
+        /// - 'id_envio: u32': identificador del envío dentro de la orden.
+        ///
+        /// # Retorna
+        /// - 'Ok(())' si la recepción se registró correctamente.
+        /// - 'Err(ErrorMarketplace::OrdenNoExiste)' si no existe la orden con el 'id_orden' dado.
+        /// - Propaga otros errores que retorne 'confirmar_recepcion_envio'.
+        fn _confirmar_recepcion_envio(
+            &mut self,
+            caller: AccountId,
+            id_orden: u32,
+            id_envio: u32,
+        ) -> Result<(), ErrorMarketplace> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErrorMarketplace::OrdenNoExiste)?;
+            orden.confirmar_recepcion_envio(caller, id_envio)?;
+            self.ordenes.insert(id_orden, &orden);
+            Ok(())
+        }
+
+        /// Método público para confirmar la recepción de un envío.
+        ///
+        /// La orden pasa a 'Recibido' recién cuando todas sus unidades fueron recibidas.
+        ///
+        /// # Parámetros
+        /// - 'id_orden: u32': identificador único de la orden.
+        /// - 'id_envio: u32': identificador del envío dentro de la orden.
+        ///
+        /// # Retorna
+        /// - 'Ok(())' si la recepción se registró correctamente.
+        /// - Propaga errores desde '_confirmar_recepcion_envio'.
+        #[ink(message)]
+        pub fn confirmar_recepcion_envio(
+            &mut self,
+            id_orden: u32,
+            id_envio: u32,
+        ) -> Result<(), ErrorMarketplace> {
+            let caller = self.env().caller();
+            self._confirmar_recepcion_envio(caller, id_orden, id_envio)
+        }
+
+        /// Devuelve los envíos de una orden. Sólo pueden consultarlos el comprador y el vendedor.
+        ///
+        /// # Parámetros
+        /// - 'id_orden: u32': identificador único de la orden.
+        ///
+        /// # Retorna
+        /// - 'Ok(envios)' con los envíos registrados.
+        /// - 'Err(ErrorMarketplace::OrdenNoExiste)' si no existe la orden.
+        /// - 'Err(ErrorMarketplace::NoAutorizado)' si el 'caller' no participa de la orden.
+        #[ink(message)]
+        pub fn ver_envios(&self, id_orden: u32) -> Result<Vec<Envio>, ErrorMarketplace> {
+            let caller = self.env().caller();
+            let orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErrorMarketplace::OrdenNoExiste)?;
+            if caller != orden.comprador && caller != orden.vendedor {
+                return Err(ErrorMarketplace::NoAutorizado);
+            }
+            Ok(orden.envios)
+        }
+
+        /// Busca publicaciones por palabras clave del nombre del producto.
+        ///
+        /// Las publicaciones se ordenan por cantidad de palabras de la consulta que coinciden
+        /// con el nombre del producto (de mayor a menor) y, a igual coincidencia, por ID.
+        /// Los candidatos salen del índice de palabras clave (los `MAX_PRODUCTOS_POR_PALABRA`
+        /// productos más recientes de cada palabra) y del índice de publicaciones por producto,
+        /// así que el costo no depende del tamaño del catálogo.
+        ///
+        /// Con una consulta vacía (o sólo con `PALABRAS_IGNORADAS`) se recorre el catálogo en
+        /// orden de ID: `offset` es la cantidad de publicaciones del catálogo a saltear y se
+        /// revisan como máximo `MAX_PUBLICACIONES_EXAMINADAS`, cortando antes si se completa la
+        /// página. Para seguir recorriendo se vuelve a llamar con `offset` igual al ID de la
+        /// última publicación devuelta si la página quedó completa, o con
+        /// `offset + MAX_PUBLICACIONES_EXAMINADAS` si no, hasta llegar a `cantidad_publicaciones`.
+        ///
+        /// # Parámetros
+        /// - `query`: Texto a buscar.
+        /// - `categoria`: Filtro opcional por categoría del producto.
+        /// - `precio_min`: Precio unitario mínimo, inclusive.
+        /// - `precio_max`: Precio unitario máximo, inclusive.
+        /// - `offset`: Cantidad de resultados a saltear (o de publicaciones, con una consulta vacía).
+        /// - `limit`: Tamaño de la página (como máximo `MAX_RESULTADOS_BUSQUEDA`).
+        ///
+        /// # Retorna
+        /// La página de publicaciones encontradas.
+        #[ink(message)]
+        pub fn buscar_publicaciones(
+            &self,
+            query: String,
+            categoria: Option<Categoria>,
+            precio_min: Option<u128>,
+            precio_max: Option<u128>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<Publicacion> {
+            let tokens = Producto::tokenizar_nombre(&query);
+            let limit = limit.min(MAX_RESULTADOS_BUSQUEDA) as usize;
+            let en_rango = |publicacion: &Publicacion| {
+                !(precio_min.is_some_and(|min| publicacion.precio < min)
+                    || precio_max.is_some_and(|max| publicacion.precio > max))
+            };
+            let en_categoria = |id_producto: u32| match &categoria {
+                Some(categoria) => self
+                    .productos
+                    .get(id_producto)
+                    .is_some_and(|producto| producto.categoria == *categoria),
+                None => true,
+            };
+
+            if tokens.is_empty() {
+                let mut pagina: Vec<Publicacion> = Vec::new();
+                let hasta = offset
+                    .saturating_add(MAX_PUBLICACIONES_EXAMINADAS)
+                    .min(self.contador_publicacion);
+                for id_publicacion in offset.saturating_add(1)..=hasta {
+                    if pagina.len() >= limit {
+                        break;
+                    }
+                    let Some(publicacion) = self.publicaciones.get(id_publicacion) else {
+                        continue;
+                    };
+                    if en_rango(&publicacion) && en_categoria(publicacion.id_producto) {
+                        pagina.push(publicacion);
+                    }
+                }
+                return pagina;
+            }
+
+            // Puntaje por producto: cuántas palabras de la consulta aparecen en su nombre
+            let mut puntajes: Vec<(u32, u32)> = Vec::new();
+            for token in tokens.iter() {
+                for id_producto in self.productos_de_palabra(token) {
+                    match puntajes.iter_mut().find(|(id, _)| *id == id_producto) {
+                        Some((_, puntaje)) => *puntaje = puntaje.saturating_add(1),
+                        None => puntajes.push((id_producto, 1)),
+                    }
+                }
+            }
+
+            let mut resultados: Vec<(u32, Publicacion)> = Vec::new();
+            for (id_producto, puntaje) in puntajes {
+                if !en_categoria(id_producto) {
+                    continue;
+                }
+                let cantidad = self
+                    .cantidad_publicaciones_por_producto
+                    .get(id_producto)
+                    .unwrap_or(0);
+                for posicion in 0..cantidad {
+                    let Some(publicacion) = self
+                        .publicaciones_por_producto
+                        .get((id_producto, posicion))
+                        .and_then(|id_publicacion| self.publicaciones.get(id_publicacion))
+                    else {
+                        continue;
+                    };
+                    if en_rango(&publicacion) {
+                        resultados.push((puntaje, publicacion));
+                    }
+                }
             }
+
+            resultados.sort_by(|(puntaje_a, a), (puntaje_b, b)| {
+                puntaje_b
+                    .cmp(puntaje_a)
+                    .then(a.id_publicacion.cmp(&b.id_publicacion))
+            });
+
+            resultados
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit)
+                .map(|(_, publicacion)| publicacion)
+                .collect()
         }
 
-        /// Método público para marcar una orden como recibida.
-        ///
-        /// # Parámetros
-        /// - '&mut self': referencia mutable al Marketplace.
-        /// - 'id_orden: u32': identificador único de la orden a modificar.
-        ///
-        /// # Comportamiento
-        /// 1. Obtiene automáticamente el 'caller' de la llamada.
-        /// 2. Llama a la función privada '_marcar_orden_como_recibida'.
-        ///
-        /// # Retorna
-        /// - 'Ok(())' si la orden fue marcada como recibida correctamente.
-        /// - Propaga errores desde '_marcar_orden_como_recibida'.
+        /// Devuelve la cantidad de publicaciones creadas, para saber hasta dónde recorrer
+        /// el catálogo con `buscar_publicaciones`.
         #[ink(message)]
-        pub fn marcar_orden_como_recibida(
-            &mut self,
-            id_orden: u32,
-        ) -> Result<(), ErrorMarketplace> {
-            let caller = self.env().caller();
-            self._marcar_orden_como_recibida(caller, id_orden)
+        pub fn cantidad_publicaciones(&self) -> u32 {
+            self.contador_publicacion
         }
     }
     /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
@@ -1445,6 +2544,17 @@ mod market_place {
                 .ok();
             contract
         }
+        #[test]
+        fn test_estado_orden_conserva_codificacion() {
+            use ink::scale::Encode;
+            // Las órdenes ya guardadas tienen que seguir decodificando al mismo estado
+            assert_eq!(EstadoOrden::Pendiente.encode(), vec![0]);
+            assert_eq!(EstadoOrden::Enviado.encode(), vec![1]);
+            assert_eq!(EstadoOrden::Recibido.encode(), vec![2]);
+            assert_eq!(EstadoOrden::Cancelada.encode(), vec![3]);
+            assert_eq!(EstadoOrden::EnviadoParcialmente.encode(), vec![4]);
+        }
+
         #[test]
         fn test_orden_enviada_ok() {
             let mut orden = Orden::new(1, account(1), account(2), 10, 3, 300);
@@ -2058,9 +3168,16 @@ mod market_place {
                 .insert((vendedor, id_producto), &deposito);
 
             // Ejecutar función
-            let result = contrato.actualizar_stock_producto(vendedor, id_producto, stock_a_vender);
+            let result =
+                contrato.actualizar_stock_producto(vendedor, id_producto, None, stock_a_vender);
             // Verificar que fue exitoso
-            assert_eq!(result, Ok(()));
+            assert_eq!(
+                result,
+                Ok(vec![StockAlmacen::new(
+                    ALMACEN_PRINCIPAL.to_string(),
+                    stock_a_vender
+                )])
+            );
 
             // Verificar que el nuevo stock es correcto
             match contrato.stock_general.get(&(vendedor, id_producto)) {
@@ -2085,7 +3202,8 @@ mod market_place {
                 .stock_general
                 .insert((vendedor, id_producto), &deposito);
 
-            let result = contrato.actualizar_stock_producto(vendedor, id_producto, stock_a_vender);
+            let result =
+                contrato.actualizar_stock_producto(vendedor, id_producto, None, stock_a_vender);
 
             assert_eq!(result, Err(ErrorMarketplace::StockDepositoInsuficiente));
         }
@@ -2099,7 +3217,8 @@ mod market_place {
 
             // No se inserta el producto en el depósito
 
-            let result = contrato.actualizar_stock_producto(vendedor, id_producto, stock_a_vender);
+            let result =
+                contrato.actualizar_stock_producto(vendedor, id_producto, None, stock_a_vender);
 
             assert_eq!(result, Err(ErrorMarketplace::DepositoNoEncontrado));
         }
@@ -2118,7 +3237,8 @@ mod market_place {
                 .stock_general
                 .insert((vendedor, id_producto), &deposito);
 
-            let result = contrato.actualizar_stock_producto(vendedor, id_producto, stock_a_vender);
+            let result =
+                contrato.actualizar_stock_producto(vendedor, id_producto, None, stock_a_vender);
 
             assert_eq!(result, Err(ErrorMarketplace::StockDepositoInsuficiente));
         }
@@ -2467,6 +3587,604 @@ mod market_place {
 
             assert_eq!(result, Err(ErrorMarketplace::Overflow));
         }
+
+        //Tests de almacenes
+        fn contrato_con_almacenes() -> MarketPlace {
+            let mut contrato = contract_dummy();
+            let vendedor = account(2);
+            contrato
+                ._registrar_producto(
+                    vendedor,
+                    "Silla".to_string(),
+                    "Silla de madera".to_string(),
+                    Categoria::Hogar,
+                    10,
+                )
+                .unwrap();
+            contrato
+                ._registrar_almacen(vendedor, "Rosario".to_string())
+                .unwrap();
+            contrato
+        }
+
+        #[ink::test]
+        fn registrar_almacen_ok() {
+            let mut contrato = contrato_con_almacenes();
+            let vendedor = account(2);
+
+            let res = contrato._registrar_almacen(vendedor, "  Cordoba ".to_string());
+            assert_eq!(res, Ok(()));
+            assert_eq!(
+                contrato._ver_almacenes(vendedor),
+                vec![
+                    ALMACEN_PRINCIPAL.to_string(),
+                    "rosario".to_string(),
+                    "cordoba".to_string()
+                ]
+            );
+        }
+
+        #[ink::test]
+        fn registrar_almacen_errores() {
+            let mut contrato = contrato_con_almacenes();
+
+            let res = contrato._registrar_almacen(account(2), "ROSARIO".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::AlmacenYaExiste));
+            let res = contrato._registrar_almacen(account(2), "Principal".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::AlmacenYaExiste));
+            let res = contrato._registrar_almacen(account(2), "   ".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::NombreInvalido));
+            let res = contrato._registrar_almacen(account(1), "Casa".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::RolInvalido));
+        }
+
+        #[ink::test]
+        fn registrar_almacen_respeta_el_limite() {
+            let mut contrato = contrato_con_almacenes();
+            let vendedor = account(2);
+
+            // contrato_con_almacenes ya registra "rosario"
+            for i in 1..MAX_ALMACENES_POR_VENDEDOR {
+                let res = contrato._registrar_almacen(vendedor, format!("Almacen {}", i));
+                assert_eq!(res, Ok(()));
+            }
+            let res = contrato._registrar_almacen(vendedor, "Uno mas".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::LimiteAlmacenesAlcanzado));
+            assert_eq!(
+                contrato._ver_almacenes(vendedor).len(),
+                MAX_ALMACENES_POR_VENDEDOR as usize + 1
+            );
+            // El límite es por vendedor
+            assert_eq!(contrato._registrar_almacen(account(3), "Otro".to_string()), Ok(()));
+        }
+
+        #[ink::test]
+        fn modificar_stock_almacen_actualiza_total() {
+            let mut contrato = contrato_con_almacenes();
+            let vendedor = account(2);
+
+            let res = contrato._modificar_stock_almacen(
+                vendedor,
+                "Silla".to_string(),
+                "Rosario".to_string(),
+                5,
+            );
+            assert_eq!(res, Ok(()));
+            assert_eq!(contrato.obtener_stock_deposito(vendedor, 1), Ok(15));
+
+            let res = contrato._modificar_stock_almacen(
+                vendedor,
+                "Silla".to_string(),
+                "Mendoza".to_string(),
+                5,
+            );
+            assert_eq!(res, Err(ErrorMarketplace::AlmacenNoExiste));
+        }
+
+        #[ink::test]
+        fn transferir_stock_registra_historial() {
+            let mut contrato = contrato_con_almacenes();
+            let vendedor = account(2);
+
+            let res = contrato._transferir_stock(
+                vendedor,
+                "Silla".to_string(),
+                "principal".to_string(),
+                "Rosario".to_string(),
+                4,
+                1_000,
+            );
+            assert_eq!(res, Ok(1));
+
+            let deposito = contrato.stock_general.get((vendedor, 1)).unwrap();
+            assert_eq!(deposito.stock, 10);
+            assert_eq!(deposito.stock_en(ALMACEN_PRINCIPAL), 6);
+            assert_eq!(deposito.stock_en("rosario"), 4);
+
+            let transferencias = contrato._ver_transferencias(vendedor);
+            assert_eq!(transferencias.len(), 1);
+            assert_eq!(transferencias[0].origen, ALMACEN_PRINCIPAL.to_string());
+            assert_eq!(transferencias[0].destino, "rosario".to_string());
+            assert_eq!(transferencias[0].cantidad, 4);
+            assert_eq!(transferencias[0].fecha, 1_000);
+            assert!(contrato._ver_transferencias(account(3)).is_empty());
+        }
+
+        #[ink::test]
+        fn transferir_stock_errores() {
+            let mut contrato = contrato_con_almacenes();
+            let vendedor = account(2);
+            let mut transferir = |origen: &str, destino: &str, cantidad: u32| {
+                contrato._transferir_stock(
+                    vendedor,
+                    "Silla".to_string(),
+                    origen.to_string(),
+                    destino.to_string(),
+                    cantidad,
+                    0,
+                )
+            };
+
+            assert_eq!(
+                transferir("principal", "Mendoza", 1),
+                Err(ErrorMarketplace::AlmacenNoExiste)
+            );
+            assert_eq!(
+                transferir("rosario", "Rosario", 1),
+                Err(ErrorMarketplace::TransferenciaInvalida)
+            );
+            assert_eq!(
+                transferir("principal", "rosario", 0),
+                Err(ErrorMarketplace::TransferenciaInvalida)
+            );
+            assert_eq!(
+                transferir("rosario", "principal", 1),
+                Err(ErrorMarketplace::StockAlmacenInsuficiente)
+            );
+            assert_eq!(contrato.contador_transferencias, 0);
+        }
+
+        #[ink::test]
+        fn crear_publicacion_desde_almacen_reserva_de_ese_almacen() {
+            let mut contrato = contrato_con_almacenes();
+            let vendedor = account(2);
+            contrato
+                ._transferir_stock(
+                    vendedor,
+                    "Silla".to_string(),
+                    "principal".to_string(),
+                    "rosario".to_string(),
+                    4,
+                    0,
+                )
+                .unwrap();
+
+            let res = contrato._crear_publicacion_desde_almacen(
+                "Silla".to_string(),
+                vendedor,
+                Some("Rosario".to_string()),
+                5,
+                100,
+            );
+            assert_eq!(res, Err(ErrorMarketplace::StockAlmacenInsuficiente));
+
+            let res = contrato._crear_publicacion_desde_almacen(
+                "Silla".to_string(),
+                vendedor,
+                Some("Rosario".to_string()),
+                3,
+                100,
+            );
+            assert_eq!(res, Ok(()));
+
+            let publicacion = contrato.publicaciones.get(1).unwrap();
+            assert_eq!(
+                publicacion.reservas,
+                vec![StockAlmacen::new("rosario".to_string(), 3)]
+            );
+            let deposito = contrato.stock_general.get((vendedor, 1)).unwrap();
+            assert_eq!(deposito.stock, 7);
+            assert_eq!(deposito.stock_en(ALMACEN_PRINCIPAL), 6);
+            assert_eq!(deposito.stock_en("rosario"), 1);
+        }
+
+        #[ink::test]
+        fn crear_orden_registra_almacen_de_despacho() {
+            let mut contrato = contrato_con_almacenes();
+            let vendedor = account(2);
+            let comprador = account(1);
+            contrato
+                ._transferir_stock(
+                    vendedor,
+                    "Silla".to_string(),
+                    "principal".to_string(),
+                    "rosario".to_string(),
+                    8,
+                    0,
+                )
+                .unwrap();
+            // Sin almacén elegido se reservan 2 del principal y 6 de rosario
+            contrato
+                ._crear_publicacion("Silla".to_string(), vendedor, 8, 100)
+                .unwrap();
+
+            // Rosario alcanza para toda la orden
+            contrato._crear_orden(comprador, 1, 5, 500).unwrap();
+            let orden = contrato.ordenes.get(0).unwrap();
+            assert_eq!(
+                orden.almacenes,
+                vec![StockAlmacen::new("rosario".to_string(), 5)]
+            );
+
+            // Ningún almacén alcanza solo, la orden se reparte
+            contrato._crear_orden(comprador, 1, 3, 300).unwrap();
+            let orden = contrato.ordenes.get(1).unwrap();
+            assert_eq!(
+                orden.almacenes,
+                vec![
+                    StockAlmacen::new(ALMACEN_PRINCIPAL.to_string(), 2),
+                    StockAlmacen::new("rosario".to_string(), 1)
+                ]
+            );
+
+            let publicacion = contrato.publicaciones.get(1).unwrap();
+            assert_eq!(publicacion.stock_a_vender, 0);
+            assert!(publicacion.reservas.is_empty());
+            assert_eq!(
+                contrato._crear_orden(comprador, 1, 1, 100),
+                Err(ErrorMarketplace::StockInsuficiente)
+            );
+        }
+
+        //Tests de envios parciales
+        fn orden_de_diez() -> Orden {
+            Orden::new(1, account(1), account(2), 10, 10, 1000)
+        }
+
+        #[test]
+        fn registrar_envio_parcial_y_final() {
+            let mut orden = orden_de_diez();
+
+            let res =
+                orden.registrar_envio(account(2), 4, "Andreani".to_string(), "AN-1".to_string());
+            assert_eq!(res, Ok(1));
+            assert_eq!(orden.estado, EstadoOrden::EnviadoParcialmente);
+
+            let res = orden.registrar_envio(account(2), 7, "OCA".to_string(), "OC-2".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::CantidadEnvioInvalida));
+
+            let res = orden.registrar_envio(account(2), 6, "OCA".to_string(), "OC-2".to_string());
+            assert_eq!(res, Ok(2));
+            assert_eq!(orden.estado, EstadoOrden::Enviado);
+            assert_eq!(orden.cantidad_enviada(), 10);
+
+            let res = orden.registrar_envio(account(2), 1, "OCA".to_string(), "OC-3".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::EstadoInvalido));
+        }
+
+        #[test]
+        fn registrar_envio_errores() {
+            let mut orden = orden_de_diez();
+
+            let res = orden.registrar_envio(account(1), 1, "OCA".to_string(), "OC-1".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::NoEsVendedor));
+            let res = orden.registrar_envio(account(2), 0, "OCA".to_string(), "OC-1".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::CantidadEnvioInvalida));
+            let res = orden.registrar_envio(account(2), 1, " ".to_string(), "OC-1".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::DatosEnvioInvalidos));
+
+            orden
+                .registrar_envio(account(2), 1, "OCA".to_string(), "OC-1".to_string())
+                .unwrap();
+            assert_eq!(
+                orden.marcar_enviada(account(2)),
+                Err(ErrorMarketplace::EstadoInvalido)
+            );
+
+            orden.estado = EstadoOrden::Cancelada;
+            let res = orden.registrar_envio(account(2), 1, "OCA".to_string(), "OC-2".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::OrdenCancelada));
+        }
+
+        #[test]
+        fn confirmar_recepcion_por_envio() {
+            let mut orden = orden_de_diez();
+            orden
+                .registrar_envio(account(2), 4, "OCA".to_string(), "OC-1".to_string())
+                .unwrap();
+
+            // Recibir el único envío no alcanza si faltan unidades por despachar
+            assert_eq!(orden.confirmar_recepcion_envio(account(1), 1), Ok(()));
+            assert_eq!(orden.estado, EstadoOrden::EnviadoParcialmente);
+            assert_eq!(
+                orden.confirmar_recepcion_envio(account(1), 1),
+                Err(ErrorMarketplace::EnvioYaRecibido)
+            );
+
+            orden
+                .registrar_envio(account(2), 6, "OCA".to_string(), "OC-2".to_string())
+                .unwrap();
+            assert_eq!(
+                orden.confirmar_recepcion_envio(account(2), 2),
+                Err(ErrorMarketplace::NoEsComprador)
+            );
+            assert_eq!(
+                orden.confirmar_recepcion_envio(account(1), 3),
+                Err(ErrorMarketplace::EnvioNoExiste)
+            );
+            assert_eq!(orden.confirmar_recepcion_envio(account(1), 2), Ok(()));
+            assert_eq!(orden.estado, EstadoOrden::Recibido);
+        }
+
+        #[ink::test]
+        fn envios_parciales_desde_el_contrato() {
+            let mut contrato = contract_dummy();
+            contrato.ordenes.insert(1, &orden_de_diez());
+
+            let res =
+                contrato._registrar_envio(account(2), 1, 5, "OCA".to_string(), "OC-1".to_string());
+            assert_eq!(res, Ok(1));
+            let res =
+                contrato._registrar_envio(account(2), 9, 5, "OCA".to_string(), "OC-1".to_string());
+            assert_eq!(res, Err(ErrorMarketplace::OrdenNoExiste));
+
+            assert_eq!(
+                contrato._confirmar_recepcion_envio(account(1), 1, 1),
+                Ok(())
+            );
+
+            set_caller(account(3));
+            assert_eq!(contrato.ver_envios(1), Err(ErrorMarketplace::NoAutorizado));
+            set_caller(account(1));
+            let envios = contrato.ver_envios(1).unwrap();
+            assert_eq!(envios.len(), 1);
+            assert!(envios[0].recibido);
+            assert_eq!(
+                contrato.ordenes.get(1).unwrap().estado,
+                EstadoOrden::EnviadoParcialmente
+            );
+        }
+
+        //Tests de busqueda
+        fn contrato_con_catalogo() -> MarketPlace {
+            let mut contrato = contract_dummy();
+            let vendedor = account(2);
+            let productos = [
+                ("Mesa de Roble", Categoria::Hogar, 300),
+                ("Silla de roble", Categoria::Hogar, 100),
+                ("Remera de algodon", Categoria::Indumentaria, 50),
+                ("Mesa ratona", Categoria::Hogar, 200),
+            ];
+            for (nombre, categoria, precio) in productos {
+                contrato
+                    ._registrar_producto(
+                        vendedor,
+                        nombre.to_string(),
+                        "Descripcion".to_string(),
+                        categoria,
+                        10,
+                    )
+                    .unwrap();
+                contrato
+                    ._crear_publicacion(nombre.to_string(), vendedor, 5, precio)
+                    .unwrap();
+            }
+            contrato
+        }
+
+        fn ids(publicaciones: Vec<Publicacion>) -> Vec<u32> {
+            publicaciones.iter().map(|p| p.id_publicacion).collect()
+        }
+
+        #[ink::test]
+        fn tokenizar_nombre_normaliza_y_deduplica() {
+            let tokens = Producto::tokenizar_nombre(&"  Mesa-de ROBLE, mesa ".to_string());
+            assert_eq!(
+                tokens,
+                vec!["mesa".to_string(), "roble".to_string()]
+            );
+        }
+
+        #[ink::test]
+        fn registrar_producto_indexa_nombre() {
+            let contrato = contrato_con_catalogo();
+            assert_eq!(
+                contrato.productos_de_palabra(&"mesa".to_string()),
+                vec![4, 1]
+            );
+            assert_eq!(
+                contrato.productos_de_palabra(&"roble".to_string()),
+                vec![2, 1]
+            );
+            assert_eq!(
+                contrato.productos_de_palabra(&"ratona".to_string()),
+                vec![4]
+            );
+            assert_eq!(
+                contrato.indice_busqueda.get(("mesa".to_string(), 0)),
+                Some(1)
+            );
+            // Las palabras comunes no se indexan
+            assert_eq!(
+                contrato
+                    .cantidad_productos_por_palabra
+                    .get("de".to_string()),
+                None
+            );
+            // Cada producto conoce sus publicaciones
+            assert_eq!(contrato.cantidad_publicaciones_por_producto.get(4), Some(1));
+            assert_eq!(contrato.publicaciones_por_producto.get((4, 0)), Some(4));
+        }
+
+        #[ink::test]
+        fn indice_busqueda_lee_los_productos_mas_recientes_por_palabra() {
+            let mut contrato = contract_dummy();
+            let vendedor = account(2);
+            for i in 0..=MAX_PRODUCTOS_POR_PALABRA {
+                let nombre = format!("Lampara {}", i);
+                contrato
+                    ._registrar_producto(
+                        vendedor,
+                        nombre.clone(),
+                        "Descripcion".to_string(),
+                        Categoria::Hogar,
+                        10,
+                    )
+                    .unwrap();
+                contrato
+                    ._crear_publicacion(nombre, vendedor, 1, 100)
+                    .unwrap();
+            }
+            // El índice guarda todos los productos de la palabra
+            assert_eq!(
+                contrato
+                    .cantidad_productos_por_palabra
+                    .get("lampara".to_string()),
+                Some(MAX_PRODUCTOS_POR_PALABRA + 1)
+            );
+            // Al buscar se leen los más recientes, así el último producto se encuentra
+            let productos = contrato.productos_de_palabra(&"lampara".to_string());
+            assert_eq!(productos.len(), MAX_PRODUCTOS_POR_PALABRA as usize);
+            assert_eq!(productos[0], MAX_PRODUCTOS_POR_PALABRA + 1);
+            assert!(!productos.contains(&1));
+            let res = contrato.buscar_publicaciones(
+                format!("lampara {}", MAX_PRODUCTOS_POR_PALABRA),
+                None,
+                None,
+                None,
+                0,
+                MAX_RESULTADOS_BUSQUEDA,
+            );
+            assert_eq!(ids(res)[0], MAX_PRODUCTOS_POR_PALABRA + 1);
+        }
+
+        #[ink::test]
+        fn buscar_publicaciones_incluye_todas_las_publicaciones_del_producto() {
+            let mut contrato = contrato_con_catalogo();
+            // account(3) tiene rol Ambos y publica el mismo producto
+            let otro_vendedor = account(3);
+            contrato
+                ._registrar_producto(
+                    otro_vendedor,
+                    "Mesa ratona".to_string(),
+                    "Descripcion".to_string(),
+                    Categoria::Hogar,
+                    10,
+                )
+                .unwrap();
+            contrato
+                ._crear_publicacion("Mesa ratona".to_string(), otro_vendedor, 5, 150)
+                .unwrap();
+
+            let res = contrato.buscar_publicaciones("ratona".to_string(), None, None, None, 0, 10);
+            assert_eq!(ids(res), vec![4, 5]);
+            let res =
+                contrato.buscar_publicaciones("ratona".to_string(), None, None, Some(180), 0, 10);
+            assert_eq!(ids(res), vec![5]);
+        }
+
+        #[ink::test]
+        fn buscar_publicaciones_ordena_por_coincidencias() {
+            let contrato = contrato_con_catalogo();
+
+            let res =
+                contrato.buscar_publicaciones("mesa roble".to_string(), None, None, None, 0, 10);
+            assert_eq!(ids(res), vec![1, 2, 4]);
+
+            let res = contrato.buscar_publicaciones("Ropero".to_string(), None, None, None, 0, 10);
+            assert!(res.is_empty());
+        }
+
+        #[ink::test]
+        fn buscar_publicaciones_filtra_por_categoria_y_precio() {
+            let contrato = contrato_con_catalogo();
+
+            let res = contrato.buscar_publicaciones(
+                "de".to_string(),
+                Some(Categoria::Indumentaria),
+                None,
+                None,
+                0,
+                10,
+            );
+            assert_eq!(ids(res), vec![3]);
+
+            let res = contrato.buscar_publicaciones(
+                "".to_string(),
+                Some(Categoria::Hogar),
+                Some(150),
+                Some(300),
+                0,
+                10,
+            );
+            assert_eq!(ids(res), vec![1, 4]);
+        }
+
+        #[ink::test]
+        fn buscar_publicaciones_pagina_resultados() {
+            let contrato = contrato_con_catalogo();
+
+            let res = contrato.buscar_publicaciones("".to_string(), None, None, None, 1, 2);
+            assert_eq!(ids(res), vec![2, 3]);
+
+            let res = contrato.buscar_publicaciones("".to_string(), None, None, None, 3, 10);
+            assert_eq!(ids(res), vec![4]);
+
+            let res = contrato.buscar_publicaciones("".to_string(), None, None, None, 0, 0);
+            assert!(res.is_empty());
+        }
+
+        #[ink::test]
+        fn buscar_publicaciones_vacia_revisa_una_ventana_del_catalogo() {
+            let mut contrato = contract_dummy();
+            let vendedor = account(2);
+            for i in 1..=MAX_PUBLICACIONES_EXAMINADAS + 1 {
+                let nombre = format!("Producto {}", i);
+                let categoria = if i > MAX_PUBLICACIONES_EXAMINADAS {
+                    Categoria::Indumentaria
+                } else {
+                    Categoria::Hogar
+                };
+                contrato
+                    ._registrar_producto(
+                        vendedor,
+                        nombre.clone(),
+                        "Descripcion".to_string(),
+                        categoria,
+                        10,
+                    )
+                    .unwrap();
+                contrato
+                    ._crear_publicacion(nombre, vendedor, 1, 100)
+                    .unwrap();
+            }
+            assert_eq!(
+                contrato.cantidad_publicaciones(),
+                MAX_PUBLICACIONES_EXAMINADAS + 1
+            );
+
+            // La primera ventana no tiene publicaciones de la categoría
+            let res = contrato.buscar_publicaciones(
+                "".to_string(),
+                Some(Categoria::Indumentaria),
+                None,
+                None,
+                0,
+                10,
+            );
+            assert!(res.is_empty());
+
+            // Se sigue desde donde terminó la ventana anterior
+            let res = contrato.buscar_publicaciones(
+                "".to_string(),
+                Some(Categoria::Indumentaria),
+                None,
+                None,
+                MAX_PUBLICACIONES_EXAMINADAS,
+                10,
+            );
+            assert_eq!(ids(res), vec![MAX_PUBLICACIONES_EXAMINADAS + 1]);
+        }
     }
 }
 
@@ -33,21 +33,34 @@ mod rustaceo_libre {
         ErrorModificarCantidadOfertada,
         ErrorVerPublicacionesVendedor,
         ErrorRealizarPublicacion,
+        ErrorPausarReanudarPublicacion,
+        ErrorFinalizarPublicacionVencida,
     };
 
     use crate::structs::pedido::{
         Pedido,
         EstadoPedido,
+        ResolucionDisputa,
+        PLAZO_RECLAMO_FONDOS_POR_DEFECTO,
+        PLAZO_DISPUTA_POR_DEFECTO,
         ErrorProductoDespachado,
         ErrorProductoRecibido,
         ErrorCalificarPedido,
         ErrorComprarProducto,
         ErrorCancelarPedido,
         ErrorReclamarFondos,
+        ErrorAbrirDisputa,
+        ErrorResolverDisputa,
         ErrorVerCompras,
         ErrorVerVentas,
     };
 
+    use crate::structs::resena::{
+        Resena,
+        ErrorResenarPedido,
+        ErrorResponderResena,
+    };
+
     //
     // RustaceoLibre: main struct
     //
@@ -63,6 +76,8 @@ mod rustaceo_libre {
         pub productos: BTreeMap<u128, Producto>,
         /// <ID, Publicacion>
         pub publicaciones: BTreeMap<u128, Publicacion>,
+        /// <ID del pedido, Resena>
+        pub resenas: BTreeMap<u128, Resena>,
         /// Lleva un recuento de la próxima ID disponible para las compras.
         pedidos_siguiente_id: u128,
         /// Lleva un recuento de la próxima ID disponible para los productos.
@@ -71,33 +86,45 @@ mod rustaceo_libre {
         publicaciones_siguiente_id: u128,
         /// ID del dueño del contrato
         pub owner: AccountId,
+        /// ID de la cuenta que resuelve las disputas
+        pub arbitro: AccountId,
+        /// Milisegundos desde el despacho a partir de los cuales el vendedor puede reclamar los fondos
+        pub plazo_reclamo_fondos: u64,
+        /// Milisegundos desde el despacho durante los cuales el comprador puede abrir una disputa
+        pub plazo_disputa: u64,
     }
 
     #[ink(impl)]
     impl RustaceoLibre {
-        /// Construye un nuevo contrato con sus valores por defecto
+        /// Construye un nuevo contrato con los plazos (en milisegundos) de reclamo de fondos y de disputa,
+        /// y la cuenta del árbitro que resolverá las disputas
         #[ink(constructor)]
-        pub fn new() -> Self {
-            Self::_new()
+        pub fn new(plazo_reclamo_fondos: u64, plazo_disputa: u64, arbitro: AccountId) -> Self {
+            Self::_new(plazo_reclamo_fondos, plazo_disputa, arbitro)
         }
 
-        /// Constructor por defecto (Ídem new())
+        /// Constructor por defecto: 60 días para reclamar fondos, 30 días para abrir una disputa
+        /// y el dueño del contrato como árbitro
         #[ink(constructor)]
         pub fn default() -> Self {
-            Self::_new()
+            Self::_new(PLAZO_RECLAMO_FONDOS_POR_DEFECTO, PLAZO_DISPUTA_POR_DEFECTO, Self::env().caller())
         }
 
         /// Crea una nueva instancia de RustaceoLibre
-        fn _new() -> Self {
+        fn _new(plazo_reclamo_fondos: u64, plazo_disputa: u64, arbitro: AccountId) -> Self {
             Self {
                 usuarios: Default::default(),
                 pedidos: Default::default(),
                 productos: Default::default(),
                 publicaciones: Default::default(),
+                resenas: Default::default(),
                 pedidos_siguiente_id: 0,
                 productos_siguiente_id: 0,
                 publicaciones_siguiente_id: 0,
                 owner: Self::env().caller(),
+                arbitro,
+                plazo_reclamo_fondos,
+                plazo_disputa,
             }
         }
 
@@ -143,6 +170,41 @@ mod rustaceo_libre {
             self._modificar_cantidad_ofertada(self.env().caller(), id_publicacion, nueva_cantidad_ofertada)
         }
 
+        /// Realiza una publicación con producto, precio, cantidad y un período de vigencia opcional.
+        /// La publicación admite compras desde `inicio` hasta `fin`; al vencer, el remanente puede devolverse al vendedor.
+        /// 
+        /// Devuelve Error si el precio o la cantidad son 0, si `caller` no existe o no es vendedor, o si el período es inválido.
+        #[ink(message)]
+        pub fn realizar_publicacion_programada(&mut self, id_producto: u128, cantidad_ofertada: u32, precio: Balance, inicio: Option<u64>, fin: Option<u64>) -> Result<u128, ErrorRealizarPublicacion> {
+            self._realizar_publicacion_programada(self.env().block_timestamp(), self.env().caller(), id_producto, cantidad_ofertada, precio, (inicio, fin))
+        }
+
+        /// Pausa una publicación propia, impidiendo que se realicen compras sobre ella.
+        /// 
+        /// Devuelve Error si el usuario no está registrado, la publicación no existe,
+        /// el usuario no es el vendedor o la publicación ya estaba pausada.
+        #[ink(message)]
+        pub fn pausar_publicacion(&mut self, id_publicacion: u128) -> Result<(), ErrorPausarReanudarPublicacion> {
+            self._pausar_publicacion(self.env().caller(), id_publicacion)
+        }
+
+        /// Reanuda una publicación propia que estaba pausada.
+        /// 
+        /// Devuelve Error si el usuario no está registrado, la publicación no existe,
+        /// el usuario no es el vendedor o la publicación no estaba pausada.
+        #[ink(message)]
+        pub fn reanudar_publicacion(&mut self, id_publicacion: u128) -> Result<(), ErrorPausarReanudarPublicacion> {
+            self._reanudar_publicacion(self.env().caller(), id_publicacion)
+        }
+
+        /// Finaliza una publicación vencida, devolviendo la cantidad ofertada no vendida al stock del vendedor.
+        /// 
+        /// Devuelve la cantidad devuelta, o Error si la publicación no existe, no venció o no le queda stock.
+        #[ink(message)]
+        pub fn finalizar_publicacion_vencida(&mut self, id_publicacion: u128) -> Result<u32, ErrorFinalizarPublicacionVencida> {
+            self._finalizar_publicacion_vencida(self.env().block_timestamp(), self.env().caller(), id_publicacion)
+        }
+
         /// Dada una ID, devuelve la publicación
         /// 
         /// Devolverá None si la publicación no existe o el usuario no está registrado
@@ -236,11 +298,11 @@ mod rustaceo_libre {
 
         /// Política de reclamo:
         /// 
-        /// Si el vendedor despachó el producto y el comprador no lo marcó como recibido después de 60 días,
-        /// el vendedor puede reclamar los fondos del pedido y la misma se marcará automáticamente como recibida,
-        /// sin necesidad de consentimiento ni voluntad del comprador.
+        /// Si el vendedor despachó el producto y el comprador no lo marcó como recibido después del plazo de reclamo
+        /// (60 días por defecto), el vendedor puede reclamar los fondos del pedido y la misma se marcará automáticamente
+        /// como recibida, sin necesidad de consentimiento ni voluntad del comprador.
         /// 
-        /// Puede dar error si el usuario no está registrado, la transacción no existe,
+        /// Puede dar error si el usuario no está registrado, la transacción no existe, el pedido está en disputa,
         /// el usuario no es el vendedor de la publicación o el tiempo pasado no condice con la política de reclamo
         #[ink(message)]
         pub fn reclamar_fondos(&mut self, id_compra: u128) -> Result<u128, ErrorReclamarFondos> {
@@ -312,6 +374,86 @@ mod rustaceo_libre {
             Ok(true)
         }
 
+        /// Abre una disputa sobre un pedido despachado, congelando sus fondos hasta que el árbitro la resuelva.
+        /// Sólo el comprador puede abrirla y sólo dentro del plazo de disputa desde el despacho.
+        /// 
+        /// Puede dar error si el usuario no está registrado, el pedido no existe, no es el comprador,
+        /// el pedido no está despachado o ya pasó el plazo de disputa.
+        #[ink(message)]
+        pub fn abrir_disputa(&mut self, id_pedido: u128) -> Result<(), ErrorAbrirDisputa> {
+            self._abrir_disputa(self.env().block_timestamp(), self.env().caller(), id_pedido)
+        }
+
+        /// Resuelve una disputa abierta. Sólo puede hacerlo el árbitro del contrato.
+        /// Transfiere los fondos del pedido según la resolución: reembolso total al comprador,
+        /// liberación total al vendedor o reparto con el porcentaje que recibe el vendedor.
+        /// 
+        /// Puede dar error si quien resuelve no es el árbitro, el pedido no existe,
+        /// no está en disputa, el porcentaje del reparto es mayor a 100 o falla alguna transferencia.
+        #[ink(message)]
+        pub fn resolver_disputa(&mut self, id_pedido: u128, resolucion: ResolucionDisputa) -> Result<(), ErrorResolverDisputa> {
+            let operacion = self._resolver_disputa(self.env().block_timestamp(), self.env().caller(), id_pedido, resolucion);
+
+            let Ok(resultado) = operacion
+            else { return Err(operacion.unwrap_err()) };
+
+            // si alguna transferencia falla se devuelve error y el mensaje se revierte
+            if resultado.monto_comprador > 0 && self.env().transfer(resultado.comprador, resultado.monto_comprador).is_err() {
+                return Err(ErrorResolverDisputa::TransferenciaFallida);
+            }
+
+            if resultado.monto_vendedor > 0 && self.env().transfer(resultado.vendedor, resultado.monto_vendedor).is_err() {
+                return Err(ErrorResolverDisputa::TransferenciaFallida);
+            }
+
+            if let Some(pedido) = self.pedidos.get_mut(&id_pedido) {
+                pedido.fondos_fueron_transferidos = true;
+            }
+
+            Ok(())
+        }
+
+        //
+        // resena.rs    /////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+        //
+
+        /// Dada una ID de pedido recibido, el comprador califica (1..=5) al vendedor y deja un comentario
+        /// de hasta `MAX_LARGO_COMENTARIO` bytes. Es la alternativa con comentario a `calificar_pedido`:
+        /// cada pedido se califica una sola vez, con o sin reseña.
+        /// 
+        /// Devolverá error si el comentario es demasiado largo, la calificación no es válida,
+        /// el usuario no está registrado, el pedido no existe, no fue recibido, el usuario no es el comprador
+        /// o ya calificó el pedido.
+        #[ink(message)]
+        pub fn resenar_pedido(&mut self, id_pedido: u128, calificacion: u8, comentario: String) -> Result<(), ErrorResenarPedido> {
+            self._resenar_pedido(self.env().block_timestamp(), self.env().caller(), id_pedido, calificacion, comentario)
+        }
+
+        /// El vendedor responde públicamente la reseña de un pedido. Sólo se puede responder una vez.
+        /// 
+        /// Devolverá error si la respuesta está vacía o es demasiado larga, la reseña no existe,
+        /// el usuario no es el vendedor o la reseña ya fue respondida.
+        #[ink(message)]
+        pub fn responder_resena(&mut self, id_pedido: u128, respuesta: String) -> Result<(), ErrorResponderResena> {
+            self._responder_resena(self.env().block_timestamp(), self.env().caller(), id_pedido, respuesta)
+        }
+
+        /// Devuelve las reseñas recibidas por un vendedor, de la más reciente a la más antigua,
+        /// paginadas con `offset` y `limit` (como máximo `MAX_RESENAS_POR_PAGINA` por página).
+        #[ink(message)]
+        pub fn ver_resenas_vendedor(&self, vendedor: AccountId, offset: u32, limit: u32) -> Vec<Resena> {
+            self._ver_resenas_vendedor(vendedor, offset, limit)
+        }
+
+        /// Reputación del vendedor en centésimas de estrella, ponderando más los pedidos recientes
+        /// y los de mayor valor.
+        /// 
+        /// Devolverá None si el vendedor no tiene calificaciones.
+        #[ink(message)]
+        pub fn ver_reputacion_vendedor(&self, vendedor: AccountId) -> Option<u16> {
+            self._ver_reputacion_vendedor(self.env().block_timestamp(), vendedor)
+        }
+
         //
         // pedido.rs: visualizar compras    ///////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
         //
@@ -448,8 +590,12 @@ mod rustaceo_libre {
 
         #[ink::test]
         fn new_works() {
-            let rustaceo_libre = RustaceoLibre::new();
+            let arbitro = AccountId::from([0x9; 32]);
+            let rustaceo_libre = RustaceoLibre::new(1_000, 500, arbitro);
             assert_eq!(rustaceo_libre.pedidos_siguiente_id, 0);
+            assert_eq!(rustaceo_libre.plazo_reclamo_fondos, 1_000);
+            assert_eq!(rustaceo_libre.plazo_disputa, 500);
+            assert_eq!(rustaceo_libre.arbitro, arbitro);
         }
 
         /// We test if the default constructor does its job.
@@ -457,12 +603,15 @@ mod rustaceo_libre {
         fn default_works() {
             let rustaceo_libre = RustaceoLibre::default();
             assert_eq!(rustaceo_libre.pedidos_siguiente_id, 0);
+            assert_eq!(rustaceo_libre.plazo_reclamo_fondos, PLAZO_RECLAMO_FONDOS_POR_DEFECTO);
+            assert_eq!(rustaceo_libre.plazo_disputa, PLAZO_DISPUTA_POR_DEFECTO);
+            assert_eq!(rustaceo_libre.arbitro, rustaceo_libre.owner);
         }
 
         /// We test a simple use case of our contract.
         #[ink::test]
         fn next_id_works() {
-            let mut rustaceo_libre = RustaceoLibre::new();
+            let mut rustaceo_libre = RustaceoLibre::default();
             assert_eq!(rustaceo_libre.next_id_pedidos(), 0);
             assert_eq!(rustaceo_libre.next_id_pedidos(), 1);
             assert_eq!(rustaceo_libre.next_id_productos(), 0);
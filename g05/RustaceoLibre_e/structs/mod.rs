@@ -1,4 +1,5 @@
 pub(crate) mod usuario;
 pub(crate) mod producto;
 pub(crate) mod publicacion;
-pub(crate) mod pedido;
\ No newline at end of file
+pub(crate) mod pedido;
+pub(crate) mod resena;
\ No newline at end of file
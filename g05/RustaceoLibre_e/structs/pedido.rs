@@ -2,6 +2,16 @@ use ink::{primitives::AccountId, prelude::vec::Vec};
 
 use crate::{rustaceo_libre::RustaceoLibre, structs::producto::CategoriaProducto};
 
+//
+// plazos por defecto (en milisegundos)
+//
+
+/// Plazo por defecto desde el despacho para que el vendedor pueda reclamar los fondos: 60 días (1000ms*60s*60m*24h*60d)
+pub const PLAZO_RECLAMO_FONDOS_POR_DEFECTO: u64 = 5_184_000_000;
+
+/// Plazo por defecto desde el despacho para que el comprador pueda abrir una disputa: 30 días (1000ms*60s*60m*24h*30d)
+pub const PLAZO_DISPUTA_POR_DEFECTO: u64 = 2_592_000_000;
+
 //
 // estado pedido
 //
@@ -17,6 +27,24 @@ pub enum EstadoPedido { // (u64 -> timestamp)
     Despachado(u64), // por el vendedor
     Recibido(u64),   // por el comprador
     Cancelado(u64),
+    EnDisputa(u64),  // abierta por el comprador
+    DisputaResuelta(u64, ResolucionDisputa), // por el árbitro
+}
+
+//
+// resolución de disputa
+//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout)
+)]
+pub enum ResolucionDisputa {
+    ReembolsoTotal, // todos los fondos vuelven al comprador
+    LiberarFondos,  // todos los fondos van al vendedor
+    Reparto(u8),    // (u8 -> porcentaje 0..=100 de los fondos para el vendedor). el resto vuelve al comprador
 }
 
 //
@@ -86,6 +114,9 @@ pub enum ErrorComprarProducto {
     VendedorInexistente,
     StockInsuficiente,
     ValorTransferidoInsuficiente,
+    PublicacionPausada,
+    PublicacionNoIniciada,
+    PublicacionVencida,
     Desconocido
 }
 
@@ -119,6 +150,8 @@ pub enum ErrorProductoRecibido {
     PedidoYaRecibido,
     PedidoNoDespachado,
     PedidoCancelado,
+    PedidoEnDisputa,
+    DisputaResuelta,
 }
 
 // cancelar pedido
@@ -136,6 +169,8 @@ pub enum ErrorCancelarPedido {
     PedidoYaRecibido,
     PedidoYaCancelado,
     EsperandoConfirmacionMutua, // sólo si quien ya solicitó la cancelación es quien hace el llamado a cancelar
+    PedidoEnDisputa,
+    DisputaResuelta,
 }
 
 // ver compras
@@ -179,6 +214,39 @@ pub enum ErrorReclamarFondos {
     NoConvalidaPoliticaDeReclamo,
     FondosYaTransferidos,
     EstadoNoEsDespachado,
+    PedidoEnDisputa,
+}
+
+// abrir disputa
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout)
+)]
+pub enum ErrorAbrirDisputa {
+    UsuarioNoRegistrado,
+    PedidoInexistente,
+    SoloCompradorPuede,
+    EstadoNoEsDespachado,
+    FueraDePlazoDeDisputa,
+}
+
+// resolver disputa
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout)
+)]
+pub enum ErrorResolverDisputa {
+    SoloArbitroPuede,
+    PedidoInexistente,
+    PedidoNoEstaEnDisputa,
+    PorcentajeInvalido,
+    TransferenciaFallida,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -204,6 +272,14 @@ pub struct ResultadoComprarProducto {
     pub monto_transferido_sobrante: u128
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub struct ResultadoResolverDisputa {
+    pub comprador: AccountId,
+    pub monto_comprador: u128, // fondos que se le devuelven al comprador
+    pub vendedor: AccountId,
+    pub monto_vendedor: u128,  // fondos que se le liberan al vendedor
+}
+
 impl RustaceoLibre {
 
     //
@@ -236,6 +312,19 @@ impl RustaceoLibre {
             return Err(ErrorComprarProducto::VendedorAutocomprandose);
         }
 
+        // validar que la publicación esté activa
+        if publicacion.pausada {
+            return Err(ErrorComprarProducto::PublicacionPausada);
+        }
+
+        if publicacion.no_iniciada(timestamp) {
+            return Err(ErrorComprarProducto::PublicacionNoIniciada);
+        }
+
+        if publicacion.vencida(timestamp) {
+            return Err(ErrorComprarProducto::PublicacionVencida);
+        }
+
         // validar vendedor
         let id_vendedor = publicacion.vendedor;
         let Some(vendedor) = self.usuarios.get(id_vendedor)
@@ -306,11 +395,13 @@ impl RustaceoLibre {
 
     /// Política de reclamo:
     /// 
-    /// Si el vendedor despachó el pedido y el comprador no lo marcó como recibido después de 60 días,
-    /// el vendedor puede reclamar los fondos del pedido y el mismo se marcará automáticamente como recibida,
-    /// sin necesidad de consentimiento ni voluntad del comprador.
+    /// Si el vendedor despachó el pedido y el comprador no lo marcó como recibido después del plazo de reclamo
+    /// (60 días por defecto, configurable en el constructor), el vendedor puede reclamar los fondos del pedido
+    /// y el mismo se marcará automáticamente como recibida, sin necesidad de consentimiento ni voluntad del comprador.
     /// 
-    /// Puede dar error si el usuario no está registrado, la transacción no existe,
+    /// Si el comprador abrió una disputa, los fondos quedan congelados hasta que el árbitro la resuelva.
+    /// 
+    /// Puede dar error si el usuario no está registrado, la transacción no existe, el pedido está en disputa,
     /// el usuario no es el vendedor de la publicación o el tiempo pasado no condice con la política de reclamo
     pub fn _reclamar_fondos(&mut self, timestamp: u64, caller: AccountId, id_compra: u128) -> Result<u128, ErrorReclamarFondos> {
         // validar usuario
@@ -332,6 +423,11 @@ impl RustaceoLibre {
             return Err(ErrorReclamarFondos::FondosYaTransferidos);
         }
 
+        // validar que no haya una disputa abierta
+        if matches!(compra.estado, EstadoPedido::EnDisputa(_)) {
+            return Err(ErrorReclamarFondos::PedidoEnDisputa);
+        }
+
         // validar politica de reclamo: estado del pedido
         let EstadoPedido::Despachado(timestamp_despacho) = compra.estado
         else { return Err(ErrorReclamarFondos::EstadoNoEsDespachado); };
@@ -340,11 +436,8 @@ impl RustaceoLibre {
         let Some(elapsed_time) = timestamp.checked_sub(timestamp_despacho)
         else { return Err(ErrorReclamarFondos::NoConvalidaPoliticaDeReclamo) };
 
-        // 30 días: 1000ms*60s*60m*24h*60d
-        let time_millis_60_days = 5_184_000_000u64;
-
-        // validar politica de reclamo: 60 días desde despacho
-        if elapsed_time < time_millis_60_days {
+        // validar politica de reclamo: plazo de reclamo desde despacho
+        if elapsed_time < self.plazo_reclamo_fondos {
             return Err(ErrorReclamarFondos::NoConvalidaPoliticaDeReclamo);
         }
 
@@ -449,6 +542,8 @@ impl RustaceoLibre {
             EstadoPedido::Despachado(_) => (),
             EstadoPedido::Recibido(_) => return Err(ErrorProductoRecibido::PedidoYaRecibido),
             EstadoPedido::Cancelado(_) => return Err(ErrorProductoRecibido::PedidoCancelado),
+            EstadoPedido::EnDisputa(_) => return Err(ErrorProductoRecibido::PedidoEnDisputa),
+            EstadoPedido::DisputaResuelta(..) => return Err(ErrorProductoRecibido::DisputaResuelta),
         }
 
         let vendedor = pedido.vendedor;
@@ -526,9 +621,10 @@ impl RustaceoLibre {
             comprador.calificar_como_comprador(calificacion);
             self.usuarios.insert(comprador.id, &comprador);
 
-            // guardar calificación en transaccion
+            // guardar calificación en transaccion. va en calificacion_vendedor: calificacion_comprador
+            // es la que dió el comprador y la que usa la reputación del vendedor
             let mut compra = compra.clone();
-            compra.calificacion_comprador = Some(calificacion);
+            compra.calificacion_vendedor = Some(calificacion);
             self.pedidos.insert(compra.id, compra);
             return Ok(())
         }
@@ -564,6 +660,8 @@ impl RustaceoLibre {
             EstadoPedido::Pendiente(_) | EstadoPedido::Despachado(_) => (),
             EstadoPedido::Recibido(_) => return Err(ErrorCancelarPedido::PedidoYaRecibido),
             EstadoPedido::Cancelado(_) => return Err(ErrorCancelarPedido::PedidoYaCancelado),
+            EstadoPedido::EnDisputa(_) => return Err(ErrorCancelarPedido::PedidoEnDisputa),
+            EstadoPedido::DisputaResuelta(..) => return Err(ErrorCancelarPedido::DisputaResuelta),
         }
     
         //
@@ -609,6 +707,101 @@ impl RustaceoLibre {
 
     //
 
+    /// Si el pedido fue despachado y no pasó el plazo de disputa, el comprador abre una disputa.
+    /// Mientras la disputa esté abierta el vendedor no puede reclamar los fondos.
+    /// 
+    /// Puede dar error si el usuario no está registrado, el pedido no existe,
+    /// no es el comprador quien abre la disputa, el pedido no está despachado o ya pasó el plazo de disputa.
+    pub fn _abrir_disputa(&mut self, timestamp: u64, caller: AccountId, id_pedido: u128) -> Result<(), ErrorAbrirDisputa> {
+        // validar usuario
+        if !self.usuarios.contains(caller) {
+            return Err(ErrorAbrirDisputa::UsuarioNoRegistrado);
+        }
+
+        // validar pedido
+        let Some(pedido) = self.pedidos.get(&id_pedido).cloned()
+        else { return Err(ErrorAbrirDisputa::PedidoInexistente); };
+
+        // validar usuario es comprador
+        if caller != pedido.comprador {
+            return Err(ErrorAbrirDisputa::SoloCompradorPuede);
+        }
+
+        // validar estado del pedido
+        let EstadoPedido::Despachado(timestamp_despacho) = pedido.estado
+        else { return Err(ErrorAbrirDisputa::EstadoNoEsDespachado); };
+
+        // validar plazo de disputa desde despacho
+        let elapsed_time = timestamp.saturating_sub(timestamp_despacho);
+        if elapsed_time > self.plazo_disputa {
+            return Err(ErrorAbrirDisputa::FueraDePlazoDeDisputa);
+        }
+
+        // guardar pedido
+        let mut pedido = pedido;
+        pedido.estado = EstadoPedido::EnDisputa(timestamp);
+        self.pedidos.insert(id_pedido, pedido);
+
+        Ok(())
+    }
+
+    //
+
+    /// El árbitro del contrato resuelve una disputa abierta: reembolso total al comprador,
+    /// liberación total al vendedor o un reparto con el porcentaje de los fondos que recibe el vendedor.
+    /// Devuelve cuánto se le debe transferir a cada parte.
+    /// 
+    /// Puede dar error si quien resuelve no es el árbitro, el pedido no existe,
+    /// no está en disputa o el porcentaje del reparto es mayor a 100.
+    pub fn _resolver_disputa(&mut self, timestamp: u64, caller: AccountId, id_pedido: u128, resolucion: ResolucionDisputa) -> Result<ResultadoResolverDisputa, ErrorResolverDisputa> {
+        // validar árbitro
+        if caller != self.arbitro {
+            return Err(ErrorResolverDisputa::SoloArbitroPuede);
+        }
+
+        // validar pedido
+        let Some(pedido) = self.pedidos.get(&id_pedido).cloned()
+        else { return Err(ErrorResolverDisputa::PedidoInexistente); };
+
+        // validar estado del pedido
+        if !matches!(pedido.estado, EstadoPedido::EnDisputa(_)) {
+            return Err(ErrorResolverDisputa::PedidoNoEstaEnDisputa);
+        }
+
+        // calcular porcentaje para el vendedor
+        let porcentaje_vendedor = match resolucion {
+            ResolucionDisputa::ReembolsoTotal => 0,
+            ResolucionDisputa::LiberarFondos => 100,
+            ResolucionDisputa::Reparto(porcentaje) => porcentaje,
+        };
+
+        if porcentaje_vendedor > 100 {
+            return Err(ErrorResolverDisputa::PorcentajeInvalido);
+        }
+
+        // repartir fondos: (valor / 100) * p + (valor % 100) * p / 100 == valor * p / 100, pero sin desbordar
+        let porcentaje_vendedor = u128::from(porcentaje_vendedor); // safe cast: u8 -> u128
+        let monto_vendedor = (pedido.valor_total / 100) * porcentaje_vendedor + (pedido.valor_total % 100) * porcentaje_vendedor / 100;
+        let monto_comprador = pedido.valor_total.saturating_sub(monto_vendedor);
+
+        let resultado = ResultadoResolverDisputa {
+            comprador: pedido.comprador,
+            monto_comprador,
+            vendedor: pedido.vendedor,
+            monto_vendedor,
+        };
+
+        // guardar pedido
+        let mut pedido = pedido;
+        pedido.estado = EstadoPedido::DisputaResuelta(timestamp, resolucion);
+        self.pedidos.insert(id_pedido, pedido);
+
+        // fin. se transferirán los fondos en lib.rs y recién ahí se marcan como transferidos
+        Ok(resultado)
+    }
+
+    //
+
     /// Devuelve las compras del usuario que lo ejecuta
     /// 
     /// Dará error si el usuario no está registrado como comprador o no tiene compras
@@ -915,6 +1108,51 @@ mod tests {
     }
 
 
+    #[ink::test]
+    fn comprar_producto_falla_publicacion_pausada() {
+        let mut contrato = RustaceoLibre::default();
+
+        let vendedor = AccountId::from([0x1; 32]);
+        let comprador = AccountId::from([0x2; 32]);
+
+        contrato._registrar_usuario(vendedor, RolDeSeleccion::Vendedor).unwrap();
+        contrato._registrar_usuario(comprador, RolDeSeleccion::Comprador).unwrap();
+
+        let id_producto = contrato._registrar_producto(vendedor, "Lapicera".into(), "Tinta negra".into(), CategoriaProducto::Tecnologia, 5).unwrap();
+        let id_publicacion = contrato._realizar_publicacion(vendedor, id_producto, 5, 50).unwrap();
+        contrato._pausar_publicacion(vendedor, id_publicacion).unwrap();
+
+        let resultado = contrato._comprar_producto(0, comprador, id_publicacion, 1, 50);
+        assert_eq!(resultado, Err(ErrorComprarProducto::PublicacionPausada));
+
+        // al reanudarla vuelve a admitir compras
+        contrato._reanudar_publicacion(vendedor, id_publicacion).unwrap();
+        assert!(contrato._comprar_producto(0, comprador, id_publicacion, 1, 50).is_ok());
+    }
+
+    #[ink::test]
+    fn comprar_producto_respeta_periodo_de_publicacion() {
+        let mut contrato = RustaceoLibre::default();
+
+        let vendedor = AccountId::from([0x1; 32]);
+        let comprador = AccountId::from([0x2; 32]);
+
+        contrato._registrar_usuario(vendedor, RolDeSeleccion::Vendedor).unwrap();
+        contrato._registrar_usuario(comprador, RolDeSeleccion::Comprador).unwrap();
+
+        let id_producto = contrato._registrar_producto(vendedor, "Lapicera".into(), "Tinta negra".into(), CategoriaProducto::Tecnologia, 5).unwrap();
+        let id_publicacion = contrato._realizar_publicacion_programada(0, vendedor, id_producto, 5, 50, (Some(1000), Some(2000))).unwrap();
+
+        let resultado = contrato._comprar_producto(999, comprador, id_publicacion, 1, 50);
+        assert_eq!(resultado, Err(ErrorComprarProducto::PublicacionNoIniciada));
+
+        assert!(contrato._comprar_producto(1000, comprador, id_publicacion, 1, 50).is_ok());
+
+        let resultado = contrato._comprar_producto(2000, comprador, id_publicacion, 1, 50);
+        assert_eq!(resultado, Err(ErrorComprarProducto::PublicacionVencida));
+    }
+
+
     #[ink::test]
     fn comprar_producto_falla_valor_transferido_insuficiente() {
         let mut contrato = RustaceoLibre::default();
@@ -977,7 +1215,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_exitoso() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
 
         // Simulación de cuentas
         let vendedor = AccountId::from([0x01; 32]);
@@ -1024,7 +1262,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_usuario_no_registrado() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
 
         // Crear ID de compra válido y simular una compra
         let comprador = AccountId::from([0x02; 32]);
@@ -1064,7 +1302,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_compra_no_existe() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x01; 32]);
         let timestamp_actual = 6_184_000_000;
         let id_compra_inexistente = 999;
@@ -1085,7 +1323,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_no_es_el_vendedor() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor_real = AccountId::from([0x02; 32]);
         let caller_falso = comprador; // el que intenta reclamar sin ser el vendedor
@@ -1125,7 +1363,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_ya_transferidos() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 0;
@@ -1165,7 +1403,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_estado_incorrecto() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 0;
@@ -1204,7 +1442,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_antes_de_tiempo() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 0;
@@ -1243,7 +1481,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_no_es_vendedor() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let otro_usuario = AccountId::from([0x03; 32]); // no es vendedor
         let comprador = AccountId::from([0x01; 32]);
@@ -1284,7 +1522,7 @@ mod tests {
     #[ink::test]
     fn reclamar_fondos_fondos_ya_transferidos() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 0;
@@ -1321,7 +1559,7 @@ mod tests {
 
     #[ink::test]
     fn reclamar_fondos_no_convalida_politica() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 0;
@@ -1355,7 +1593,7 @@ mod tests {
     #[ink::test]
     fn compra_despachada_exitoso() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
 
         // Crear cuentas
         let vendedor = AccountId::from([0x01; 32]);
@@ -1417,7 +1655,7 @@ mod tests {
 
     #[ink::test]
     fn compra_despachada_usuario_no_registrado() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x01; 32]);
         let comprador = AccountId::from([0x02; 32]);
 
@@ -1447,7 +1685,7 @@ mod tests {
 
     #[ink::test]
     fn compra_despachada_compra_inexistente() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
 
         let vendedor = AccountId::from([0x01; 32]);
         contrato._registrar_usuario(vendedor, RolDeSeleccion::Vendedor).unwrap();
@@ -1460,7 +1698,7 @@ mod tests {
     #[ink::test]
     fn compra_recibida_exitoso() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
 
         // Crear cuentas
         let vendedor = AccountId::from([0x01; 32]);
@@ -1524,7 +1762,7 @@ mod tests {
     #[ink::test]
     fn cancelar_compra_exitoso() {
         // Arrange
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
 
         // Crear cuentas
         let vendedor = AccountId::from([0x01; 32]);
@@ -1586,7 +1824,7 @@ mod tests {
 
     #[ink::test]
     fn reclamar_fondos_estado_recibido() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 0;
@@ -1618,7 +1856,7 @@ mod tests {
 
     #[ink::test]
     fn reclamar_fondos_estado_cancelado() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 0;
@@ -1650,7 +1888,7 @@ mod tests {
 
     #[ink::test]
     fn reclamar_fondos_estado_ya_recibido() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x02; 32]);
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 0;
@@ -1682,7 +1920,7 @@ mod tests {
 
     #[ink::test]
     fn compra_recibida_usuario_no_registrado() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 1;
 
@@ -1695,7 +1933,7 @@ mod tests {
 
     #[ink::test]
     fn compra_recibida_ya_recibida() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let vendedor = AccountId::from([0x01; 32]);
         let id_compra = 1;
@@ -1735,7 +1973,7 @@ mod tests {
 
     #[ink::test]
     fn compra_recibida_cancelada() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let vendedor = AccountId::from([0x01; 32]);
         let id_compra = 42;
@@ -1775,7 +2013,7 @@ mod tests {
 
     #[ink::test]
     fn compra_recibida_no_despachada() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let vendedor = AccountId::from([0x01; 32]);
         let id_compra = 7;
@@ -1815,7 +2053,7 @@ mod tests {
 
     #[ink::test]
     fn compra_recibida_solo_comprador_puede() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let vendedor = AccountId::from([0x01; 32]);
         let id_compra = 10;
@@ -1856,7 +2094,7 @@ mod tests {
 
     #[ink::test]
     fn cancelar_compra_usuario_no_registrado() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 1;
 
@@ -1869,7 +2107,7 @@ mod tests {
 
     #[ink::test]
     fn cancelar_compra_inexistente() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 999;
 
@@ -1885,7 +2123,7 @@ mod tests {
 
     #[ink::test]
     fn cancelar_compra_usuario_no_participa() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let vendedor = AccountId::from([0x01; 32]);
         let otro_usuario = AccountId::from([0x03; 32]);
@@ -1939,7 +2177,7 @@ mod tests {
 
     #[ink::test]
     fn cancelar_compra_ya_recibida() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let vendedor = AccountId::from([0x01; 32]);
         let id_compra = 555;
@@ -1986,7 +2224,7 @@ mod tests {
 
     #[ink::test]
     fn cancelar_compra_ya_cancelada() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x02; 32]);
         let vendedor = AccountId::from([0x01; 32]);
         let id_compra = 777;
@@ -2033,7 +2271,7 @@ mod tests {
 
     #[ink::test]
     fn ver_compras_usuario_no_registrado() {
-        let contrato = RustaceoLibre::new();
+        let contrato = RustaceoLibre::default();
         let usuario = AccountId::from([0x01; 32]);
 
         let resultado = contrato._ver_compras(usuario);
@@ -2042,7 +2280,7 @@ mod tests {
 
     #[ink::test]
     fn ver_compras_no_es_comprador() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let usuario = AccountId::from([0x01; 32]);
 
         // Registrar usuario como vendedor (no comprador)
@@ -2055,7 +2293,7 @@ mod tests {
 
     #[ink::test]
     fn ver_compras_sin_compras() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let usuario = AccountId::from([0x01; 32]);
 
         // Registrar usuario como comprador
@@ -2070,7 +2308,7 @@ mod tests {
 
     #[ink::test]
     fn ver_compras_exitoso() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 123;
@@ -2112,7 +2350,7 @@ mod tests {
 
     #[ink::test]
     fn ver_ventas_usuario_no_registrado() {
-        let contrato = RustaceoLibre::new();
+        let contrato = RustaceoLibre::default();
         let usuario = AccountId::from([0x01; 32]);
 
         let resultado = contrato._ver_ventas(usuario);
@@ -2122,7 +2360,7 @@ mod tests {
 
     #[ink::test]
     fn ver_ventas_no_es_vendedor() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let usuario = AccountId::from([0x01; 32]);
 
         // Registrar usuario como comprador (no vendedor)
@@ -2135,7 +2373,7 @@ mod tests {
 
     #[ink::test]
     fn ver_ventas_sin_ventas() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let usuario = AccountId::from([0x01; 32]);
 
         // Registrar usuario como vendedor
@@ -2150,7 +2388,7 @@ mod tests {
 
     #[ink::test]
     fn ver_ventas_exitoso() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x01; 32]);
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 456;
@@ -2192,7 +2430,7 @@ mod tests {
 
     #[ink::test]
     fn ver_compras_estado_vacio() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 1;
@@ -2234,7 +2472,7 @@ mod tests {
 
     #[ink::test]
     fn ver_compras_estado_exitoso() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 1;
@@ -2277,7 +2515,7 @@ mod tests {
 
     #[ink::test]
     fn ver_ventas_estado_exitoso() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x01; 32]);
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 1;
@@ -2320,7 +2558,7 @@ mod tests {
 
     #[ink::test]
     fn ver_compras_categoria_vacio() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 1;
@@ -2372,7 +2610,7 @@ mod tests {
 
     #[ink::test]
     fn ver_compras_categoria_exitoso() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 2;
@@ -2425,7 +2663,7 @@ mod tests {
 
     #[ink::test]
     fn ver_ventas_categoria_exitoso() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x01; 32]);
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 3;
@@ -2478,7 +2716,7 @@ mod tests {
 
     #[ink::test]
     fn ver_ventas_categoria_vacio() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x01; 32]);
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 4;
@@ -2530,7 +2768,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_calificacion_invalida() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 1;
 
@@ -2548,7 +2786,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_usuario_no_registrado() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let usuario = AccountId::from([0x01; 32]);
         let id_compra = 1;
 
@@ -2561,7 +2799,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_compra_inexistente() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 999;
 
@@ -2577,7 +2815,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_compra_no_recibida() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 10;
@@ -2609,7 +2847,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_usuario_ya_califico() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 20;
@@ -2641,7 +2879,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_usuario_no_participa() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let otro_usuario = AccountId::from([0x03; 32]);
@@ -2676,7 +2914,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_vendedor_inexistente() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 40;
@@ -2709,7 +2947,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_comprador_inexistente() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x01; 32]);
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 41;
@@ -2742,7 +2980,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_exitoso() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 50;
@@ -2778,7 +3016,7 @@ mod tests {
 
     #[ink::test]
     fn calificar_transaccion_vendedor_exitoso() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 51;
@@ -2808,13 +3046,14 @@ mod tests {
         assert_eq!(resultado, Ok(()));
         // Verificar que la calificación se guardó
         let compra_actualizada = contrato.pedidos.get(&id_compra).unwrap();
-        assert_eq!(compra_actualizada.calificacion_comprador, Some(4));
+        assert_eq!(compra_actualizada.calificacion_vendedor, Some(4));
+        assert_eq!(compra_actualizada.calificacion_comprador, None);
     }
 
 
     #[ink::test]
     fn calificar_transaccion_compra_cancelada() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let vendedor = AccountId::from([0x02; 32]);
         let id_compra = 60;
@@ -2844,7 +3083,7 @@ mod tests {
 
     #[ink::test]
     fn reclamar_fondos_timestamp_invalido() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let vendedor = AccountId::from([0x01; 32]);
         let comprador = AccountId::from([0x02; 32]);
         let id_compra = 999;
@@ -2877,7 +3116,7 @@ mod tests {
 
     #[ink::test]
     fn compra_recibida_sin_compras_registradas() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
 
         contrato._registrar_usuario(comprador, RolDeSeleccion::Comprador).unwrap();
@@ -2891,7 +3130,7 @@ mod tests {
 
     #[ink::test]
     fn compra_recibida_id_no_en_lista_usuario() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 1;
 
@@ -2927,7 +3166,7 @@ mod tests {
 
     #[ink::test]
     fn compra_recibida_compra_inexistente_en_storage() {
-        let mut contrato = RustaceoLibre::new();
+        let mut contrato = RustaceoLibre::default();
         let comprador = AccountId::from([0x01; 32]);
         let id_compra = 1;
 
@@ -2945,6 +3184,168 @@ mod tests {
         assert_eq!(res, Err(ErrorProductoRecibido::PedidoInexistente));
     }
 
+    //
+    // disputas
+    //
 
-}
+    /// Contrato con árbitro propio, usuarios registrados y un pedido (id 0, valor 1000) en el estado indicado
+    fn contrato_con_pedido(estado: EstadoPedido) -> (RustaceoLibre, AccountId, AccountId, AccountId) {
+        contrato_con_plazos_y_pedido(PLAZO_RECLAMO_FONDOS_POR_DEFECTO, PLAZO_DISPUTA_POR_DEFECTO, estado)
+    }
+
+    fn contrato_con_plazos_y_pedido(plazo_reclamo_fondos: u64, plazo_disputa: u64, estado: EstadoPedido) -> (RustaceoLibre, AccountId, AccountId, AccountId) {
+        let arbitro = AccountId::from([0x09; 32]);
+        let vendedor = AccountId::from([0x01; 32]);
+        let comprador = AccountId::from([0x02; 32]);
 
+        let mut contrato = RustaceoLibre::new(plazo_reclamo_fondos, plazo_disputa, arbitro);
+        contrato._registrar_usuario(vendedor, RolDeSeleccion::Vendedor).unwrap();
+        contrato._registrar_usuario(comprador, RolDeSeleccion::Comprador).unwrap();
+
+        contrato.pedidos.insert(0, Pedido {
+            id: 0,
+            timestamp: 0,
+            publicacion: 0,
+            cantidad_comprada: 1,
+            valor_total: 1000,
+            fondos_fueron_transferidos: false,
+            estado,
+            comprador,
+            vendedor,
+            calificacion_comprador: None,
+            calificacion_vendedor: None,
+            primer_solicitud_cancelacion: None,
+        });
+
+        let mut usuario_comprador = contrato.usuarios.get(comprador).unwrap();
+        usuario_comprador.agregar_compra(0);
+        contrato.usuarios.insert(comprador, &usuario_comprador);
+
+        let mut usuario_vendedor = contrato.usuarios.get(vendedor).unwrap();
+        usuario_vendedor.agregar_venta(0);
+        contrato.usuarios.insert(vendedor, &usuario_vendedor);
+
+        (contrato, arbitro, vendedor, comprador)
+    }
+
+    /// Cambia el estado del pedido 0
+    fn cambiar_estado(contrato: &mut RustaceoLibre, estado: EstadoPedido) {
+        let pedido = contrato.pedidos.get(&0).unwrap().clone();
+        contrato.pedidos.insert(0, Pedido { estado, ..pedido });
+    }
+
+    #[ink::test]
+    fn abrir_disputa_exitoso_congela_reclamo() {
+        // Arrange
+        let timestamp_despacho = 1_000_000;
+        let (mut contrato, _, vendedor, comprador) = contrato_con_pedido(EstadoPedido::Despachado(timestamp_despacho));
+        let timestamp_disputa = timestamp_despacho + PLAZO_DISPUTA_POR_DEFECTO; // último momento del plazo
+
+        // Act
+        let resultado = contrato._abrir_disputa(timestamp_disputa, comprador, 0);
+
+        // Assert
+        assert_eq!(resultado, Ok(()));
+        assert_eq!(contrato.pedidos.get(&0).unwrap().estado, EstadoPedido::EnDisputa(timestamp_disputa));
+
+        // pasado el plazo de reclamo, el vendedor sigue sin poder reclamar
+        let timestamp_reclamo = timestamp_despacho + PLAZO_RECLAMO_FONDOS_POR_DEFECTO;
+        assert_eq!(contrato._reclamar_fondos(timestamp_reclamo, vendedor, 0), Err(ErrorReclamarFondos::PedidoEnDisputa));
+        assert!(!contrato.pedidos.get(&0).unwrap().fondos_fueron_transferidos);
+
+        // tampoco se puede recibir ni cancelar
+        assert_eq!(contrato._pedido_recibido(timestamp_reclamo, comprador, 0), Err(ErrorProductoRecibido::PedidoEnDisputa));
+        assert_eq!(contrato._cancelar_pedido(timestamp_reclamo, comprador, 0), Err(ErrorCancelarPedido::PedidoEnDisputa));
+    }
+
+    #[ink::test]
+    fn abrir_disputa_errores() {
+        let (mut contrato, _, vendedor, comprador) = contrato_con_pedido(EstadoPedido::Despachado(0));
+
+        // usuario no registrado
+        let desconocido = AccountId::from([0x07; 32]);
+        assert_eq!(contrato._abrir_disputa(10, desconocido, 0), Err(ErrorAbrirDisputa::UsuarioNoRegistrado));
+
+        // pedido inexistente
+        assert_eq!(contrato._abrir_disputa(10, comprador, 5), Err(ErrorAbrirDisputa::PedidoInexistente));
+
+        // sólo el comprador
+        assert_eq!(contrato._abrir_disputa(10, vendedor, 0), Err(ErrorAbrirDisputa::SoloCompradorPuede));
+
+        // fuera de plazo
+        assert_eq!(contrato._abrir_disputa(PLAZO_DISPUTA_POR_DEFECTO + 1, comprador, 0), Err(ErrorAbrirDisputa::FueraDePlazoDeDisputa));
+
+        // pedido no despachado
+        cambiar_estado(&mut contrato, EstadoPedido::Pendiente(0));
+        assert_eq!(contrato._abrir_disputa(10, comprador, 0), Err(ErrorAbrirDisputa::EstadoNoEsDespachado));
+    }
+
+    #[ink::test]
+    fn abrir_disputa_plazo_configurable() {
+        // plazo de reclamo de 1 segundo y de disputa de medio segundo
+        let (mut contrato, _, vendedor, comprador) = contrato_con_plazos_y_pedido(1_000, 500, EstadoPedido::Despachado(0));
+        let pedido = contrato.pedidos.get(&0).unwrap().clone();
+        contrato.pedidos.insert(1, Pedido { id: 1, ..pedido });
+
+        assert_eq!(contrato._abrir_disputa(501, comprador, 0), Err(ErrorAbrirDisputa::FueraDePlazoDeDisputa));
+        assert_eq!(contrato._abrir_disputa(500, comprador, 1), Ok(()));
+
+        // el reclamo de fondos usa el plazo del constructor
+        assert_eq!(contrato._reclamar_fondos(999, vendedor, 0), Err(ErrorReclamarFondos::NoConvalidaPoliticaDeReclamo));
+        assert_eq!(contrato._reclamar_fondos(1_000, vendedor, 0), Ok(1000));
+    }
+
+    #[ink::test]
+    fn resolver_disputa_reparto() {
+        // Arrange
+        let (mut contrato, arbitro, vendedor, comprador) = contrato_con_pedido(EstadoPedido::EnDisputa(100));
+
+        // Act
+        let resultado = contrato._resolver_disputa(200, arbitro, 0, ResolucionDisputa::Reparto(70));
+
+        // Assert
+        assert_eq!(resultado, Ok(ResultadoResolverDisputa {
+            comprador,
+            monto_comprador: 300,
+            vendedor,
+            monto_vendedor: 700,
+        }));
+
+        let pedido = contrato.pedidos.get(&0).unwrap();
+        assert_eq!(pedido.estado, EstadoPedido::DisputaResuelta(200, ResolucionDisputa::Reparto(70)));
+        assert!(!pedido.fondos_fueron_transferidos);
+
+        // no se puede resolver dos veces
+        assert_eq!(contrato._resolver_disputa(300, arbitro, 0, ResolucionDisputa::LiberarFondos), Err(ErrorResolverDisputa::PedidoNoEstaEnDisputa));
+    }
+
+    #[ink::test]
+    fn resolver_disputa_reembolso_y_liberacion() {
+        let (mut contrato, arbitro, _, _) = contrato_con_pedido(EstadoPedido::EnDisputa(100));
+        let resultado = contrato._resolver_disputa(200, arbitro, 0, ResolucionDisputa::ReembolsoTotal).unwrap();
+        assert_eq!((resultado.monto_comprador, resultado.monto_vendedor), (1000, 0));
+
+        cambiar_estado(&mut contrato, EstadoPedido::EnDisputa(100));
+        let resultado = contrato._resolver_disputa(200, arbitro, 0, ResolucionDisputa::LiberarFondos).unwrap();
+        assert_eq!((resultado.monto_comprador, resultado.monto_vendedor), (0, 1000));
+    }
+
+    #[ink::test]
+    fn resolver_disputa_errores() {
+        let (mut contrato, arbitro, vendedor, _) = contrato_con_pedido(EstadoPedido::EnDisputa(100));
+
+        // sólo el árbitro
+        assert_eq!(contrato._resolver_disputa(200, vendedor, 0, ResolucionDisputa::LiberarFondos), Err(ErrorResolverDisputa::SoloArbitroPuede));
+
+        // pedido inexistente
+        assert_eq!(contrato._resolver_disputa(200, arbitro, 5, ResolucionDisputa::LiberarFondos), Err(ErrorResolverDisputa::PedidoInexistente));
+
+        // porcentaje inválido
+        assert_eq!(contrato._resolver_disputa(200, arbitro, 0, ResolucionDisputa::Reparto(101)), Err(ErrorResolverDisputa::PorcentajeInvalido));
+        assert_eq!(contrato.pedidos.get(&0).unwrap().estado, EstadoPedido::EnDisputa(100));
+
+        // pedido sin disputa
+        cambiar_estado(&mut contrato, EstadoPedido::Despachado(100));
+        assert_eq!(contrato._resolver_disputa(200, arbitro, 0, ResolucionDisputa::LiberarFondos), Err(ErrorResolverDisputa::PedidoNoEstaEnDisputa));
+    }
+}
@@ -18,6 +18,12 @@ pub struct Publicacion {
     pub producto: u128,
     pub cantidad_ofertada: u32,
     pub precio_unitario: u128,
+    /// Si está pausada, la publicación no admite compras hasta que el vendedor la reanude.
+    pub pausada: bool,
+    /// Timestamp a partir del cual la publicación admite compras. None: desde su creación.
+    pub inicio: Option<u64>,
+    /// Timestamp a partir del cual la publicación vence. None: no vence.
+    pub fin: Option<u64>,
 }
 
 //
@@ -31,8 +37,21 @@ impl Publicacion {
             producto,
             cantidad_ofertada,
             precio_unitario: precio,
+            pausada: false,
+            inicio: None,
+            fin: None,
         }
     }
+
+    /// Devuelve true si la publicación todavía no alcanzó su timestamp de inicio.
+    pub fn no_iniciada(&self, timestamp: u64) -> bool {
+        self.inicio.is_some_and(|inicio| timestamp < inicio)
+    }
+
+    /// Devuelve true si la publicación alcanzó su timestamp de fin.
+    pub fn vencida(&self, timestamp: u64) -> bool {
+        self.fin.is_some_and(|fin| timestamp >= fin)
+    }
 }
 
 //
@@ -51,6 +70,7 @@ pub enum ErrorRealizarPublicacion {
     NoEsVendedor,
     StockInsuficiente,
     PrecioCero,
+    PeriodoInvalido,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -63,6 +83,23 @@ pub enum ErrorPausarReanudarPublicacion {
     UsuarioNoRegistrado,
     PublicacionInexistente,
     NoEsElVendedor,
+    YaPausada,
+    NoPausada,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout)
+)]
+pub enum ErrorFinalizarPublicacionVencida {
+    UsuarioNoRegistrado,
+    PublicacionInexistente,
+    PublicacionNoVencida,
+    SinStockPendiente,
+    VendedorInexistente,
+    Desconocido,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -98,11 +135,26 @@ impl RustaceoLibre {
     /// 
     /// Devuelve Error si el precio o la cantidad son 0, o si `caller` no existe o no es vendedor.
     pub(crate) fn _realizar_publicacion(&mut self, caller: AccountId, id_producto: u128, cantidad_ofertada: u32, precio: u128) -> Result<u128, ErrorRealizarPublicacion> {
+        self._realizar_publicacion_programada(0, caller, id_producto, cantidad_ofertada, precio, (None, None))
+    }
+
+    /// Realiza una publicación con producto, precio, cantidad y un período de vigencia opcional.
+    /// `periodo` es (inicio, fin): la publicación admite compras desde `inicio` (inclusive) hasta `fin` (exclusive).
+    /// 
+    /// Devuelve Error si el precio o la cantidad son 0, si `caller` no existe o no es vendedor,
+    /// o si `fin` no es posterior a `inicio` y al timestamp actual.
+    pub(crate) fn _realizar_publicacion_programada(&mut self, timestamp: u64, caller: AccountId, id_producto: u128, cantidad_ofertada: u32, precio: u128, periodo: (Option<u64>, Option<u64>)) -> Result<u128, ErrorRealizarPublicacion> {
         // verificar precio
         if precio == 0 {
             return Err(ErrorRealizarPublicacion::PrecioCero);
         }
 
+        // verificar período
+        let (inicio, fin) = periodo;
+        if fin.is_some_and(|fin| fin <= timestamp || inicio.is_some_and(|inicio| fin <= inicio)) {
+            return Err(ErrorRealizarPublicacion::PeriodoInvalido);
+        }
+
         //verificar cantidad ofertada
         if cantidad_ofertada == 0{
             return Err(ErrorRealizarPublicacion::StockInsuficiente);
@@ -137,7 +189,9 @@ impl RustaceoLibre {
 
         // obtener id de publicación e instanciarla
         let id_publicacion = self.next_id_publicaciones();
-        let publicacion = Publicacion::new(caller, id_producto, cantidad_ofertada, precio);
+        let mut publicacion = Publicacion::new(caller, id_producto, cantidad_ofertada, precio);
+        publicacion.inicio = inicio;
+        publicacion.fin = fin;
 
         // agregar al map principal
         self.publicaciones.insert(id_publicacion, publicacion);
@@ -213,6 +267,89 @@ impl RustaceoLibre {
 
     //
 
+    /// Pausa una publicación, impidiendo que se realicen compras sobre ella.
+    /// 
+    /// Devuelve Error si el usuario no está registrado, la publicación no existe,
+    /// el usuario no es el vendedor o la publicación ya estaba pausada.
+    pub(crate) fn _pausar_publicacion(&mut self, caller: AccountId, id_publicacion: u128) -> Result<(), ErrorPausarReanudarPublicacion> {
+        self.establecer_pausa_publicacion(caller, id_publicacion, true)
+    }
+
+    /// Reanuda una publicación pausada, volviendo a admitir compras sobre ella.
+    /// 
+    /// Devuelve Error si el usuario no está registrado, la publicación no existe,
+    /// el usuario no es el vendedor o la publicación no estaba pausada.
+    pub(crate) fn _reanudar_publicacion(&mut self, caller: AccountId, id_publicacion: u128) -> Result<(), ErrorPausarReanudarPublicacion> {
+        self.establecer_pausa_publicacion(caller, id_publicacion, false)
+    }
+
+    fn establecer_pausa_publicacion(&mut self, caller: AccountId, id_publicacion: u128, pausada: bool) -> Result<(), ErrorPausarReanudarPublicacion> {
+        if !self.usuarios.contains(caller) {
+            return Err(ErrorPausarReanudarPublicacion::UsuarioNoRegistrado);
+        }
+
+        let Some(mut publicacion) = self.publicaciones.get(&id_publicacion).cloned()
+        else { return Err(ErrorPausarReanudarPublicacion::PublicacionInexistente); };
+
+        if publicacion.vendedor != caller {
+            return Err(ErrorPausarReanudarPublicacion::NoEsElVendedor);
+        }
+
+        if publicacion.pausada == pausada {
+            return Err(if pausada { ErrorPausarReanudarPublicacion::YaPausada } else { ErrorPausarReanudarPublicacion::NoPausada });
+        }
+
+        publicacion.pausada = pausada;
+        self.publicaciones.insert(id_publicacion, publicacion);
+        Ok(())
+    }
+
+    /// Finaliza una publicación vencida, devolviendo la cantidad ofertada no vendida
+    /// al stock del vendedor. Puede ejecutarla cualquier usuario registrado.
+    /// 
+    /// Devuelve la cantidad devuelta al vendedor.
+    /// Devuelve Error si el usuario no está registrado, la publicación no existe,
+    /// todavía no venció o no le queda stock por devolver.
+    pub(crate) fn _finalizar_publicacion_vencida(&mut self, timestamp: u64, caller: AccountId, id_publicacion: u128) -> Result<u32, ErrorFinalizarPublicacionVencida> {
+        if !self.usuarios.contains(caller) {
+            return Err(ErrorFinalizarPublicacionVencida::UsuarioNoRegistrado);
+        }
+
+        let Some(publicacion) = self.publicaciones.get(&id_publicacion)
+        else { return Err(ErrorFinalizarPublicacionVencida::PublicacionInexistente); };
+
+        if !publicacion.vencida(timestamp) {
+            return Err(ErrorFinalizarPublicacionVencida::PublicacionNoVencida);
+        }
+
+        let remanente = publicacion.cantidad_ofertada;
+        if remanente == 0 {
+            return Err(ErrorFinalizarPublicacionVencida::SinStockPendiente);
+        }
+
+        let Some(mut vendedor) = self.usuarios.get(publicacion.vendedor)
+        else { return Err(ErrorFinalizarPublicacionVencida::VendedorInexistente); };
+
+        // devolver el remanente al stock del vendedor
+        let stock_vendedor = vendedor.obtener_stock_producto(&publicacion.producto).unwrap_or(0);
+        let Some(nuevo_stock_vendedor) = stock_vendedor.checked_add(remanente)
+        else { return Err(ErrorFinalizarPublicacionVencida::Desconocido); };
+
+        if !vendedor.establecer_stock_producto(&publicacion.producto, &nuevo_stock_vendedor) {
+            return Err(ErrorFinalizarPublicacionVencida::VendedorInexistente);
+        }
+
+        // todo bien: guardar cambios
+        let mut publicacion = publicacion.clone();
+        publicacion.cantidad_ofertada = 0;
+        self.publicaciones.insert(id_publicacion, publicacion);
+        self.usuarios.insert(vendedor.id, &vendedor);
+
+        Ok(remanente)
+    }
+
+    //
+
     /// Dada una ID, devuelve la publicación
     /// 
     /// Devolverá None si la publicación no existe o el usuario no está registrado
@@ -284,7 +421,7 @@ mod tests {
 
     #[ink::test]
     fn test_realizar_publicacion_precio_cero() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -302,7 +439,7 @@ mod tests {
 
     #[ink::test]
     fn test_realizar_publicacion_cantidad_cero() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -320,7 +457,7 @@ mod tests {
 
     #[ink::test]
     fn test_realizar_publicacion_usuario_no_registrado() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
 
         let result = rustaceo._realizar_publicacion(caller, 1, 10, 100);
@@ -329,7 +466,7 @@ mod tests {
 
     #[ink::test]
     fn test_realizar_publicacion_no_es_vendedor() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let usuario = Usuario::new(caller, Rol::Comprador(DataComprador {
             compras: Vec::new(),
@@ -344,7 +481,7 @@ mod tests {
 
     #[ink::test]
     fn test_realizar_publicacion_stock_insuficiente() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -363,7 +500,7 @@ mod tests {
 
     #[ink::test]
     fn test_realizar_publicacion_producto_inexistente() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -381,7 +518,7 @@ mod tests {
 
     #[ink::test]
     fn test_realizar_publicacion_success() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -410,7 +547,7 @@ mod tests {
 
     #[ink::test]
     fn test_modificar_cantidad_ofertada_usuario_inexistente() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
 
         let result = rustaceo._modificar_cantidad_ofertada(caller, 0, 15);
@@ -419,7 +556,7 @@ mod tests {
 
     #[ink::test]
     fn test_modificar_cantidad_ofertada_no_es_vendedor() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let usuario = Usuario::new(caller, Rol::Comprador(DataComprador {
             compras: Vec::new(),
@@ -434,7 +571,7 @@ mod tests {
 
     #[ink::test]
     fn test_modificar_cantidad_ofertada_publicacion_inexistente() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -451,7 +588,7 @@ mod tests {
 
     #[ink::test]
     fn test_modificar_cantidad_ofertada_no_es_el_vendedor() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let otro_vendedor = AccountId::from([0x2; 32]);
         let usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
@@ -471,7 +608,7 @@ mod tests {
 
     #[ink::test]
     fn test_modificar_cantidad_ofertada_sin_cambios() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -491,7 +628,7 @@ mod tests {
 
     #[ink::test]
     fn test_modificar_cantidad_ofertada_success_increase() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -517,7 +654,7 @@ mod tests {
 
     #[ink::test]
     fn test_modificar_cantidad_ofertada_success_decrease() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -543,7 +680,7 @@ mod tests {
 
     #[ink::test]
     fn test_modificar_cantidad_ofertada_stock_vendedor_insuficiente() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let mut usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -569,7 +706,7 @@ mod tests {
 
     #[ink::test]
     fn test_ver_publicacion_usuario_no_registrado() {
-        let rustaceo = RustaceoLibre::new();
+        let rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
 
         let result = rustaceo._ver_publicacion(caller, 0);
@@ -578,7 +715,7 @@ mod tests {
 
     #[ink::test]
     fn test_ver_publicacion_publicacion_inexistente() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -595,7 +732,7 @@ mod tests {
 
     #[ink::test]
     fn test_ver_publicaciones_vendedor_usuario_no_registrado() {
-        let rustaceo = RustaceoLibre::new();
+        let rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
 
         let result = rustaceo._ver_publicaciones_vendedor(caller);
@@ -604,7 +741,7 @@ mod tests {
 
     #[ink::test]
     fn test_ver_publicaciones_vendedor_no_es_vendedor() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let usuario = Usuario::new(caller, Rol::Comprador(DataComprador {
             compras: Vec::new(),
@@ -619,7 +756,7 @@ mod tests {
     
     #[ink::test]
     fn test_ver_publicaciones_vendedor_no_tiene_publicaciones() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -636,7 +773,7 @@ mod tests {
 
     #[ink::test]
     fn test_ver_publicaciones_vendedor_success() {
-        let mut rustaceo = RustaceoLibre::new();
+        let mut rustaceo = RustaceoLibre::default();
         let caller = AccountId::from([0x1; 32]);
         let usuario = Usuario::new(caller, Rol::Vendedor(DataVendedor {
             ventas: Vec::new(),
@@ -659,4 +796,83 @@ mod tests {
         assert_eq!(publicacion.cantidad_ofertada, 10);
         assert_eq!(publicacion.precio_unitario, 100);
     }
+    fn rustaceo_con_publicacion(vendedor: AccountId, inicio: Option<u64>, fin: Option<u64>) -> (RustaceoLibre, u128) {
+        let mut rustaceo = RustaceoLibre::default();
+        let mut usuario = Usuario::new(vendedor, Rol::Vendedor(DataVendedor::default()));
+        usuario.establecer_stock_producto(&1, &15);
+        rustaceo.usuarios.insert(vendedor, &usuario);
+        rustaceo.productos.insert(1, Producto::new(String::from("Test"), String::from("Desc"), CategoriaProducto::Hogar));
+
+        let id = rustaceo._realizar_publicacion_programada(100, vendedor, 1, 10, 100, (inicio, fin)).unwrap();
+        (rustaceo, id)
+    }
+
+    #[ink::test]
+    fn test_pausar_y_reanudar_publicacion() {
+        let caller = AccountId::from([0x1; 32]);
+        let (mut rustaceo, id) = rustaceo_con_publicacion(caller, None, None);
+
+        assert!(rustaceo._pausar_publicacion(caller, id).is_ok());
+        assert!(rustaceo.publicaciones.get(&id).unwrap().pausada);
+        assert_eq!(rustaceo._pausar_publicacion(caller, id), Err(ErrorPausarReanudarPublicacion::YaPausada));
+
+        assert!(rustaceo._reanudar_publicacion(caller, id).is_ok());
+        assert!(!rustaceo.publicaciones.get(&id).unwrap().pausada);
+        assert_eq!(rustaceo._reanudar_publicacion(caller, id), Err(ErrorPausarReanudarPublicacion::NoPausada));
+    }
+
+    #[ink::test]
+    fn test_pausar_publicacion_errores() {
+        let caller = AccountId::from([0x1; 32]);
+        let otro = AccountId::from([0x2; 32]);
+        let (mut rustaceo, id) = rustaceo_con_publicacion(caller, None, None);
+
+        assert_eq!(rustaceo._pausar_publicacion(otro, id), Err(ErrorPausarReanudarPublicacion::UsuarioNoRegistrado));
+        assert_eq!(rustaceo._pausar_publicacion(caller, id + 1), Err(ErrorPausarReanudarPublicacion::PublicacionInexistente));
+
+        rustaceo.usuarios.insert(otro, &Usuario::new(otro, Rol::Vendedor(DataVendedor::default())));
+        assert_eq!(rustaceo._pausar_publicacion(otro, id), Err(ErrorPausarReanudarPublicacion::NoEsElVendedor));
+    }
+
+    #[ink::test]
+    fn test_realizar_publicacion_programada_periodo_invalido() {
+        let caller = AccountId::from([0x1; 32]);
+        let (mut rustaceo, _) = rustaceo_con_publicacion(caller, None, None);
+
+        // fin anterior al timestamp actual
+        let result = rustaceo._realizar_publicacion_programada(100, caller, 1, 1, 100, (None, Some(50)));
+        assert_eq!(result, Err(ErrorRealizarPublicacion::PeriodoInvalido));
+
+        // fin anterior al inicio
+        let result = rustaceo._realizar_publicacion_programada(100, caller, 1, 1, 100, (Some(500), Some(400)));
+        assert_eq!(result, Err(ErrorRealizarPublicacion::PeriodoInvalido));
+
+        // no se tocó el stock del vendedor
+        assert_eq!(rustaceo.usuarios.get(caller).unwrap().obtener_stock_producto(&1), Some(5));
+    }
+
+    #[ink::test]
+    fn test_finalizar_publicacion_vencida_devuelve_stock() {
+        let caller = AccountId::from([0x1; 32]);
+        let (mut rustaceo, id) = rustaceo_con_publicacion(caller, None, Some(1000));
+        assert_eq!(rustaceo.usuarios.get(caller).unwrap().obtener_stock_producto(&1), Some(5));
+
+        assert_eq!(rustaceo._finalizar_publicacion_vencida(999, caller, id), Err(ErrorFinalizarPublicacionVencida::PublicacionNoVencida));
+
+        assert_eq!(rustaceo._finalizar_publicacion_vencida(1000, caller, id), Ok(10));
+        assert_eq!(rustaceo.publicaciones.get(&id).unwrap().cantidad_ofertada, 0);
+        assert_eq!(rustaceo.usuarios.get(caller).unwrap().obtener_stock_producto(&1), Some(15));
+
+        // no se puede devolver dos veces
+        assert_eq!(rustaceo._finalizar_publicacion_vencida(1000, caller, id), Err(ErrorFinalizarPublicacionVencida::SinStockPendiente));
+    }
+
+    #[ink::test]
+    fn test_finalizar_publicacion_sin_vencimiento() {
+        let caller = AccountId::from([0x1; 32]);
+        let (mut rustaceo, id) = rustaceo_con_publicacion(caller, None, None);
+
+        assert_eq!(rustaceo._finalizar_publicacion_vencida(u64::MAX, caller, id), Err(ErrorFinalizarPublicacionVencida::PublicacionNoVencida));
+        assert_eq!(rustaceo._finalizar_publicacion_vencida(0, caller, id + 1), Err(ErrorFinalizarPublicacionVencida::PublicacionInexistente));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,365 @@
+//
+// reseña
+//
+
+use ink::primitives::AccountId;
+use ink::prelude::{string::String, vec::Vec};
+
+use crate::rustaceo_libre::RustaceoLibre;
+use crate::structs::pedido::ErrorCalificarPedido;
+
+/// Largo máximo (en bytes) del comentario de una reseña y de la respuesta del vendedor
+pub const MAX_LARGO_COMENTARIO: usize = 500;
+
+/// Cantidad máxima de reseñas que devuelve una página de `ver_resenas_vendedor`
+pub const MAX_RESENAS_POR_PAGINA: u32 = 50;
+
+/// Período de antigüedad (30 días en milisegundos) en el que un pedido pierde un punto de peso por recencia
+pub const PERIODO_RECENCIA: u64 = 2_592_000_000;
+
+/// Peso por recencia de un pedido recién hecho. Baja de a uno por cada período hasta llegar a 1
+pub const PESO_RECENCIA_MAXIMO: u128 = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout)
+)]
+pub struct RespuestaResena {
+    pub comentario: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout)
+)]
+pub struct Resena {
+    pub pedido: u128,
+    pub comprador: AccountId,
+    pub vendedor: AccountId,
+    pub calificacion: u8,
+    pub comentario: String,
+    pub timestamp: u64,
+    pub respuesta: Option<RespuestaResena>, // única respuesta pública del vendedor
+}
+
+//
+// impl Resena -> RustaceoLibre
+//
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout)
+)]
+pub enum ErrorResenarPedido {
+    ComentarioDemasiadoLargo,
+    CalificacionInvalida,
+    UsuarioNoRegistrado,
+    PedidoInexistente,
+    PedidoNoRecibido,
+    SoloCompradorPuede,
+    UsuarioYaCalifico,
+    VendedorInexistente,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(ink::storage::traits::StorageLayout)
+)]
+pub enum ErrorResponderResena {
+    RespuestaVacia,
+    RespuestaDemasiadoLarga,
+    ResenaInexistente,
+    SoloVendedorPuede,
+    ResenaYaRespondida,
+}
+
+impl RustaceoLibre {
+
+    //
+
+    /// Dada una ID de pedido, una calificación (1..=5) y un comentario, el comprador reseña al vendedor.
+    /// La calificación cuenta igual que la de `calificar_pedido`, por lo que sólo se puede reseñar
+    /// un pedido que el comprador todavía no calificó.
+    /// 
+    /// Devolverá error si el comentario supera `MAX_LARGO_COMENTARIO`, la calificación no es válida,
+    /// el usuario no está registrado, el pedido no existe o no fue recibido, el usuario no es el comprador
+    /// o ya calificó el pedido.
+    pub fn _resenar_pedido(&mut self, timestamp: u64, caller: AccountId, id_pedido: u128, calificacion: u8, comentario: String) -> Result<(), ErrorResenarPedido> {
+        // validar comentario
+        if comentario.len() > MAX_LARGO_COMENTARIO {
+            return Err(ErrorResenarPedido::ComentarioDemasiadoLargo);
+        }
+
+        // validar pedido
+        let Some(pedido) = self.pedidos.get(&id_pedido)
+        else { return Err(ErrorResenarPedido::PedidoInexistente); };
+
+        // sólo el comprador reseña
+        if self.usuarios.contains(caller) && pedido.comprador != caller {
+            return Err(ErrorResenarPedido::SoloCompradorPuede);
+        }
+
+        let vendedor = pedido.vendedor;
+
+        // registrar la calificación con la misma lógica que calificar_pedido
+        self._calificar_pedido(caller, id_pedido, calificacion).map_err(|error| match error {
+            ErrorCalificarPedido::CalificacionInvalida => ErrorResenarPedido::CalificacionInvalida,
+            ErrorCalificarPedido::UsuarioNoRegistrado => ErrorResenarPedido::UsuarioNoRegistrado,
+            ErrorCalificarPedido::PedidoInexistente => ErrorResenarPedido::PedidoInexistente,
+            ErrorCalificarPedido::PedidoNoRecibido => ErrorResenarPedido::PedidoNoRecibido,
+            ErrorCalificarPedido::UsuarioYaCalifico => ErrorResenarPedido::UsuarioYaCalifico,
+            ErrorCalificarPedido::VendedorInexistente => ErrorResenarPedido::VendedorInexistente,
+            ErrorCalificarPedido::UsuarioNoParticipa
+            | ErrorCalificarPedido::CompradorInexistente => ErrorResenarPedido::SoloCompradorPuede,
+        })?;
+
+        // guardar reseña
+        self.resenas.insert(id_pedido, Resena {
+            pedido: id_pedido,
+            comprador: caller,
+            vendedor,
+            calificacion,
+            comentario,
+            timestamp,
+            respuesta: None,
+        });
+
+        Ok(())
+    }
+
+    //
+
+    /// El vendedor responde públicamente la reseña de un pedido. Sólo se puede responder una vez.
+    /// 
+    /// Devolverá error si la respuesta está vacía o supera `MAX_LARGO_COMENTARIO`, la reseña no existe,
+    /// el usuario no es el vendedor del pedido o la reseña ya fue respondida.
+    pub fn _responder_resena(&mut self, timestamp: u64, caller: AccountId, id_pedido: u128, respuesta: String) -> Result<(), ErrorResponderResena> {
+        // validar respuesta
+        if respuesta.trim().is_empty() {
+            return Err(ErrorResponderResena::RespuestaVacia);
+        }
+
+        if respuesta.len() > MAX_LARGO_COMENTARIO {
+            return Err(ErrorResponderResena::RespuestaDemasiadoLarga);
+        }
+
+        // validar reseña
+        let Some(resena) = self.resenas.get(&id_pedido)
+        else { return Err(ErrorResponderResena::ResenaInexistente); };
+
+        if resena.vendedor != caller {
+            return Err(ErrorResponderResena::SoloVendedorPuede);
+        }
+
+        if resena.respuesta.is_some() {
+            return Err(ErrorResponderResena::ResenaYaRespondida);
+        }
+
+        // guardar respuesta
+        let mut resena = resena.clone();
+        resena.respuesta = Some(RespuestaResena { comentario: respuesta, timestamp });
+        self.resenas.insert(id_pedido, resena);
+
+        Ok(())
+    }
+
+    //
+
+    /// Devuelve las reseñas recibidas por un vendedor, de la más reciente a la más antigua,
+    /// salteando `offset` reseñas y devolviendo como máximo `limit` (tope `MAX_RESENAS_POR_PAGINA`).
+    pub fn _ver_resenas_vendedor(&self, vendedor: AccountId, offset: u32, limit: u32) -> Vec<Resena> {
+        let mut resenas: Vec<Resena> = self.resenas.values()
+            .filter(|resena| resena.vendedor == vendedor)
+            .cloned()
+            .collect();
+
+        resenas.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then(b.pedido.cmp(&a.pedido)));
+
+        resenas.into_iter()
+            .skip(offset as usize)
+            .take(limit.min(MAX_RESENAS_POR_PAGINA) as usize)
+            .collect()
+    }
+
+    //
+
+    /// Reputación ponderada del vendedor, en centésimas (por ejemplo, 435 equivale a 4,35 estrellas).
+    /// 
+    /// Cada calificación recibida como vendedor pesa más cuanto más reciente es el pedido
+    /// (de `PESO_RECENCIA_MAXIMO` a 1, bajando uno por cada `PERIODO_RECENCIA`) y cuanto mayor es su valor
+    /// (según el orden de magnitud del valor total, para que un pedido enorme no anule al resto).
+    /// 
+    /// Devolverá None si el vendedor no tiene calificaciones.
+    pub fn _ver_reputacion_vendedor(&self, timestamp: u64, vendedor: AccountId) -> Option<u16> {
+        let mut suma_ponderada: u128 = 0;
+        let mut suma_pesos: u128 = 0;
+
+        for pedido in self.pedidos.values() {
+            if pedido.vendedor != vendedor {
+                continue;
+            }
+
+            let Some(calificacion) = pedido.calificacion_comprador
+            else { continue };
+
+            let peso = peso_pedido(timestamp, pedido.timestamp, pedido.valor_total);
+            suma_ponderada = suma_ponderada.saturating_add(peso.saturating_mul(u128::from(calificacion)));
+            suma_pesos = suma_pesos.saturating_add(peso);
+        }
+
+        let promedio = suma_ponderada.saturating_mul(100).checked_div(suma_pesos)?;
+        u16::try_from(promedio).ok()
+    }
+}
+
+/// Peso de un pedido en la reputación: peso por recencia multiplicado por peso por valor.
+fn peso_pedido(ahora: u64, timestamp_pedido: u64, valor_total: u128) -> u128 {
+    let periodos = u128::from(ahora.saturating_sub(timestamp_pedido) / PERIODO_RECENCIA);
+    let peso_recencia = PESO_RECENCIA_MAXIMO.saturating_sub(periodos).max(1);
+
+    // cantidad de bits del valor: crece con el orden de magnitud
+    let peso_valor = u128::from(u128::BITS.saturating_sub(valor_total.leading_zeros())).max(1);
+
+    peso_recencia.saturating_mul(peso_valor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::{pedido::{Pedido, EstadoPedido}, usuario::RolDeSeleccion};
+
+    fn contrato_con_pedido_recibido(id_pedido: u128, valor: u128, timestamp: u64) -> (RustaceoLibre, AccountId, AccountId) {
+        let mut contrato = RustaceoLibre::default();
+        let comprador = AccountId::from([0x01; 32]);
+        let vendedor = AccountId::from([0x02; 32]);
+
+        contrato._registrar_usuario(comprador, RolDeSeleccion::Comprador).unwrap();
+        contrato._registrar_usuario(vendedor, RolDeSeleccion::Vendedor).unwrap();
+
+        insertar_pedido_recibido(&mut contrato, id_pedido, valor, timestamp, comprador, vendedor);
+        (contrato, comprador, vendedor)
+    }
+
+    fn insertar_pedido_recibido(contrato: &mut RustaceoLibre, id_pedido: u128, valor: u128, timestamp: u64, comprador: AccountId, vendedor: AccountId) {
+        let mut pedido = Pedido::new(id_pedido, timestamp, 0, 1, valor, comprador, vendedor);
+        pedido.estado = EstadoPedido::Recibido(timestamp);
+        pedido.fondos_fueron_transferidos = true;
+        contrato.pedidos.insert(id_pedido, pedido);
+    }
+
+    #[ink::test]
+    fn resenar_pedido_funciona() {
+        let (mut contrato, comprador, vendedor) = contrato_con_pedido_recibido(1, 100, 0);
+
+        let resultado = contrato._resenar_pedido(10, comprador, 1, 4, "Llegó rápido".into());
+        assert_eq!(resultado, Ok(()));
+
+        let resena = contrato.resenas.get(&1).unwrap();
+        assert_eq!(resena.vendedor, vendedor);
+        assert_eq!(resena.calificacion, 4);
+        assert_eq!(resena.comentario, String::from("Llegó rápido"));
+        assert_eq!(contrato.pedidos.get(&1).unwrap().calificacion_comprador, Some(4));
+
+        // no se puede reseñar ni calificar dos veces
+        let resultado = contrato._resenar_pedido(11, comprador, 1, 5, "Otra".into());
+        assert_eq!(resultado, Err(ErrorResenarPedido::UsuarioYaCalifico));
+    }
+
+    #[ink::test]
+    fn resenar_pedido_errores() {
+        let (mut contrato, comprador, vendedor) = contrato_con_pedido_recibido(1, 100, 0);
+
+        let largo = "a".repeat(MAX_LARGO_COMENTARIO + 1);
+        assert_eq!(contrato._resenar_pedido(0, comprador, 1, 4, largo), Err(ErrorResenarPedido::ComentarioDemasiadoLargo));
+        assert_eq!(contrato._resenar_pedido(0, comprador, 1, 0, "".into()), Err(ErrorResenarPedido::CalificacionInvalida));
+        assert_eq!(contrato._resenar_pedido(0, comprador, 9, 4, "".into()), Err(ErrorResenarPedido::PedidoInexistente));
+        assert_eq!(contrato._resenar_pedido(0, vendedor, 1, 4, "".into()), Err(ErrorResenarPedido::SoloCompradorPuede));
+        assert_eq!(contrato._resenar_pedido(0, AccountId::from([0x7; 32]), 1, 4, "".into()), Err(ErrorResenarPedido::UsuarioNoRegistrado));
+
+        // pedido sin recibir
+        let mut pedido = contrato.pedidos.get(&1).unwrap().clone();
+        pedido.estado = EstadoPedido::Despachado(0);
+        contrato.pedidos.insert(1, pedido);
+        assert_eq!(contrato._resenar_pedido(0, comprador, 1, 4, "".into()), Err(ErrorResenarPedido::PedidoNoRecibido));
+        assert!(contrato.resenas.is_empty());
+    }
+
+    #[ink::test]
+    fn responder_resena_una_sola_vez() {
+        let (mut contrato, comprador, vendedor) = contrato_con_pedido_recibido(1, 100, 0);
+
+        assert_eq!(contrato._responder_resena(5, vendedor, 1, "Gracias".into()), Err(ErrorResponderResena::ResenaInexistente));
+
+        contrato._resenar_pedido(1, comprador, 1, 2, "Llegó roto".into()).unwrap();
+
+        assert_eq!(contrato._responder_resena(5, vendedor, 1, "  ".into()), Err(ErrorResponderResena::RespuestaVacia));
+        assert_eq!(contrato._responder_resena(5, vendedor, 1, "a".repeat(MAX_LARGO_COMENTARIO + 1)), Err(ErrorResponderResena::RespuestaDemasiadoLarga));
+        assert_eq!(contrato._responder_resena(5, comprador, 1, "Gracias".into()), Err(ErrorResponderResena::SoloVendedorPuede));
+
+        assert_eq!(contrato._responder_resena(5, vendedor, 1, "Te enviamos otro".into()), Ok(()));
+        assert_eq!(
+            contrato.resenas.get(&1).unwrap().respuesta,
+            Some(RespuestaResena { comentario: "Te enviamos otro".into(), timestamp: 5 })
+        );
+
+        assert_eq!(contrato._responder_resena(6, vendedor, 1, "Otra".into()), Err(ErrorResponderResena::ResenaYaRespondida));
+    }
+
+    #[ink::test]
+    fn ver_resenas_vendedor_paginado() {
+        let (mut contrato, comprador, vendedor) = contrato_con_pedido_recibido(1, 100, 0);
+        insertar_pedido_recibido(&mut contrato, 2, 100, 0, comprador, vendedor);
+        insertar_pedido_recibido(&mut contrato, 3, 100, 0, comprador, vendedor);
+
+        contrato._resenar_pedido(30, comprador, 1, 5, "Primera".into()).unwrap();
+        contrato._resenar_pedido(10, comprador, 2, 4, "Segunda".into()).unwrap();
+        contrato._resenar_pedido(20, comprador, 3, 3, "Tercera".into()).unwrap();
+
+        let pedidos = |resenas: Vec<Resena>| resenas.iter().map(|r| r.pedido).collect::<Vec<u128>>();
+
+        assert_eq!(pedidos(contrato._ver_resenas_vendedor(vendedor, 0, 10)), Vec::from([1, 3, 2]));
+        assert_eq!(pedidos(contrato._ver_resenas_vendedor(vendedor, 1, 1)), Vec::from([3]));
+        assert!(contrato._ver_resenas_vendedor(vendedor, 3, 10).is_empty());
+        assert!(contrato._ver_resenas_vendedor(comprador, 0, 10).is_empty());
+    }
+
+    #[ink::test]
+    fn reputacion_pondera_recencia_y_valor() {
+        let (mut contrato, comprador, vendedor) = contrato_con_pedido_recibido(1, 100, 0);
+        assert_eq!(contrato._ver_reputacion_vendedor(0, vendedor), None);
+
+        // pedido viejo y barato con 1 estrella, pedido reciente y caro con 5 estrellas
+        let ahora = PERIODO_RECENCIA * 11;
+        insertar_pedido_recibido(&mut contrato, 2, 1_000_000, ahora, comprador, vendedor);
+        contrato._calificar_pedido(comprador, 1, 1).unwrap();
+        contrato._resenar_pedido(ahora, comprador, 2, 5, "Excelente".into()).unwrap();
+
+        // pesos: pedido 1 -> 1 (recencia) * 7 (bits de 100) = 7; pedido 2 -> 12 * 20 = 240
+        // (1*7 + 5*240) * 100 / 247 = 488
+        assert_eq!(contrato._ver_reputacion_vendedor(ahora, vendedor), Some(488));
+
+        // pesos individuales de cada pedido
+        assert_eq!(peso_pedido(ahora, 0, 100), 7);
+        assert_eq!(peso_pedido(ahora, ahora, 1_000_000), 240);
+    }
+
+    #[ink::test]
+    fn calificacion_del_vendedor_no_cuenta_en_su_reputacion() {
+        let (mut contrato, comprador, vendedor) = contrato_con_pedido_recibido(1, 100, 0);
+
+        contrato._calificar_pedido(vendedor, 1, 1).unwrap();
+        assert_eq!(contrato._ver_reputacion_vendedor(0, vendedor), None);
+
+        contrato._resenar_pedido(0, comprador, 1, 5, "".into()).unwrap();
+        assert_eq!(contrato._ver_reputacion_vendedor(0, vendedor), Some(500));
+    }
+}
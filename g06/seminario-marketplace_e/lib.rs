@@ -30,6 +30,9 @@ mod marketplace {
         NoEsVendedor,
         VendedorNoEncontrado,
         SinInventario,
+        TransicionInvalida, // la orden no puede pasar del estado actual al pedido
+        PublicacionNoExistente,
+        DescuentoInvalido, // porcentaje fuera de 1..=99 o periodo vacio
     }
 
     #[derive(Clone, Debug,PartialEq)]
@@ -111,6 +114,7 @@ mod marketplace {
         nombre:String,
         categoria:Categoria,
         cantidad: u64,
+        umbral_minimo: u64, // por debajo de este stock el producto se considera bajo de stock (0 = sin umbral)
     }
     impl Producto{
         /// Creacion de un nuevo producto
@@ -119,9 +123,14 @@ mod marketplace {
                 id,
                 nombre,
                 categoria, 
-                cantidad
+                cantidad,
+                umbral_minimo: 0,
             }
         } 
+        /// Devuelve si el stock esta por debajo del umbral minimo
+        fn bajo_stock(&self)->bool{
+            self.cantidad < self.umbral_minimo
+        }
         /// Se devuelve el producto
         fn get_producto(&self)->Producto{
             self.clone()
@@ -146,15 +155,19 @@ mod marketplace {
     /// Orden de compra
     pub struct OrdenCompra{
         id_orden:u64,
+        id_publicacion:u32, // indice de la publicacion de la que se compro
         id_comprador:AccountId,
         id_vendedor:AccountId,
         id_producto:u64,
         cantidad:u64,
+        precio_unitario:Balance, // precio vigente de la publicacion al momento de la compra
         estado:EstadoOrden,
+        cancelacion_comprador:bool, // el comprador dio su consentimiento para cancelar
+        cancelacion_vendedor:bool, // el vendedor dio su consentimiento para cancelar
     }
     impl OrdenCompra {
         /// Se crea una nueva orden
-        fn crear_orden(id_ord:u64, comprador : AccountId,  id_vend:AccountId, id_prod:u64, cant:u64) -> Result<OrdenCompra, Errores> {
+        fn crear_orden(id_ord:u64, id_pub:u32, comprador : AccountId,  id_vend:AccountId, id_prod:u64, cant:u64, precio:Balance) -> Result<OrdenCompra, Errores> {
             
             if cant == 0 {
                 return Err(Errores::ProductoSinStock);
@@ -162,52 +175,108 @@ mod marketplace {
 
             Ok(OrdenCompra{
                 id_orden: id_ord, 
+                id_publicacion: id_pub,
                 id_comprador: comprador, 
                 id_vendedor: id_vend, 
                 id_producto: id_prod, 
                 cantidad: cant,
-                estado: EstadoOrden::Pendiente
+                precio_unitario: precio,
+                estado: EstadoOrden::Pendiente,
+                cancelacion_comprador: false,
+                cancelacion_vendedor: false,
             })
 
         }
 
-        //Caso de cambiar de estado validando roles de usuarios
-        /// Cambia el estado interno de la orden
-        pub fn cambiar_estado(&mut self, usuario: Usuario, estado_nuevo: EstadoOrden) -> Result<bool, Errores> {
-            //Se necesita un get_rol() que nos permita verificar el rol del usuario
-            match usuario.get_rol() {
-                //Primera implementacion - no final - de como desarrollar el metodo
-                Roles::Comprador => {
-                    match estado_nuevo {
-                        EstadoOrden::Enviado => {
-                            self.estado = estado_nuevo;
-                            Ok(true)
-                        },
-                        _ => Err(Errores::PermisoDenegado)
-                        
+        /// Cambia el estado interno de la orden validando que el usuario sea parte de ella.
+        /// Solo el vendedor puede marcarla como enviada (desde pendiente) y solo el comprador como recibida (desde enviada).
+        /// Pedir `Cancelada` registra el consentimiento del usuario: devuelve true si con eso se completa la cancelacion.
+        pub fn cambiar_estado(&mut self, id_usuario: AccountId, estado_nuevo: EstadoOrden) -> Result<bool, Errores> {
+            match estado_nuevo {
+                EstadoOrden::Enviado => {
+                    if id_usuario != self.id_vendedor {
+                        return Err(Errores::PermisoDenegado);
                     }
+                    if self.estado != EstadoOrden::Pendiente {
+                        return Err(Errores::TransicionInvalida);
+                    }
+                    self.estado = estado_nuevo;
+                    Ok(true)
                 },
-
-                Roles::Vendedor => {
-                    match estado_nuevo {
-                        EstadoOrden::Recibido => {
-                            self.estado = estado_nuevo;
-                            Ok(true)
-                        },
-                        EstadoOrden::Cancelada => {
-                            // Se necesita consentimiento mutuo
-                            Err(Errores::ConsentimientoFaltante)
-                            
-                        },
-                        _ => {
-                            Err(Errores::PermisoDenegado)
-                        }
+                EstadoOrden::Recibido => {
+                    if id_usuario != self.id_comprador {
+                        return Err(Errores::PermisoDenegado);
                     }
-                }
-                _ => Err(Errores::PermisoDenegado)
+                    if self.estado != EstadoOrden::Enviado {
+                        return Err(Errores::TransicionInvalida);
+                    }
+                    self.estado = estado_nuevo;
+                    Ok(true)
+                },
+                EstadoOrden::Cancelada => self.consentir_cancelacion(id_usuario),
+                EstadoOrden::Pendiente => Err(Errores::TransicionInvalida),
             }
         }
 
+        /// Registra el consentimiento de una de las partes para cancelar la orden.
+        /// Se cancela recien cuando consintieron el comprador y el vendedor; devuelve true en ese caso.
+        fn consentir_cancelacion(&mut self, id_usuario: AccountId) -> Result<bool, Errores> {
+            if id_usuario != self.id_comprador && id_usuario != self.id_vendedor {
+                return Err(Errores::PermisoDenegado);
+            }
+            // una orden recibida o ya cancelada no se puede cancelar
+            if !matches!(self.estado, EstadoOrden::Pendiente | EstadoOrden::Enviado) {
+                return Err(Errores::TransicionInvalida);
+            }
+
+            if id_usuario == self.id_comprador {
+                self.cancelacion_comprador = true;
+            }
+            if id_usuario == self.id_vendedor {
+                self.cancelacion_vendedor = true;
+            }
+
+            if self.cancelacion_comprador && self.cancelacion_vendedor {
+                self.estado = EstadoOrden::Cancelada;
+                return Ok(true);
+            }
+            Ok(false)
+        }
+
+        /// Devuelve si la otra parte de la orden ya pidio cancelarla
+        fn cancelacion_pendiente_para(&self, id_usuario: AccountId) -> bool {
+            if id_usuario == self.id_comprador {
+                self.cancelacion_vendedor
+            } else {
+                self.cancelacion_comprador
+            }
+        }
+
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Decode, Encode, TypeInfo)]
+    /// Cambio de precio de una publicacion
+    pub struct CambioPrecio {
+        fecha: Timestamp,
+        precio: Balance,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Decode, Encode, TypeInfo)]
+    /// Descuento porcentual programado entre dos fechas (inicio incluido, fin excluido)
+    pub struct Descuento {
+        porcentaje: u8,
+        inicio: Timestamp,
+        fin: Timestamp,
+    }
+    impl Descuento {
+        /// Devuelve si el descuento aplica en la fecha dada
+        fn vigente(&self, ahora: Timestamp) -> bool {
+            self.inicio <= ahora && ahora < self.fin
+        }
     }
 
     #[derive(Clone, Debug, PartialEq)]
@@ -218,9 +287,10 @@ mod marketplace {
         id_vendedor: AccountId,
         producto: Producto,
         descripcion: String,
-        fecha: Timestamp, // !TODO implementar fecha de alta de la publicacion
+        fecha: Timestamp, // fecha de alta de la publicacion (block_timestamp)
         precio: Balance,
         disponibles: u64,
+        descuento: Option<Descuento>,
     }    
     impl Publicacion {
         /// Se crea una nueva publicacion
@@ -228,7 +298,7 @@ mod marketplace {
             id_vendedor: AccountId, 
             producto: Producto, 
             descripcion: String, 
-            fecha: Timestamp /* implementar fecha */, 
+            fecha: Timestamp, 
             precio: Balance, 
             stock_inicial: u64
         ) -> Self {
@@ -240,6 +310,7 @@ mod marketplace {
                 fecha,
                 precio,
                 disponibles: stock_inicial,
+                descuento: None,
             }
 
         }
@@ -255,14 +326,24 @@ mod marketplace {
         }
         pub fn descontar_stock(&mut self, cantidad: u64) -> Result<bool, Errores> {
             match self.disponibles.checked_sub(cantidad) {
-                Some(_) => {
-                    self.disponibles =cantidad;//actualizo el stock YA SE DESCONTO CANTIDAD PERO NO SE ACTUALIZABA 
+                Some(restantes) => {
+                    self.disponibles = restantes;
                     return Ok(true)},
                 None => return Err(Errores::ProductoSinStock)
             }
         }
+        /// Devuelve unidades a la publicacion (por ejemplo al cancelarse una orden)
+        pub fn reponer_stock(&mut self, cantidad: u64) -> Result<bool, Errores> {
+            match self.disponibles.checked_add(cantidad) {
+                Some(disponibles) => {
+                    self.disponibles = disponibles;
+                    Ok(true)
+                },
+                None => Err(Errores::ProductoSinStock)
+            }
+        }
 
-        /// Se cambia el precio de la publicacion
+        /// Se cambia el precio de la publicacion (el historial lo registra el contrato)
         pub fn cambiar_precio(&mut self, nuevo_precio: Balance) -> Result<bool, Errores> {
             if nuevo_precio == 0 {
                 return Err(Errores::PrecioNulo)
@@ -270,6 +351,29 @@ mod marketplace {
             self.precio = nuevo_precio;
             Ok(true)
         }
+
+        /// Se programa un descuento porcentual, reemplazando al anterior si lo hubiera
+        pub fn programar_descuento(&mut self, porcentaje: u8, inicio: Timestamp, fin: Timestamp) -> Result<bool, Errores> {
+            if porcentaje == 0 || porcentaje >= 100 || inicio >= fin {
+                return Err(Errores::DescuentoInvalido)
+            }
+            self.descuento = Some(Descuento { porcentaje, inicio, fin });
+            Ok(true)
+        }
+
+        /// Devuelve el precio con el descuento aplicado si esta vigente en la fecha dada
+        pub fn precio_vigente(&self, ahora: Timestamp) -> Balance {
+            match &self.descuento {
+                Some(descuento) if descuento.vigente(ahora) => {
+                    let rebaja = self.precio
+                        .saturating_mul(Balance::from(descuento.porcentaje))
+                        .checked_div(100)
+                        .unwrap_or(0);
+                    self.precio.saturating_sub(rebaja)
+                },
+                _ => self.precio,
+            }
+        }
         
     }
 
@@ -299,8 +403,54 @@ mod marketplace {
             }
             Err(Errores::ProductoNoEncontrado)
         }
+        /// Devuelve el producto del inventario con ese id
+        fn buscar_producto(&self, id_producto: u64) -> Option<&Producto> {
+            self.listado_productos.iter().find(|p| p.id == id_producto)
+        }
+        /// Devuelve el stock de un producto (0 si no esta en el inventario)
+        fn stock_de(&self, id_producto: u64) -> u64 {
+            self.buscar_producto(id_producto).map_or(0, |p| p.cantidad)
+        }
+        /// Cambia el umbral minimo de stock de un producto
+        fn definir_umbral_minimo(&mut self, id_producto: u64, umbral: u64) -> Result<bool, Errores> {
+            match self.listado_productos.iter_mut().find(|p| p.id == id_producto) {
+                Some(p) => {
+                    p.umbral_minimo = umbral;
+                    Ok(true)
+                },
+                None => Err(Errores::ProductoNoEncontrado)
+            }
+        }
+        /// Devuelve los productos que estan por debajo de su umbral minimo
+        fn productos_bajo_stock(&self) -> Vec<Producto> {
+            self.listado_productos.iter().filter(|p| p.bajo_stock()).cloned().collect()
+        }
+        /// Devuelve al inventario las unidades de un producto
+        fn reponer_stock_inventario(&mut self, cantidad: u64, id_producto: u64) -> Result<bool, Errores>{
+            for p in self.listado_productos.iter_mut() {
+                if p.id == id_producto {
+                    match p.cantidad.checked_add(cantidad) {
+                        Some(nuevo_stock) => {
+                            p.cantidad=nuevo_stock;
+                            return Ok(true)},
+                        None => return Err(Errores::ProductoSinStock)
+                    }
+                }
+            }
+            Err(Errores::ProductoNoEncontrado)
+        }
     }
     
+    /// Evento emitido cuando un producto queda por debajo de su umbral minimo, ya sea por una venta o por subir el umbral
+    #[ink(event)]
+    pub struct StockBajo {
+        #[ink(topic)]
+        vendedor: AccountId,
+        id_producto: u64,
+        cantidad: u64,
+        umbral_minimo: u64,
+    }
+
     /// # Marketplace Contract
     #[ink(storage)]
     pub struct Marketplace {
@@ -308,6 +458,8 @@ mod marketplace {
         usuarios: Mapping<AccountId, Usuario>,
         publicaciones: StorageVec<Publicacion>,
         ordenes_de_compra: StorageVec<OrdenCompra>,
+        historial_precios: Mapping<(u32, u32), CambioPrecio>, // (publicacion, n° de cambio), incluye el precio inicial
+        cambios_de_precio: Mapping<u32, u32>, // cantidad de cambios registrados por publicacion
     }
 
     impl Marketplace {        
@@ -319,6 +471,8 @@ mod marketplace {
                 publicaciones: StorageVec::default(),
                 ordenes_de_compra: StorageVec::default(),
                 inventarios: Mapping::default(),
+                historial_precios: Mapping::default(),
+                cambios_de_precio: Mapping::default(),
             }
         }  
 
@@ -383,15 +537,19 @@ mod marketplace {
                             self.publicaciones.set(i, &publi);
                             
                             if let Some(  mut inventario ) = self.inventarios.get(&publi.id_vendedor){
+                                let estaba_bajo = inventario.buscar_producto(id_producto).is_some_and(|p| p.bajo_stock());
                                 inventario.descontar_stock_inventario(cantidad, id_producto)?;
                                 self.inventarios.insert(&publi.id_vendedor,&inventario);
+                                self.avisar_stock_bajo(publi.id_vendedor, &inventario, id_producto, estaba_bajo);
                             }
                             let orden = OrdenCompra::crear_orden(
                                 self.ordenes_de_compra.len() as u64,
+                                i,
                                 id_usuario,
                                 publi.id_vendedor.clone(),
-                                0,
+                                id_producto,
                                 cantidad,
+                                publi.precio_vigente(self.env().block_timestamp()),
                             )?;
 
                             self.ordenes_de_compra.push(&orden);
@@ -445,6 +603,8 @@ mod marketplace {
                                         stock_inicial
                                     );
                                     self.publicaciones.push(&publi);
+                                    let id_publicacion = self.publicaciones.len().saturating_sub(1);
+                                    self.registrar_cambio_precio(id_publicacion, CambioPrecio { fecha, precio });
                                     Ok(true)
                                 },
                                 None => Err(Errores::ProductoNoEncontrado)
@@ -474,6 +634,136 @@ mod marketplace {
             self.alta_publicacion(caller, nombre_producto, fecha, descripcion, precio, stock_inicial)
         }
 
+        /// Devuelve la publicacion con los disponibles limitados al stock del inventario del vendedor
+        /// Si el inventario del producto llego a cero la publicacion figura con 0 disponibles
+        #[ink(message)]
+        pub fn ver_publicacion(&self, id_publicacion: u32) -> Result<Publicacion, Errores> {
+            let mut publi = self.publicaciones.get(id_publicacion).ok_or(Errores::PublicacionNoExistente)?;
+            let stock = self.inventarios.get(publi.id_vendedor).map_or(0, |inv| inv.stock_de(publi.producto.id));
+            publi.disponibles = publi.disponibles.min(stock);
+            Ok(publi)
+        }
+        /// Devuelve si la publicacion se puede comprar (le quedan disponibles y hay stock en el inventario)
+        #[ink(message)]
+        pub fn publicacion_disponible(&self, id_publicacion: u32) -> Result<bool, Errores> {
+            Ok(self.ver_publicacion(id_publicacion)?.disponibles > 0)
+        }
+        /// Busca una publicacion y verifica que el usuario sea quien la publico
+        fn publicacion_del_vendedor(&self, id_usuario: AccountId, id_publicacion: u32) -> Result<Publicacion, Errores> {
+            let publi = self.publicaciones.get(id_publicacion).ok_or(Errores::PublicacionNoExistente)?;
+            if publi.id_vendedor != id_usuario {
+                return Err(Errores::PermisoDenegado);
+            }
+            Ok(publi)
+        }
+        /// Cambia el precio de una publicacion propia
+        #[ink(message)]
+        pub fn editar_publicacion_precio(&mut self, id_publicacion: u32, nuevo_precio: Balance) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            let mut publi = self.publicacion_del_vendedor(caller, id_publicacion)?;
+            publi.cambiar_precio(nuevo_precio)?;
+            self.publicaciones.set(id_publicacion, &publi);
+            self.registrar_cambio_precio(id_publicacion, CambioPrecio { fecha: self.env().block_timestamp(), precio: nuevo_precio });
+            Ok(true)
+        }
+        /// Agrega un cambio al historial de precios de la publicacion
+        /// El historial se guarda aparte para no cargarlo cada vez que se lee la publicacion
+        fn registrar_cambio_precio(&mut self, id_publicacion: u32, cambio: CambioPrecio) {
+            let cantidad = self.cambios_de_precio.get(id_publicacion).unwrap_or(0);
+            self.historial_precios.insert((id_publicacion, cantidad), &cambio);
+            self.cambios_de_precio.insert(id_publicacion, &cantidad.saturating_add(1));
+        }
+        /// Cambia la descripcion de una publicacion propia
+        #[ink(message)]
+        pub fn editar_publicacion_descripcion(&mut self, id_publicacion: u32, nueva_descripcion: String) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            let mut publi = self.publicacion_del_vendedor(caller, id_publicacion)?;
+            publi.cambiar_descripcion(nueva_descripcion)?;
+            self.publicaciones.set(id_publicacion, &publi);
+            Ok(true)
+        }
+        /// Programa un descuento porcentual sobre una publicacion propia entre dos fechas
+        #[ink(message)]
+        pub fn programar_descuento(&mut self, id_publicacion: u32, porcentaje: u8, inicio: Timestamp, fin: Timestamp) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            let mut publi = self.publicacion_del_vendedor(caller, id_publicacion)?;
+            publi.programar_descuento(porcentaje, inicio, fin)?;
+            self.publicaciones.set(id_publicacion, &publi);
+            Ok(true)
+        }
+        /// Devuelve el historial de precios de una publicacion, del mas viejo al mas nuevo
+        #[ink(message)]
+        pub fn ver_historial_precios(&self, id_publicacion: u32) -> Result<Vec<CambioPrecio>, Errores> {
+            if id_publicacion >= self.publicaciones.len() {
+                return Err(Errores::PublicacionNoExistente);
+            }
+            let cantidad = self.cambios_de_precio.get(id_publicacion).unwrap_or(0);
+            Ok((0..cantidad).filter_map(|i| self.historial_precios.get((id_publicacion, i))).collect())
+        }
+        /// Devuelve el precio de una publicacion con el descuento vigente aplicado
+        #[ink(message)]
+        pub fn ver_precio_vigente(&self, id_publicacion: u32) -> Result<Balance, Errores> {
+            let publi = self.publicaciones.get(id_publicacion).ok_or(Errores::PublicacionNoExistente)?;
+            Ok(publi.precio_vigente(self.env().block_timestamp()))
+        }
+
+        /// Devuelve el inventario del usuario si es vendedor
+        fn inventario_de(&self, id_usuario: AccountId) -> Result<Inventario, Errores> {
+            let usuario = self.usuarios.get(id_usuario).ok_or(Errores::UsuarioInexistente)?;
+            if !usuario.puede_vender() {
+                return Err(Errores::NoEsVendedor);
+            }
+            self.inventarios.get(id_usuario).ok_or(Errores::SinInventario)
+        }
+        /// Suma unidades al stock de un producto del inventario propio
+        #[ink(message)]
+        pub fn reponer_stock(&mut self, id_producto: u64, cantidad: u64) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            if cantidad == 0 {
+                return Err(Errores::ProductoSinStock);
+            }
+            let mut inventario = self.inventario_de(caller)?;
+            inventario.reponer_stock_inventario(cantidad, id_producto)?;
+            self.inventarios.insert(caller, &inventario);
+            Ok(true)
+        }
+        /// Devuelve los productos del inventario propio
+        #[ink(message)]
+        pub fn ver_inventario(&self) -> Result<Vec<Producto>, Errores> {
+            let caller = self.env().caller();
+            Ok(self.inventario_de(caller)?.listado_productos)
+        }
+        /// Define el stock minimo de un producto propio; 0 desactiva el aviso
+        #[ink(message)]
+        pub fn definir_umbral_minimo(&mut self, id_producto: u64, umbral_minimo: u64) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            let mut inventario = self.inventario_de(caller)?;
+            let estaba_bajo = inventario.buscar_producto(id_producto).is_some_and(|p| p.bajo_stock());
+            inventario.definir_umbral_minimo(id_producto, umbral_minimo)?;
+            self.inventarios.insert(caller, &inventario);
+            self.avisar_stock_bajo(caller, &inventario, id_producto, estaba_bajo);
+            Ok(true)
+        }
+        /// Emite `StockBajo` si el producto quedo por debajo de su umbral y antes no lo estaba
+        /// Se avisa solo cuando se cruza el umbral, no en cada cambio posterior
+        fn avisar_stock_bajo(&self, vendedor: AccountId, inventario: &Inventario, id_producto: u64, estaba_bajo: bool) {
+            if let Some(p) = inventario.buscar_producto(id_producto) {
+                if !estaba_bajo && p.bajo_stock() {
+                    self.env().emit_event(StockBajo {
+                        vendedor,
+                        id_producto,
+                        cantidad: p.cantidad,
+                        umbral_minimo: p.umbral_minimo,
+                    });
+                }
+            }
+        }
+        /// Devuelve los productos de un vendedor que estan por debajo de su umbral minimo
+        #[ink(message)]
+        pub fn productos_bajo_stock(&self, vendedor: AccountId) -> Result<Vec<Producto>, Errores> {
+            Ok(self.inventario_de(vendedor)?.productos_bajo_stock())
+        }
+
         fn cambiar_de_rol(&mut self, rol_nuevo: Roles) -> Result<bool, Errores> {
             // ErrorUsuario::UsuarioInexistente
             let caller = self.env().caller();
@@ -560,6 +850,61 @@ mod marketplace {
 
             self.agregar_producto_inventario(id_producto, stock_total, nombre, categoria)
         }
+
+        /// Aplica un cambio de estado a una orden en nombre del usuario
+        /// Si con el cambio se completa la cancelacion, devuelve el stock al inventario del vendedor y a la publicacion de la que se compro
+        fn actualizar_orden(&mut self, id_usuario: AccountId, id_orden: u64, estado_nuevo: EstadoOrden) -> Result<bool, Errores> {
+            let indice = u32::try_from(id_orden).map_err(|_| Errores::OrdenNoExistente)?;
+            let mut orden = self.ordenes_de_compra.get(indice).ok_or(Errores::OrdenNoExistente)?;
+
+            let resultado = orden.cambiar_estado(id_usuario, estado_nuevo)?;
+
+            if orden.estado == EstadoOrden::Cancelada {
+                let mut inventario = self.inventarios.get(orden.id_vendedor).ok_or(Errores::SinInventario)?;
+                inventario.reponer_stock_inventario(orden.cantidad, orden.id_producto)?;
+                self.inventarios.insert(orden.id_vendedor, &inventario);
+
+                let mut publi = self.publicaciones.get(orden.id_publicacion).ok_or(Errores::PublicacionNoExistente)?;
+                publi.reponer_stock(orden.cantidad)?;
+                self.publicaciones.set(orden.id_publicacion, &publi);
+            }
+
+            self.ordenes_de_compra.set(indice, &orden);
+            Ok(resultado)
+        }
+        /// Marca la orden como enviada (solo el vendedor)
+        #[ink(message)]
+        pub fn marcar_enviado(&mut self, id_orden: u64) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            self.actualizar_orden(caller, id_orden, EstadoOrden::Enviado)
+        }
+        /// Marca la orden como recibida (solo el comprador)
+        #[ink(message)]
+        pub fn marcar_recibido(&mut self, id_orden: u64) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            self.actualizar_orden(caller, id_orden, EstadoOrden::Recibido)
+        }
+        /// Pide la cancelacion de la orden (comprador o vendedor)
+        /// Devuelve true si la otra parte ya lo habia pedido y la orden quedo cancelada
+        #[ink(message)]
+        pub fn solicitar_cancelacion(&mut self, id_orden: u64) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            self.actualizar_orden(caller, id_orden, EstadoOrden::Cancelada)
+        }
+        /// Acepta la cancelacion que pidio la otra parte de la orden
+        #[ink(message)]
+        pub fn aceptar_cancelacion(&mut self, id_orden: u64) -> Result<bool, Errores> {
+            let caller = self.env().caller();
+            self.aceptar_cancelacion_orden(caller, id_orden)
+        }
+        fn aceptar_cancelacion_orden(&mut self, id_usuario: AccountId, id_orden: u64) -> Result<bool, Errores> {
+            let indice = u32::try_from(id_orden).map_err(|_| Errores::OrdenNoExistente)?;
+            let orden = self.ordenes_de_compra.get(indice).ok_or(Errores::OrdenNoExistente)?;
+            if !orden.cancelacion_pendiente_para(id_usuario) {
+                return Err(Errores::ConsentimientoFaltante);
+            }
+            self.actualizar_orden(id_usuario, id_orden, EstadoOrden::Cancelada)
+        }
     }
     #[cfg(test)]
     mod tests {
@@ -608,7 +953,7 @@ mod marketplace {
         }
         #[test]
         fn test_crear_orden_valida() {
-            let orden = OrdenCompra::crear_orden(1, crear_id(1), crear_id(1), 10, 5);
+            let orden = OrdenCompra::crear_orden(1, 0, crear_id(1), crear_id(1), 10, 5, 100);
             assert!(orden.is_ok());
             let orden = orden.unwrap();
             assert_eq!(orden.id_comprador, crear_id(1));           
@@ -617,7 +962,7 @@ mod marketplace {
         }
         #[test]
         fn test_crear_orden_cantidad_cero() {
-            let orden = OrdenCompra::crear_orden(1, crear_id(1), crear_id(1), 10, 0);
+            let orden = OrdenCompra::crear_orden(1, 0, crear_id(1), crear_id(1), 10, 0, 100);
             assert_eq!(orden, Err(Errores::ProductoSinStock));
         }
         #[ink::test]
@@ -657,11 +1002,10 @@ mod marketplace {
         }
 
         #[test]
-        fn test_cambiar_estado_por_comprador() {
-            match OrdenCompra::crear_orden(1, crear_id(1), crear_id(1), 10, 2) {
+        fn test_cambiar_estado_por_vendedor() {
+            match OrdenCompra::crear_orden(1, 0, crear_id(1), crear_id(2), 10, 2, 100) {
                 Ok(mut orden) => {
-                    let usuario = Usuario::new("comprador".to_string(), 100, crear_id(2), Roles::Comprador);
-                    let resultado = orden.cambiar_estado(usuario, EstadoOrden::Enviado);
+                    let resultado = orden.cambiar_estado(crear_id(2), EstadoOrden::Enviado);
                     assert_eq!(resultado, Ok(true));
                     assert_eq!(orden.estado, EstadoOrden::Enviado);
                 },
@@ -670,10 +1014,36 @@ mod marketplace {
         }
         #[test]
         fn test_cambiar_estado_permiso_denegado() {
-            let mut orden=OrdenCompra::crear_orden(1, crear_id(1), crear_id(1), 10, 2).unwrap();
-            let usuario=Usuario::new("otro".to_string(), 101, crear_id(1), Roles::Vendedor);
-            let resultado=orden.cambiar_estado(usuario, EstadoOrden::Enviado);
+            let mut orden=OrdenCompra::crear_orden(1, 0, crear_id(1), crear_id(2), 10, 2, 100).unwrap();
+            // el comprador no puede marcarla como enviada
+            let resultado=orden.cambiar_estado(crear_id(1), EstadoOrden::Enviado);
             assert_eq!(resultado,Err(Errores::PermisoDenegado));
+            // el vendedor no puede marcarla como recibida
+            let resultado=orden.cambiar_estado(crear_id(2), EstadoOrden::Recibido);
+            assert_eq!(resultado,Err(Errores::PermisoDenegado));
+            // alguien ajeno a la orden no puede cancelarla
+            let resultado=orden.cambiar_estado(crear_id(3), EstadoOrden::Cancelada);
+            assert_eq!(resultado,Err(Errores::PermisoDenegado));
+        }
+        #[test]
+        fn test_cambiar_estado_transiciones_invalidas() {
+            let mut orden=OrdenCompra::crear_orden(1, 0, crear_id(1), crear_id(2), 10, 2, 100).unwrap();
+            // no se puede recibir algo que no se envio
+            assert_eq!(orden.cambiar_estado(crear_id(1), EstadoOrden::Recibido), Err(Errores::TransicionInvalida));
+            assert_eq!(orden.cambiar_estado(crear_id(2), EstadoOrden::Enviado), Ok(true));
+            assert_eq!(orden.cambiar_estado(crear_id(2), EstadoOrden::Enviado), Err(Errores::TransicionInvalida));
+            assert_eq!(orden.cambiar_estado(crear_id(1), EstadoOrden::Recibido), Ok(true));
+            // una orden recibida no se cancela ni vuelve a pendiente
+            assert_eq!(orden.cambiar_estado(crear_id(1), EstadoOrden::Cancelada), Err(Errores::TransicionInvalida));
+            assert_eq!(orden.cambiar_estado(crear_id(2), EstadoOrden::Pendiente), Err(Errores::TransicionInvalida));
+        }
+        #[test]
+        fn test_cancelacion_necesita_ambas_partes() {
+            let mut orden=OrdenCompra::crear_orden(1, 0, crear_id(1), crear_id(2), 10, 2, 100).unwrap();
+            assert_eq!(orden.cambiar_estado(crear_id(1), EstadoOrden::Cancelada), Ok(false));
+            assert_eq!(orden.estado, EstadoOrden::Pendiente);
+            assert_eq!(orden.cambiar_estado(crear_id(2), EstadoOrden::Cancelada), Ok(true));
+            assert_eq!(orden.estado, EstadoOrden::Cancelada);
         }
         #[test]
         fn test_alta_publicacion_y_cambiar_descripcion() {
@@ -696,9 +1066,23 @@ mod marketplace {
             assert_eq!(pub1.precio, 800);
             resultado = pub1.cambiar_precio(0);
             assert_eq!(resultado, Err(Errores::PrecioNulo));            
+            assert_eq!(pub1.precio, 800);
 
         }
         #[test]
+        fn test_precio_vigente_con_descuento() {
+            let prod = Producto::new(1,"monitor".to_string(), Categoria::Electronica, 20);
+            let mut pub1 = Publicacion::alta_publicacion(crear_id(1),prod,String::from("HD"),123,500,10,);
+            assert_eq!(pub1.programar_descuento(0, 100, 200), Err(Errores::DescuentoInvalido));
+            assert_eq!(pub1.programar_descuento(100, 100, 200), Err(Errores::DescuentoInvalido));
+            assert_eq!(pub1.programar_descuento(20, 200, 200), Err(Errores::DescuentoInvalido));
+            assert_eq!(pub1.programar_descuento(20, 100, 200), Ok(true));
+            assert_eq!(pub1.precio_vigente(99), 500);
+            assert_eq!(pub1.precio_vigente(100), 400);
+            assert_eq!(pub1.precio_vigente(199), 400);
+            assert_eq!(pub1.precio_vigente(200), 500);
+        }
+        #[test]
         fn test_inventario_descontar_stock_ok() {
             let mut inventario = Inventario::new();
             let prod = Producto::new(1,"camiseta".to_string(), Categoria::Deporte, 10);
@@ -765,6 +1149,196 @@ mod marketplace {
             let resultado = contrato.crear_publicacion("mouse".to_string(), "buen mouse".to_string(), 1000, 10);
             assert_eq!(resultado, Err(Errores::ProductoNoEncontrado));
         }
+        /// Arma un contrato con alice compradora, bob vendedor y una orden de 3 mouses (de 10)
+        fn contrato_con_orden() -> Marketplace {
+            let mut contrato = iniciar_contrato();
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+            contrato.crear_usuario("Vendedor".to_string(), 2222, Roles::Vendedor).unwrap();
+            contrato.agregar_producto(10, "mouse".to_string(), Categoria::Electronica).unwrap();
+            contrato.crear_publicacion("mouse".to_string(), "rapido".to_string(), 100, 10).unwrap();
+            set_caller(accounts.alice);
+            contrato.crear_usuario("Comprador".to_string(), 1111, Roles::Comprador).unwrap();
+            contrato.hacer_orden(0, 3).unwrap();
+            contrato
+        }
+        #[ink::test]
+        fn test_marcar_enviado_y_recibido() {
+            let mut contrato = contrato_con_orden();
+            let accounts = default_accounts();
+            // la compradora no puede marcarla como enviada
+            assert_eq!(contrato.marcar_enviado(0), Err(Errores::PermisoDenegado));
+            assert_eq!(contrato.marcar_recibido(0), Err(Errores::TransicionInvalida));
+            set_caller(accounts.bob);
+            assert_eq!(contrato.marcar_enviado(0), Ok(true));
+            assert_eq!(contrato.marcar_recibido(0), Err(Errores::PermisoDenegado));
+            set_caller(accounts.alice);
+            assert_eq!(contrato.marcar_recibido(0), Ok(true));
+            assert_eq!(contrato.ordenes_de_compra.get(0).unwrap().estado, EstadoOrden::Recibido);
+            assert_eq!(contrato.marcar_recibido(1), Err(Errores::OrdenNoExistente));
+        }
+        #[ink::test]
+        fn test_cancelacion_mutua_repone_inventario() {
+            let mut contrato = contrato_con_orden();
+            let accounts = default_accounts();
+            assert_eq!(contrato.inventarios.get(accounts.bob).unwrap().listado_productos[0].cantidad, 7);
+            // no hay nada que aceptar todavia
+            assert_eq!(contrato.aceptar_cancelacion(0), Err(Errores::ConsentimientoFaltante));
+            assert_eq!(contrato.solicitar_cancelacion(0), Ok(false));
+            // pedirla de nuevo no alcanza para cancelarla
+            assert_eq!(contrato.aceptar_cancelacion(0), Err(Errores::ConsentimientoFaltante));
+            assert_eq!(contrato.ordenes_de_compra.get(0).unwrap().estado, EstadoOrden::Pendiente);
+            // alguien ajeno no puede aceptar
+            set_caller(accounts.charlie);
+            assert_eq!(contrato.aceptar_cancelacion(0), Err(Errores::PermisoDenegado));
+            set_caller(accounts.bob);
+            assert_eq!(contrato.aceptar_cancelacion(0), Ok(true));
+            assert_eq!(contrato.ordenes_de_compra.get(0).unwrap().estado, EstadoOrden::Cancelada);
+            assert_eq!(contrato.inventarios.get(accounts.bob).unwrap().listado_productos[0].cantidad, 10);
+            // una orden cancelada no se puede enviar
+            assert_eq!(contrato.marcar_enviado(0), Err(Errores::TransicionInvalida));
+        }
+        #[ink::test]
+        fn test_cancelacion_repone_publicacion() {
+            let mut contrato = contrato_con_orden();
+            let accounts = default_accounts();
+            assert_eq!(contrato.ordenes_de_compra.get(0).unwrap().id_publicacion, 0);
+            assert_eq!(contrato.ver_publicacion(0).unwrap().disponibles, 7);
+            assert_eq!(contrato.solicitar_cancelacion(0), Ok(false));
+            // con un solo consentimiento la publicacion no cambia
+            assert_eq!(contrato.ver_publicacion(0).unwrap().disponibles, 7);
+            set_caller(accounts.bob);
+            assert_eq!(contrato.aceptar_cancelacion(0), Ok(true));
+            assert_eq!(contrato.ver_publicacion(0).unwrap().disponibles, 10);
+            assert_eq!(contrato.publicaciones.get(0).unwrap().disponibles, 10);
+        }
+        #[ink::test]
+        fn test_editar_publicacion_solo_vendedor() {
+            let mut contrato = contrato_con_orden();
+            let accounts = default_accounts();
+            // alice es la compradora, no puede editar
+            assert_eq!(contrato.editar_publicacion_precio(0, 150), Err(Errores::PermisoDenegado));
+            assert_eq!(contrato.editar_publicacion_descripcion(0, "otra".to_string()), Err(Errores::PermisoDenegado));
+            assert_eq!(contrato.programar_descuento(0, 10, 0, 100), Err(Errores::PermisoDenegado));
+            set_caller(accounts.bob);
+            assert_eq!(contrato.editar_publicacion_precio(1, 150), Err(Errores::PublicacionNoExistente));
+            assert_eq!(contrato.editar_publicacion_descripcion(0, "".to_string()), Err(Errores::ProductoSinDescripcion));
+            assert_eq!(contrato.editar_publicacion_descripcion(0, "muy rapido".to_string()), Ok(true));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(50);
+            assert_eq!(contrato.editar_publicacion_precio(0, 150), Ok(true));
+            // cualquiera puede consultar el historial
+            set_caller(accounts.alice);
+            let historial = contrato.ver_historial_precios(0).unwrap();
+            assert_eq!(historial, vec![CambioPrecio { fecha: 0, precio: 100 }, CambioPrecio { fecha: 50, precio: 150 }]);
+            assert_eq!(contrato.ver_historial_precios(1), Err(Errores::PublicacionNoExistente));
+            assert_eq!(contrato.publicaciones.get(0).unwrap().descripcion, "muy rapido");
+        }
+        #[ink::test]
+        fn test_hacer_orden_aplica_descuento_vigente() {
+            let mut contrato = contrato_con_orden();
+            let accounts = default_accounts();
+            // la primera orden se hizo sin descuento
+            assert_eq!(contrato.ordenes_de_compra.get(0).unwrap().precio_unitario, 100);
+            set_caller(accounts.bob);
+            assert_eq!(contrato.programar_descuento(0, 25, 1000, 2000), Ok(true));
+            set_caller(accounts.alice);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1500);
+            assert_eq!(contrato.ver_precio_vigente(0), Ok(75));
+            contrato.hacer_orden(0, 1).unwrap();
+            assert_eq!(contrato.ordenes_de_compra.get(1).unwrap().precio_unitario, 75);
+            // vencido el descuento vuelve el precio de lista
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2000);
+            contrato.hacer_orden(0, 1).unwrap();
+            assert_eq!(contrato.ordenes_de_compra.get(2).unwrap().precio_unitario, 100);
+        }
+        #[ink::test]
+        fn test_reponer_stock_y_ver_inventario() {
+            let mut contrato = contrato_con_orden();
+            let accounts = default_accounts();
+            // alice es compradora, no tiene inventario
+            assert_eq!(contrato.ver_inventario(), Err(Errores::NoEsVendedor));
+            assert_eq!(contrato.reponer_stock(0, 5), Err(Errores::NoEsVendedor));
+            set_caller(accounts.bob);
+            assert_eq!(contrato.reponer_stock(0, 0), Err(Errores::ProductoSinStock));
+            assert_eq!(contrato.reponer_stock(9, 5), Err(Errores::ProductoNoEncontrado));
+            assert_eq!(contrato.reponer_stock(0, 5), Ok(true));
+            let inventario = contrato.ver_inventario().unwrap();
+            assert_eq!(inventario.len(), 1);
+            assert_eq!(inventario[0].cantidad, 12);
+        }
+        #[ink::test]
+        fn test_umbral_minimo_emite_evento_una_vez() {
+            let mut contrato = contrato_con_orden();
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+            assert_eq!(contrato.definir_umbral_minimo(9, 5), Err(Errores::ProductoNoEncontrado));
+            assert_eq!(contrato.definir_umbral_minimo(0, 5), Ok(true));
+            assert_eq!(contrato.productos_bajo_stock(accounts.bob), Ok(Vec::new()));
+            let eventos_antes = ink::env::test::recorded_events().count();
+            // 7 -> 4: cruza el umbral
+            set_caller(accounts.alice);
+            contrato.hacer_orden(0, 3).unwrap();
+            assert_eq!(ink::env::test::recorded_events().count(), eventos_antes + 1);
+            let evento = ultimo_stock_bajo();
+            assert_eq!(evento.vendedor, accounts.bob);
+            assert_eq!(evento.id_producto, 0);
+            assert_eq!(evento.cantidad, 4);
+            assert_eq!(evento.umbral_minimo, 5);
+            let bajo_stock = contrato.productos_bajo_stock(accounts.bob).unwrap();
+            assert_eq!(bajo_stock.len(), 1);
+            assert_eq!(bajo_stock[0].cantidad, 4);
+            // 4 -> 3: ya estaba bajo, no se vuelve a avisar
+            contrato.hacer_orden(0, 1).unwrap();
+            assert_eq!(ink::env::test::recorded_events().count(), eventos_antes + 1);
+            // reponiendo sale de la lista
+            set_caller(accounts.bob);
+            contrato.reponer_stock(0, 10).unwrap();
+            assert_eq!(contrato.productos_bajo_stock(accounts.bob), Ok(Vec::new()));
+            assert_eq!(contrato.productos_bajo_stock(accounts.alice), Err(Errores::NoEsVendedor));
+        }
+        #[ink::test]
+        fn test_subir_umbral_por_encima_del_stock_emite_evento() {
+            let mut contrato = contrato_con_orden();
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+            let eventos_antes = ink::env::test::recorded_events().count();
+            // el stock es 7: un umbral de 5 no lo marca
+            assert_eq!(contrato.definir_umbral_minimo(0, 5), Ok(true));
+            assert_eq!(ink::env::test::recorded_events().count(), eventos_antes);
+            // con 8 el producto queda bajo sin que haya ninguna venta
+            assert_eq!(contrato.definir_umbral_minimo(0, 8), Ok(true));
+            assert_eq!(ink::env::test::recorded_events().count(), eventos_antes + 1);
+            let evento = ultimo_stock_bajo();
+            assert_eq!(evento.vendedor, accounts.bob);
+            assert_eq!(evento.id_producto, 0);
+            assert_eq!(evento.cantidad, 7);
+            assert_eq!(evento.umbral_minimo, 8);
+            // subirlo de nuevo no vuelve a avisar
+            assert_eq!(contrato.definir_umbral_minimo(0, 9), Ok(true));
+            assert_eq!(ink::env::test::recorded_events().count(), eventos_antes + 1);
+        }
+        fn ultimo_stock_bajo() -> StockBajo {
+            let evento = ink::env::test::recorded_events().last().expect("deberia haber un evento");
+            <StockBajo as ink::scale::Decode>::decode(&mut &evento.data[..]).expect("el evento deberia ser StockBajo")
+        }
+        #[ink::test]
+        fn test_publicacion_sin_stock_en_inventario_no_disponible() {
+            let mut contrato = iniciar_contrato();
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+            contrato.crear_usuario("Vendedor".to_string(), 2222, Roles::Vendedor).unwrap();
+            contrato.agregar_producto(2, "mouse".to_string(), Categoria::Electronica).unwrap();
+            contrato.crear_publicacion("mouse".to_string(), "rapido".to_string(), 100, 5).unwrap();
+            // la publicacion ofrece 5 pero el inventario solo tiene 2
+            assert_eq!(contrato.ver_publicacion(0).unwrap().disponibles, 2);
+            assert_eq!(contrato.publicacion_disponible(0), Ok(true));
+            set_caller(accounts.alice);
+            contrato.crear_usuario("Comprador".to_string(), 1111, Roles::Comprador).unwrap();
+            contrato.hacer_orden(0, 2).unwrap();
+            assert_eq!(contrato.ver_publicacion(0).unwrap().disponibles, 0);
+            assert_eq!(contrato.publicacion_disponible(0), Ok(false));
+            assert_eq!(contrato.publicacion_disponible(1), Err(Errores::PublicacionNoExistente));
+        }
         #[ink::test]
         fn test_hacer_orden_sin_usuario() {
             let mut contrato = iniciar_contrato();
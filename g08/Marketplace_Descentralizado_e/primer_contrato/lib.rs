@@ -6,7 +6,7 @@ mod primer_contrato {
     use ink::storage::Mapping;
     use ink::storage::StorageVec;
     use ink::prelude::vec::Vec;
-    use ink::prelude::string::{String, ToString};
+    use ink::prelude::string::String;
 
 /////////////////////////// SISTEMA ///////////////////////////
     /// Struct que hace de "sistema". Encargado de persistir los datos. 
@@ -40,13 +40,13 @@ mod primer_contrato {
         #[ink(message)]
         /// La función agregar_usuario_sistema se encarga de registrar el usuario en mi sistema (se almacena en "usuarios"). 
         /// Solo será registrado si su ID no se halla en mi Mapping de usuarios. 
-        pub fn agregar_usuario_sistema(&mut self, nombre: String, apellido: String, direccion: String, email: String, rol: Rol) -> Result <(), String>{
+        pub fn agregar_usuario_sistema(&mut self, nombre: String, apellido: String, direccion: String, email: String, rol: Rol) -> Result <(), Error>{
             let account_id = self.env().caller();
             self.priv_agregar_usuario_sistema(account_id, nombre, apellido, direccion, email, rol)
         }
-        fn priv_agregar_usuario_sistema(&mut self, account_id: AccountId, nombre: String, apellido: String, direccion: String, email: String, rol: Rol) -> Result <(), String>{
+        fn priv_agregar_usuario_sistema(&mut self, account_id: AccountId, nombre: String, apellido: String, direccion: String, email: String, rol: Rol) -> Result <(), Error>{
             if self.usuarios.get(account_id).is_some(){
-                Err("El usuario ya esta registrado.".to_string())
+                Err(Error::UsuarioYaRegistrado)
             } else {
                 let usuario = Usuario::nuevo(account_id, nombre, apellido, direccion, email, rol);
                 self.usuarios.insert(account_id, &usuario); 
@@ -57,55 +57,55 @@ mod primer_contrato {
         /// La función cargar_producto se encarga de registrar el producto en mi sistema (se almacena en "historial_productos"). 
         /// Se comprueba que el usuario que invoca la función esté registrado en mi sistema. Si lo está, se comprueba su rol. Si el usuario tiene rol vendedor u ambos, se le asigna un id al producto y luego es agregado a su estructura correspondiente. 
         #[ink(message)]
-        pub fn cargar_producto(&mut self, nombre: String, descripcion: String, precio: u32, categoria: String, stock: u32) -> Result<(), String>{
+        pub fn cargar_producto(&mut self, nombre: String, descripcion: String, precio: u32, categoria: String, stock: u32) -> Result<(), Error>{
             let account_id = self.env().caller();
             self.priv_cargar_producto(account_id, nombre, descripcion, precio, categoria, stock)
         }
-        fn priv_cargar_producto(&mut self, account_id: AccountId, nombre: String, descripcion: String, precio: u32, categoria: String, stock: u32) -> Result<(), String>{
+        fn priv_cargar_producto(&mut self, account_id: AccountId, nombre: String, descripcion: String, precio: u32, categoria: String, stock: u32) -> Result<(), Error>{
             if let Some(usuario) = self.usuarios.get(account_id){
                 if (usuario.rol == Rol::Vend) | (usuario.rol == Rol::Ambos){
-                    self.dimension_logica_productos = self.dimension_logica_productos.checked_add(1).ok_or("Error al sumar.")?;
+                    self.dimension_logica_productos = self.dimension_logica_productos.checked_add(1).ok_or(Error::OverflowIdProducto)?;
                     self.historial_productos.insert(self.dimension_logica_productos, &(Producto::cargar_producto(self.dimension_logica_productos, nombre, descripcion, precio, categoria), stock));
                     Ok(())
                 }
                 else {
-                    Err("El usuario no tiene permisos para cargar productos. No es vendedor.".to_string())
+                    Err(Error::SinPermisoParaCargarProductos)
                 }
             }
             else {
-                Err("No existe el usuario.".to_string())
+                Err(Error::UsuarioInexistente)
             }
         }
 
         /// La función "crear_publicacion" se encarga de crear la publicación y luego registrarla en mi sistema (se almacena en "historial_publicaciones"). 
         /// Primero se comprueba que el usuario que invocó la función exista en mi sistema. En caso de no estar se retorna un error.
         /// Si el usuario existe, se asigna el id de la publicación, se calcula su precio final, luego se crea la publicación delegando su creación al usuario.
+        /// Solo se comprueba que haya stock para al menos un paquete. El stock no se reserva, se descuenta recién al crear cada orden de compra.
         /// Una vez creada la publicación es agregada a mi sistema ("historial_publicaciones"). 
         /// Luego el usuario se reinserta en mis sistema debido a que en su método "crear_publicación" su estado fue modificado.
         /// Finalmente se retorna Ok, si todo ha salido bien. Indicando que la operación fue un éxito. 
         #[ink(message)]
-        pub fn crear_publicacion(&mut self, productos_a_publicar: Vec<(u32, u32)>) -> Result<(), String> {
+        pub fn crear_publicacion(&mut self, productos_a_publicar: Vec<(u32, u32)>) -> Result<(), Error> {
             let account_id = self.env().caller();
             self.priv_crear_publicacion(account_id, productos_a_publicar)
         }
-        fn priv_crear_publicacion(&mut self, account_id: AccountId, productos_a_publicar: Vec<(u32, u32)>) -> Result<(), String> {
+        fn priv_crear_publicacion(&mut self, account_id: AccountId, productos_a_publicar: Vec<(u32, u32)>) -> Result<(), Error> {
             if let Some(mut usuario) = self.usuarios.get(account_id){
                 for (_id, cantidad) in productos_a_publicar.clone(){
                     if cantidad == 0 {
-                        return Err("Un producto tiene cantidades no validas.".to_string())
+                        return Err(Error::CantidadInvalida)
                     }
                 }
                 self.hay_stock_suficiente(productos_a_publicar.clone())?;
                 let id_publicacion = self.historial_publicaciones.len();
                 let precio_final = self.calcular_precio_final(productos_a_publicar.clone())?;
-                let publicacion = usuario.crear_publicacion(productos_a_publicar.clone(), precio_final, id_publicacion, account_id)?;
-                self.descontar_stock(productos_a_publicar)?;
+                let publicacion = usuario.crear_publicacion(productos_a_publicar, precio_final, id_publicacion, account_id)?;
                 self.historial_publicaciones.push(&(id_publicacion, publicacion));
                 self.usuarios.insert(account_id, &usuario);
                 Ok(())
             }
             else {
-                Err("No existe el usuario.".to_string())
+                Err(Error::UsuarioInexistente)
             }
 
         }
@@ -115,10 +115,10 @@ mod primer_contrato {
         /// La función "visualizar_productos_de_publicación" se encarga de retornar todos los datos de una publicación especifica (id) recibida por parámetro. 
         /// Recorro todo mi StorageVec en busca de la publicación deseada. Hasta encontrar la id correspondiente al producto buscado o hasta terminar de iterar sobre él. Si termina la iteración significa que no se encontró el producto con la id recibida, entonces terminando la función y retornando un error. 
         /// Si se halló el producto se clona los datos y son retornados en un Ok, indicando que la operación de búsqueda fue existosa. 
-        pub fn visualizar_productos_de_publicacion(&self, id_publicacion: u32) -> Result<Publicacion, String> {
+        pub fn visualizar_productos_de_publicacion(&self, id_publicacion: u32) -> Result<Publicacion, Error> {
             self.priv_visualizar_productos_de_publicacion(id_publicacion)
         }
-        fn priv_visualizar_productos_de_publicacion(&self, id_publicacion: u32) -> Result<Publicacion, String> {
+        fn priv_visualizar_productos_de_publicacion(&self, id_publicacion: u32) -> Result<Publicacion, Error> {
             for i in 0..self.historial_publicaciones.len() {
                 if let Some((id, publicacion)) = self.historial_publicaciones.get(i) {
                     if id == id_publicacion { 
@@ -126,7 +126,7 @@ mod primer_contrato {
                     }
                 }
             }
-            Err("No se encontro la publicacion.".to_string())
+            Err(Error::PublicacionInexistente)
         }
 
         #[ink(message)]
@@ -134,52 +134,56 @@ mod primer_contrato {
         /// Primero se comprueba que el usuario esté en mi sistema. En caso de no estarlo, se retorna un error. 
         /// Si el usuario se encuentra en mi sistema, se delega el cambio de estado al usuario. Si el rol recibido por parametro, es igual al que posee el usuario se retorna error.
         /// Si el rol fue efectivamente modificado se vuelve a insertar en el Mapping de usuarios del sistema debido a que su estado se alteró. Retornando Ok, indicando el éxito de la operación.
-        pub fn modificar_rol(&mut self, nuevo_rol: Rol) -> Result<(), String>{
+        pub fn modificar_rol(&mut self, nuevo_rol: Rol) -> Result<(), Error>{
             let account_id = self.env().caller();
             self.priv_modificar_rol(account_id, nuevo_rol)
         }
-        fn priv_modificar_rol(&mut self, account_id: AccountId, nuevo_rol: Rol) -> Result<(), String>{
+        fn priv_modificar_rol(&mut self, account_id: AccountId, nuevo_rol: Rol) -> Result<(), Error>{
             if let Some(mut usuario) = self.usuarios.get(account_id){
                 usuario.modificar_rol(nuevo_rol)?;
                 self.usuarios.insert(account_id, &usuario);
                 Ok(())
             }
             else {
-                Err("No existe el usuario.".to_string())
+                Err(Error::UsuarioInexistente)
             }
         }
          
         #[ink(message)]
-        /// La función crear_orden_de_compra se encarga de crear una orden de compra de una publicación recibida por parametro.
+        /// La función crear_orden_de_compra se encarga de crear una orden de compra por "cantidad" paquetes de una publicación recibida por parametro.
         /// Primero se comprueba que el usuario que invoca la función se encuentra en mi sistema. En caso de no estarlo, se retorna error.
-        /// Luego se comprueba si el stock de la publicación es suficiente con el que posee el vendedor de los productos indicados en la publicación. Si no hay suficiente, se cancela la creación de la orden, retornando el error correspondiente.
+        /// Si la cantidad de paquetes es cero se retorna error.
         /// Se crea el id de la orden.
         /// Se delega la creación de la orden al usuario. Si la creación de la orden fue un éxito se sigue, sino se retorna el error apropidado. 
-        /// Se descuenta el stock de los productos vendidos. 
+        /// Se descuenta el stock de los productos vendidos (cantidad de cada producto del paquete multiplicada por la cantidad de paquetes). Si algún producto no tiene stock suficiente, no se descuenta nada y se retorna error.
         /// Se agrega la orden de compra a mi sistema.
         /// Por último se reinserta el usuario debido a que su estado interno ha sido modificado en el transcurso de la función. Retornando un Ok, indicando el éxito de la operación. 
-        pub fn crear_orden_de_compra(&mut self, id_publicacion: u32) -> Result<(), String>{
+        pub fn crear_orden_de_compra(&mut self, id_publicacion: u32, cantidad: u32) -> Result<(), Error>{
             let account_id = self.env().caller();
-            self.priv_crear_orden_de_compra(account_id, id_publicacion)
+            self.priv_crear_orden_de_compra(account_id, id_publicacion, cantidad)
         }
-        fn priv_crear_orden_de_compra(&mut self, account_id: AccountId, id_publicacion: u32) -> Result<(), String>{
+        fn priv_crear_orden_de_compra(&mut self, account_id: AccountId, id_publicacion: u32, cantidad: u32) -> Result<(), Error>{
             if let Some(mut usuario) = self.usuarios.get(account_id){
+                if cantidad == 0 {
+                    return Err(Error::CantidadInvalida)
+                }
                 let publicacion = self.visualizar_productos_de_publicacion(id_publicacion)?;
                 let vendedor_de_la_orden = self.usuarios.get(publicacion.id_vendedor).unwrap();
                 if account_id == vendedor_de_la_orden.id_usuario{
-                    return Err("El usuario no puede comprar sus propias publicaciones.".to_string())
+                    return Err(Error::CompraDePublicacionPropia)
                 }
                 if vendedor_de_la_orden.rol == Rol::Comp {
-                    return Err("La publicacion ya no se encuentra disponible.".to_string())
+                    return Err(Error::PublicacionNoDisponible)
                 }
                 let id_orden = self.historial_ordenes_de_compra.len();
-                let orden_de_compra = usuario.crear_orden_de_compra(id_orden, publicacion.clone(), account_id)?;
+                let orden_de_compra = usuario.crear_orden_de_compra(id_orden, publicacion, account_id, cantidad)?;
+                self.descontar_stock(orden_de_compra.info_publicacion.1.clone())?;
                 self.historial_ordenes_de_compra.push(&(id_orden, orden_de_compra));
                 self.usuarios.insert(account_id, &usuario);
                 Ok(())  
             }
             else {
-                Err("No existe el usuario.".to_string())
+                Err(Error::UsuarioInexistente)
             }
         }
         
@@ -189,17 +193,17 @@ mod primer_contrato {
         /// Llama a la funcion de Usuario.
         /// Sobreescribe la orden en el sistema.
         #[ink(message)]
-        pub fn enviar_compra(&mut self, id_orden: u32) -> Result<(), String>{
+        pub fn enviar_compra(&mut self, id_orden: u32) -> Result<(), Error>{
             let account_id = self.env().caller();
             self.priv_enviar_compra(account_id, id_orden)
         }
-        fn priv_enviar_compra(&mut self, account_id: AccountId, id_orden: u32) -> Result<(), String>{
+        fn priv_enviar_compra(&mut self, account_id: AccountId, id_orden: u32) -> Result<(), Error>{
             if let Some(usuario) = self.usuarios.get(account_id){
                 for i in 0..self.historial_ordenes_de_compra.len() {
                     if let Some((id, mut orden_de_compra)) = self.historial_ordenes_de_compra.get(i){
                         if id == id_orden {
                             if orden_de_compra.estado != EstadoCompra::Pendiente{
-                                return Err("El producto no puede ser enviado.".to_string());
+                                return Err(Error::EnvioNoPermitido);
                             }
                             let id_publicacion = orden_de_compra.info_publicacion.0;
                             usuario.enviar_compra(id_publicacion)?;
@@ -209,10 +213,10 @@ mod primer_contrato {
                         }
                     }
                 }
-                Err("No existe la orden buscada.".to_string())
+                Err(Error::OrdenInexistente)
             }
             else {
-                Err("No existe el usuario.".to_string())
+                Err(Error::UsuarioInexistente)
             }
         }
         
@@ -223,17 +227,17 @@ mod primer_contrato {
         /// Llama a la funcion de Usuario.
         /// Sobreescribe la orden en el sistema. 
         #[ink(message)]
-        pub fn recibir_compra(&mut self, id_orden: u32) -> Result<(), String>{
+        pub fn recibir_compra(&mut self, id_orden: u32) -> Result<(), Error>{
             let account_id = self.env().caller();
             self.priv_recibir_compra(account_id, id_orden)
         }
-        fn priv_recibir_compra(&mut self, account_id: AccountId, id_orden: u32) -> Result<(), String>{ 
+        fn priv_recibir_compra(&mut self, account_id: AccountId, id_orden: u32) -> Result<(), Error>{ 
             if let Some(usuario) = self.usuarios.get(account_id){
                 for i in 0..self.historial_ordenes_de_compra.len() {
                     if let Some((id, mut orden_de_compra)) = self.historial_ordenes_de_compra.get(i){
                         if id == id_orden {
                             if orden_de_compra.estado != EstadoCompra::Enviado{
-                                return Err("El producto todavia no fue enviado.".to_string());
+                                return Err(Error::CompraNoEnviada);
                             }
                             usuario.recibir_compra(id_orden)?;
                             orden_de_compra.estado = EstadoCompra::Recibido;
@@ -242,24 +246,80 @@ mod primer_contrato {
                         }
                     }
                 }
-                Err("No existe la orden buscada.".to_string())
+                Err(Error::OrdenInexistente)
             }
             else {
-                Err("No existe el usuario.".to_string())
+                Err(Error::UsuarioInexistente)
+            }
+        }
+
+        /// Funcion para que el vendedor establezca un descuento (en porcentaje) sobre el precio del paquete de una publicación.
+        /// Revisa que el usuario este cargado en sistema.
+        /// Revisa que el descuento este entre 0 y 99. Un descuento de 0 quita el descuento vigente.
+        /// Revisa que la publicación exista y que pertenezca al vendedor.
+        /// Sobreescribe la publicación en el sistema.
+        #[ink(message)]
+        pub fn establecer_descuento_publicacion(&mut self, id_publicacion: u32, descuento: u8) -> Result<(), Error>{
+            let account_id = self.env().caller();
+            self.priv_establecer_descuento_publicacion(account_id, id_publicacion, descuento)
+        }
+        fn priv_establecer_descuento_publicacion(&mut self, account_id: AccountId, id_publicacion: u32, descuento: u8) -> Result<(), Error>{
+            if self.usuarios.get(account_id).is_none(){
+                return Err(Error::UsuarioInexistente)
+            }
+            if descuento > 99 {
+                return Err(Error::DescuentoInvalido)
+            }
+            for i in 0..self.historial_publicaciones.len() {
+                if let Some((id, mut publicacion)) = self.historial_publicaciones.get(i) {
+                    if id == id_publicacion {
+                        if publicacion.id_vendedor != account_id {
+                            return Err(Error::PublicacionDeOtroVendedor)
+                        }
+                        publicacion.descuento = descuento;
+                        let _ = self.historial_publicaciones.set(i, &(id, publicacion));
+                        return Ok(())
+                    }
+                }
+            }
+            Err(Error::PublicacionInexistente)
+        }
+
+        /// Funcion que devuelve cuantos paquetes de una publicación se pueden comprar.
+        /// La disponibilidad la determina el producto más escaso: para cada producto se divide su stock por la cantidad que lleva el paquete y se toma el mínimo.
+        #[ink(message)]
+        pub fn paquetes_disponibles(&self, id_publicacion: u32) -> Result<u32, Error>{
+            let publicacion = self.priv_visualizar_productos_de_publicacion(id_publicacion)?;
+            Ok(self.calcular_paquetes_disponibles(publicacion.productos))
+        }
+
+        // Fn calcular_paquetes_disponibles recibe los productos de un paquete (id, cantidad)
+        // devuelve el minimo entre stock / cantidad de cada producto
+        // si algun producto no esta cargado en el sistema no hay paquetes disponibles
+        fn calcular_paquetes_disponibles(&self, productos_paquete: Vec<(u32, u32)>) -> u32{
+            let mut disponibles = u32::MAX;
+            for (id, cantidad) in productos_paquete{
+                match self.historial_productos.get(id){
+                    Some((_producto, stock)) => {
+                        disponibles = disponibles.min(stock.checked_div(cantidad).unwrap_or(0));
+                    }
+                    None => return 0,
+                }
             }
+            if disponibles == u32::MAX { 0 } else { disponibles }
         }
 
         //  Fn calcular_precio final  va a recibir los productos de la publicacion y va a prodecer a realizar 
         //  la suma de los valores de los productos
         //  esto retorna el valor total 
         //  y se chequea que no ocurra overflow como caso de error  
-        fn calcular_precio_final(&self, productos_publicados: Vec<(u32, u32)>) -> Result<u32, String>{
+        fn calcular_precio_final(&self, productos_publicados: Vec<(u32, u32)>) -> Result<u32, Error>{
             let mut total: u32 = 0;
             for (id, cantidad) in productos_publicados{
                 if let Some((producto, _stock)) = self.historial_productos.get(id){
                     total = total.checked_add(producto.precio.checked_mul(cantidad)
-                    .ok_or("Overflow al multiplicar precio por cantidad.")?)
-                    .ok_or("Overflow al acumular el total.")?;
+                    .ok_or(Error::OverflowAlMultiplicar)?)
+                    .ok_or(Error::OverflowAlAcumular)?;
                 }
             }
             Ok(total)
@@ -271,31 +331,41 @@ mod primer_contrato {
         // errores 
         // se encuentra el producto si hay stock continua, pero si no hay stock suficiente y devuelve error 
         // el producto puede no estas cargado en el sistema historial de productos 
-        fn hay_stock_suficiente(&self, productos_cantidades: Vec<(u32, u32)>) -> Result<(), String>{
+        fn hay_stock_suficiente(&self, productos_cantidades: Vec<(u32, u32)>) -> Result<(), Error>{
             for (id, cantidad) in productos_cantidades{
                 if let Some((_producto, stock)) = self.historial_productos.get(id){
                     if (stock < cantidad) | (stock == 0){
-                        return Err("No hay stock suficiente.".to_string())
+                        return Err(Error::StockInsuficiente)
                     }
                 }
                 else{
-                    return Err("No se encontro el producto.".to_string())
+                    return Err(Error::ProductoInexistente)
                 }
             }
             Ok(())
         }
         
-        /// Funcion para descontar el stock en sistema de un cierto producto. Llamada dentro de la funcion "crear_orden_de_compra"
-        /// Revisa que el producto este cargado en sistema.
-        /// Resta el stock. Siempre habra stock minimo suficiente ya que se revisara con la funcion "hay_stock_suficiente".
-        /// Sobreescribe el producto con su nuevo stock.
-        fn descontar_stock(&mut self, productos_cantidades: Vec<(u32, u32)>) -> Result<(), String>{
+        /// Funcion para descontar el stock en sistema de los productos de una orden. Llamada dentro de la funcion "crear_orden_de_compra"
+        /// Primero calcula el nuevo stock de todos los productos sin escribir nada. Si un producto no esta cargado o no tiene stock suficiente, retorna error y el stock queda intacto.
+        /// Si un mismo producto aparece mas de una vez, las cantidades se acumulan.
+        /// Recien cuando todos los productos fueron validados, sobreescribe cada producto con su nuevo stock.
+        fn descontar_stock(&mut self, productos_cantidades: Vec<(u32, u32)>) -> Result<(), Error>{
+            let mut nuevos_stocks: Vec<(u32, Producto, u32)> = Vec::new();
             for (id, cantidad) in productos_cantidades{
-                if let Some ((producto, mut stock)) = self.historial_productos.get(id){
-                    stock = stock.checked_sub(cantidad).ok_or("Error al restar stock")?;
-                    self.historial_productos.insert(id, &(producto, stock));
+                if let Some(pendiente) = nuevos_stocks.iter_mut().find(|(id_pendiente, _, _)| *id_pendiente == id){
+                    pendiente.2 = pendiente.2.checked_sub(cantidad).ok_or(Error::StockInsuficiente)?;
+                }
+                else if let Some((producto, stock)) = self.historial_productos.get(id){
+                    let stock = stock.checked_sub(cantidad).ok_or(Error::StockInsuficiente)?;
+                    nuevos_stocks.push((id, producto, stock));
+                }
+                else {
+                    return Err(Error::ProductoInexistente)
                 }
             }
+            for (id, producto, stock) in nuevos_stocks{
+                self.historial_productos.insert(id, &(producto, stock));
+            }
             Ok(())
         }
     }
@@ -366,9 +436,9 @@ mod primer_contrato {
         /// Funcion que permite crear una publicacion. Llamada por sistema.
         /// Revisa que el usuario tenga el rol Vend o Ambos
         /// Llama a la funcion de Vendedor
-        fn crear_publicacion(&mut self, productos_a_publicar: Vec<(u32, u32)>, precio_final: u32, id_publicacion: u32, id_vendedor: AccountId) -> Result<Publicacion, String>{  //productos_a_publicar = Vec<(id, cantidad)>
+        fn crear_publicacion(&mut self, productos_a_publicar: Vec<(u32, u32)>, precio_final: u32, id_publicacion: u32, id_vendedor: AccountId) -> Result<Publicacion, Error>{  //productos_a_publicar = Vec<(id, cantidad)>
             if self.rol == Rol::Comp {
-                Err("El usuario no es vendedor.".to_string())
+                Err(Error::NoEsVendedor)
             }
             else {
                 Ok(self.datos_vendedor.as_mut().expect("Error con los datos del vendedor.").crear_publicacion(productos_a_publicar, precio_final, id_publicacion, id_vendedor))
@@ -379,9 +449,9 @@ mod primer_contrato {
         /// Funcion que permite modificar el rol de un usuario. Llamada por sistema.
         /// Revisa que el rol a cambiar sea valido
         /// Si habia datos previos (en caso de haber cambiado de rol anteriormente) los usa, sino inicializa los campos
-        fn modificar_rol(&mut self, nuevo_rol: Rol) -> Result<(), String>{
+        fn modificar_rol(&mut self, nuevo_rol: Rol) -> Result<(), Error>{
             if nuevo_rol == self.rol {
-                Err("El usuario ya posee ese rol.".to_string())
+                Err(Error::RolYaAsignado)
             }
             else {
                 self.rol = nuevo_rol.clone();
@@ -439,21 +509,21 @@ mod primer_contrato {
         /// Funcion para crear una orden de compra. Llamada por sistema.
         /// Revisa que el usuario tenga el rol de Comp o Ambos.
         /// Llama a la funcion de Comprador
-        fn crear_orden_de_compra(&mut self, id_orden: u32, publicacion: Publicacion, id_comprador: AccountId) -> Result<OrdenCompra, String>{
+        fn crear_orden_de_compra(&mut self, id_orden: u32, publicacion: Publicacion, id_comprador: AccountId, cantidad: u32) -> Result<OrdenCompra, Error>{
             if self.rol == Rol::Vend{
-                Err("El usuario no esta autorizado para realizar una compra. ERROR: No posee el rol comprador.".to_string())
+                Err(Error::CompraNoAutorizada)
             }
             else{
-                Ok(self.datos_comprador.as_mut().expect("No hay datos del comprador.").crear_orden_de_compra(id_orden, publicacion, id_comprador))
+                self.datos_comprador.as_mut().expect("No hay datos del comprador.").crear_orden_de_compra(id_orden, publicacion, id_comprador, cantidad)
             }
         }
 
         /// Funcion para enviar una compra. Llamada por sistema.
         /// Revisa que el usuario tenga el rol Vend o Ambos.
         /// Llama a la funcion de Vendedor.
-        fn enviar_compra(&self, id_publicacion: u32) -> Result<(), String>{
+        fn enviar_compra(&self, id_publicacion: u32) -> Result<(), Error>{
             if self.rol == Rol::Comp{
-                Err("El usuario no posee el rol de vendedor.".to_string())
+                Err(Error::NoPoseeRolVendedor)
             }
             else{
                 self.datos_vendedor.as_ref().expect("No hay datos del vendedor.").enviar_compra(id_publicacion)
@@ -463,9 +533,9 @@ mod primer_contrato {
         /// Funcion para recibir una compra. Llamada por sistema.
         /// Revisa que el usuario tenga el rol Comp o Ambos.
         /// Llama a la funcion de Comprador.
-        fn recibir_compra(&self, id_orden: u32) -> Result<(), String>{
+        fn recibir_compra(&self, id_orden: u32) -> Result<(), Error>{
             if self.rol == Rol::Vend{
-                Err("El usuario no posee el rol de comprador.".to_string())
+                Err(Error::NoPoseeRolComprador)
             }
             else{
                 self.datos_comprador.as_ref().expect("No hay datos del comprador.").recibir_compra(id_orden)
@@ -488,23 +558,24 @@ mod primer_contrato {
     }
     impl Comprador{
         /// Esta fn crear orden compra es llamada por usuario y recibe estos datos:
-        ///     Id de la orden, informarcion de la publicacion, y ademas el id del comprador 
-        /// Se guarda el id de la orden
-        /// Genera la orden de comrpra y la devuelve al sistema 
-        fn crear_orden_de_compra(&mut self, id_orden: u32, publicacion: Publicacion, id_comprador: AccountId) -> OrdenCompra{
+        ///     Id de la orden, informarcion de la publicacion, el id del comprador y la cantidad de paquetes
+        /// Genera la orden de comrpra
+        /// Se guarda el id de la orden y la devuelve al sistema 
+        fn crear_orden_de_compra(&mut self, id_orden: u32, publicacion: Publicacion, id_comprador: AccountId, cantidad: u32) -> Result<OrdenCompra, Error>{
+            let orden = OrdenCompra::crear_orden_de_compra(id_orden, publicacion, id_comprador, cantidad)?;
             self.ordenes_de_compra.push(id_orden);
-            OrdenCompra::crear_orden_de_compra(id_orden, publicacion, id_comprador)
+            Ok(orden)
         }
 
         /// esta fn recibir_compra es llamada por usuario y recibe estos datos:=
         /// compra indica si el id de la orden esta en el vector de ordenes de compra perteneciente al comprador 
         /// en caso de no estar  genera el error
-        fn recibir_compra(&self, id_orden: u32) -> Result<(), String>{
+        fn recibir_compra(&self, id_orden: u32) -> Result<(), Error>{
             if self.ordenes_de_compra.contains(&id_orden){
                 Ok(())
             }
             else {
-                Err("No se encontro la orden de compra.".to_string())
+                Err(Error::OrdenNoEncontradaEnComprador)
             }
         }
 
@@ -540,12 +611,12 @@ mod primer_contrato {
         // Esta fn enviar_compra es llamada por usuario recibe como dato  el id de la publicacion 
         // si el id se encuentra en  las publicaciones del vendenderor devuelve ok // exito 
         // en caso contrario  la pubiclacion no seria  de ese vendedor 
-        fn enviar_compra(&self, id_publicacion: u32) -> Result<(), String>{
+        fn enviar_compra(&self, id_publicacion: u32) -> Result<(), Error>{
             if self.publicaciones.contains(&id_publicacion){
                 Ok(())
             }
             else {
-                Err("La publicacion buscada no pertenece a este vendedor.".to_string())
+                Err(Error::PublicacionDeOtroVendedor)
             }
         }
 
@@ -581,11 +652,13 @@ mod primer_contrato {
     /// productos, es un Vec que contiene tuplas cuyos campos son, el id de cada producto publicado y la cantidad de unidades publicadas de ese mismo producto. (id producto, cantidad del producto)
     /// precio_final, es la suma de, el precio de cada producto multiplicado por la cantidad de unidades del mismo. 
     /// id_vendedor, es el id del vendedor que realizó la publicación. 
+    /// descuento, es el porcentaje (0 a 99) que el vendedor descuenta sobre el precio_final de cada paquete. 
     pub struct Publicacion{
         id: u32,
         productos: Vec<(u32, u32)>,
         precio_final: u32,
-        id_vendedor:AccountId
+        id_vendedor:AccountId,
+        descuento: u8,
     }
 
     /// Funcion para crear y devolver una publicacion. Llamada por Vendedor.
@@ -597,7 +670,24 @@ mod primer_contrato {
                 productos: productos_a_publicar,
                 precio_final,
                 id_vendedor,
+                descuento: 0,
+            }
+        }
+
+        /// Funcion que devuelve el precio de un paquete con el descuento aplicado.
+        /// El calculo se hace en u64 para que no haya overflow, el resultado nunca supera a precio_final.
+        fn precio_con_descuento(&self) -> u32{
+            let precio = u64::from(self.precio_final) * u64::from(100_u8.saturating_sub(self.descuento)) / 100;
+            u32::try_from(precio).unwrap_or(self.precio_final)
+        }
+
+        /// Funcion que devuelve los productos necesarios para "cantidad" paquetes. (id producto, cantidad del producto * cantidad de paquetes)
+        fn productos_por_cantidad(&self, cantidad: u32) -> Result<Vec<(u32, u32)>, Error>{
+            let mut productos = Vec::new();
+            for (id, cantidad_producto) in self.productos.iter(){
+                productos.push((*id, cantidad_producto.checked_mul(cantidad).ok_or(Error::OverflowAlMultiplicar)?));
             }
+            Ok(productos)
         }
     }
 
@@ -646,6 +736,8 @@ mod primer_contrato {
     /// estado, almacena el estado de la compra. Éste puede ser: Pendiente, Enviado, Recibido, Cancelada. 
     /// cancelacion, es una tupla que almacena el pedido de cancelación, tanto del vendedor como del comprador. (vendedor, comprador)
     /// info_publicacion, tupla que almacena los datos de la publicación. (ID de la publicacion, Vec<(IDs de los productos, cantidades de ese producto)>, precio final de la publicacion, ID del Vendedor).
+    /// Las cantidades de info_publicacion y el precio final ya estan multiplicados por la cantidad de paquetes comprados, y el precio tiene aplicado el descuento.
+    /// cantidad, almacena la cantidad de paquetes de la publicación que se compraron. 
     /// id_comprador, almacena el id del comprador de la orden de compra. 
     /// calificaciones, es una tupla que indica si el vendor y/o comprador realizó la calificación a su contraparte. (vendedor, comprador)
     struct OrdenCompra{
@@ -653,6 +745,7 @@ mod primer_contrato {
         estado: EstadoCompra,
         cancelacion: (bool, bool), 
         info_publicacion: (u32, Vec<(u32, u32)>, u32, AccountId),
+        cantidad: u32,
         id_comprador:AccountId,
         calificaciones: (bool, bool),
     }
@@ -660,22 +753,24 @@ mod primer_contrato {
         
         /// Funcion para crear y devolver una orden de compra. Llamada por Comprador.
         /// Crea una orden con los datos pasados por parametros, tanto "cancelacion" como "calificaciones" se inicializan con los dos campos en false.
-        fn crear_orden_de_compra(id_orden: u32, publicacion: Publicacion, id_comprador: AccountId) -> OrdenCompra{
+        /// Las cantidades de los productos y el precio (con descuento) se multiplican por la cantidad de paquetes. Si hay overflow se retorna error.
+        fn crear_orden_de_compra(id_orden: u32, publicacion: Publicacion, id_comprador: AccountId, cantidad: u32) -> Result<OrdenCompra, Error>{
             let id_publicacion = publicacion.id;
-            let productos = publicacion.productos;
-            let precio_final = publicacion.precio_final;
+            let productos = publicacion.productos_por_cantidad(cantidad)?;
+            let precio_final = publicacion.precio_con_descuento().checked_mul(cantidad).ok_or(Error::OverflowAlMultiplicar)?;
             let id_vendedor = publicacion.id_vendedor;
             let info_publicacion = (id_publicacion, productos, precio_final, id_vendedor);
             let calificaciones = (false, false);
 
-            OrdenCompra{
+            Ok(OrdenCompra{
                 id: id_orden,
                 estado: EstadoCompra::Pendiente,
                 cancelacion: (false, false),
                 info_publicacion,
+                cantidad,
                 id_comprador, 
                 calificaciones,
-            }
+            })
         }
     }
 
@@ -698,6 +793,72 @@ mod primer_contrato {
         Cancelada,
     }
 
+/////////////////////////// ERROR ///////////////////////////
+
+    #[derive(Debug, Clone, PartialEq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    /// Enum con los errores que pueden devolver los mensajes del contrato.
+    /// Se codifica con scale, por lo que los clientes pueden distinguir cada caso por su variante.
+    /// El texto de cada error se obtiene con Display (to_string).
+    pub enum Error {
+        UsuarioYaRegistrado,
+        OverflowIdProducto,
+        SinPermisoParaCargarProductos,
+        UsuarioInexistente,
+        CantidadInvalida,
+        PublicacionInexistente,
+        CompraDePublicacionPropia,
+        PublicacionNoDisponible,
+        EnvioNoPermitido,
+        OrdenInexistente,
+        CompraNoEnviada,
+        OverflowAlMultiplicar,
+        OverflowAlAcumular,
+        StockInsuficiente,
+        ProductoInexistente,
+        FalloAlRestarStock,
+        NoEsVendedor,
+        RolYaAsignado,
+        CompraNoAutorizada,
+        NoPoseeRolVendedor,
+        NoPoseeRolComprador,
+        OrdenNoEncontradaEnComprador,
+        PublicacionDeOtroVendedor,
+        DescuentoInvalido,
+    }
+
+    impl core::fmt::Display for Error {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            let mensaje = match self {
+                Error::UsuarioYaRegistrado => "El usuario ya esta registrado.",
+                Error::OverflowIdProducto => "Error al sumar.",
+                Error::SinPermisoParaCargarProductos => "El usuario no tiene permisos para cargar productos. No es vendedor.",
+                Error::UsuarioInexistente => "No existe el usuario.",
+                Error::CantidadInvalida => "Un producto tiene cantidades no validas.",
+                Error::PublicacionInexistente => "No se encontro la publicacion.",
+                Error::CompraDePublicacionPropia => "El usuario no puede comprar sus propias publicaciones.",
+                Error::PublicacionNoDisponible => "La publicacion ya no se encuentra disponible.",
+                Error::EnvioNoPermitido => "El producto no puede ser enviado.",
+                Error::OrdenInexistente => "No existe la orden buscada.",
+                Error::CompraNoEnviada => "El producto todavia no fue enviado.",
+                Error::OverflowAlMultiplicar => "Overflow al multiplicar precio por cantidad.",
+                Error::OverflowAlAcumular => "Overflow al acumular el total.",
+                Error::StockInsuficiente => "No hay stock suficiente.",
+                Error::ProductoInexistente => "No se encontro el producto.",
+                Error::FalloAlRestarStock => "Error al restar stock",
+                Error::NoEsVendedor => "El usuario no es vendedor.",
+                Error::RolYaAsignado => "El usuario ya posee ese rol.",
+                Error::CompraNoAutorizada => "El usuario no esta autorizado para realizar una compra. ERROR: No posee el rol comprador.",
+                Error::NoPoseeRolVendedor => "El usuario no posee el rol de vendedor.",
+                Error::NoPoseeRolComprador => "El usuario no posee el rol de comprador.",
+                Error::OrdenNoEncontradaEnComprador => "No se encontro la orden de compra.",
+                Error::PublicacionDeOtroVendedor => "La publicacion buscada no pertenece a este vendedor.",
+                Error::DescuentoInvalido => "El descuento debe estar entre 0 y 99.",
+            };
+            f.write_str(mensaje)
+        }
+    }
+
 /////////////////////////// TESTS ///////////////////////////
     #[cfg(test)]
     mod tests {
@@ -797,7 +958,7 @@ mod primer_contrato {
             );
 
             assert!(resultado.is_err(), "El sistema debería rechazar registros duplicados");
-            assert_eq!(resultado.unwrap_err(), "El usuario ya esta registrado.");
+            assert_eq!(resultado.unwrap_err(), Error::UsuarioYaRegistrado);
         }
 
         #[ink::test]
@@ -883,7 +1044,7 @@ mod primer_contrato {
             assert!(result.is_err(), "Un comprador no debería poder cargar productos");
             assert_eq!(
                 result.unwrap_err(),
-                "El usuario no tiene permisos para cargar productos. No es vendedor."
+                Error::SinPermisoParaCargarProductos
             );
         }
 
@@ -903,7 +1064,7 @@ mod primer_contrato {
             );
 
             assert!(result.is_err(), "Usuario no registrado no debería poder cargar producto");
-            assert_eq!(result.unwrap_err(), "No existe el usuario.");
+            assert_eq!(result.unwrap_err(), Error::UsuarioInexistente);
         }
 
         #[ink::test]
@@ -999,7 +1160,7 @@ mod primer_contrato {
 
             let resultado = contrato.crear_publicacion(vec![(1, 1)]);
             assert!(resultado.is_err(), "Comprador no debería poder publicar");
-            assert_eq!(resultado.unwrap_err(), "El usuario no es vendedor.");
+            assert_eq!(resultado.unwrap_err(), Error::NoEsVendedor);
         }
 
         #[ink::test]
@@ -1010,7 +1171,7 @@ mod primer_contrato {
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
             let resultado = contrato.crear_publicacion(vec![(1, 1)]);
             assert!(resultado.is_err(), "Usuario no registrado no debería poder publicar");
-            assert_eq!(resultado.unwrap_err(), "No existe el usuario.");
+            assert_eq!(resultado.unwrap_err(), Error::UsuarioInexistente);
         }
 
         #[ink::test]
@@ -1036,7 +1197,7 @@ mod primer_contrato {
             assert!(resultado.is_err(), "Debería fallar por producto inexistente");
 
             let err = resultado.unwrap_err();
-            assert_eq!(err, "No se encontro el producto.");
+            assert_eq!(err, Error::ProductoInexistente);
         }
         #[ink::test]
         fn test_visualizar_publicacion_existente() {
@@ -1085,7 +1246,7 @@ mod primer_contrato {
 
             assert_eq!(
                 resultado.unwrap_err(),
-                "No se encontro la publicacion.".to_string()
+                Error::PublicacionInexistente
             );
         }
         #[ink::test]
@@ -1153,7 +1314,7 @@ mod primer_contrato {
 
             let result = contrato.modificar_rol(Rol::Comp);
             assert!(result.is_err(), "No debería poder cambiar al mismo rol");
-            assert_eq!(result.unwrap_err(), "El usuario ya posee ese rol.");
+            assert_eq!(result.unwrap_err(), Error::RolYaAsignado);
         }
 
         #[ink::test]
@@ -1165,7 +1326,7 @@ mod primer_contrato {
             let result = contrato.modificar_rol(Rol::Vend);
 
             assert!(result.is_err(), "Usuario no registrado no debería poder cambiar rol");
-            assert_eq!(result.unwrap_err(), "No existe el usuario.");
+            assert_eq!(result.unwrap_err(), Error::UsuarioInexistente);
         }
 
         #[ink::test]
@@ -1182,7 +1343,7 @@ mod primer_contrato {
             // Comprador compra
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             contrato.agregar_usuario_sistema("Comprador".into(), "B".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
-            let result = contrato.crear_orden_de_compra(0);
+            let result = contrato.crear_orden_de_compra(0, 1);
 
             assert!(result.is_ok(), "El comprador debería poder crear orden de compra");
 
@@ -1208,7 +1369,7 @@ mod primer_contrato {
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
             contrato.agregar_usuario_sistema("Charlie".into(), "C".into(), "Dir".into(), "charlie@mail.com".into(), Rol::Ambos).unwrap();
 
-            let result = contrato.crear_orden_de_compra(0);
+            let result = contrato.crear_orden_de_compra(0, 1);
             assert!(result.is_ok(), "Usuario con rol Ambos debería poder comprar");
         }
 
@@ -1225,11 +1386,12 @@ mod primer_contrato {
 
             // Intenta comprar con el mismo vendedor
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
-            let result = contrato.crear_orden_de_compra(0);
+            let result = contrato.crear_orden_de_compra(0, 1);
 
             assert!(result.is_err(), "Un vendedor no puede comprar");
             let mensaje = result.unwrap_err();
-            assert_eq!(mensaje, "El usuario no puede comprar sus propias publicaciones.");
+            assert_eq!(mensaje, Error::CompraDePublicacionPropia);
+            assert_eq!(mensaje.to_string(), "El usuario no puede comprar sus propias publicaciones.");
         }
 
         #[ink::test]
@@ -1245,10 +1407,10 @@ mod primer_contrato {
 
             // Usuario no registrado
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
-            let result = contrato.crear_orden_de_compra(0);
+            let result = contrato.crear_orden_de_compra(0, 1);
 
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "No existe el usuario.");
+            assert_eq!(result.unwrap_err(), Error::UsuarioInexistente);
         }
 
         #[ink::test]
@@ -1264,7 +1426,7 @@ mod primer_contrato {
             // Intentar crear publicación con más cantidad que stock disponible
             let resultado_pub = contrato.crear_publicacion(vec![(1, 2)]);
             assert!(resultado_pub.is_err(), "No debería poder crear publicación con cantidad mayor al stock");
-            assert_eq!(resultado_pub.unwrap_err(), "No hay stock suficiente.");
+            assert_eq!(resultado_pub.unwrap_err(), Error::StockInsuficiente);
         }
 
         #[ink::test]
@@ -1276,9 +1438,77 @@ mod primer_contrato {
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             contrato.agregar_usuario_sistema("Comprador".into(), "B".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
 
-            let result = contrato.crear_orden_de_compra(999); // ID inexistente
+            let result = contrato.crear_orden_de_compra(999, 1); // ID inexistente
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "No se encontro la publicacion.");
+            assert_eq!(result.unwrap_err(), Error::PublicacionInexistente);
+        }
+
+        fn contrato_con_paquete() -> PrimerContrato {
+            let accounts = default_accounts();
+            let mut contrato = PrimerContrato::default();
+
+            // Vendedor publica un paquete con 2 unidades de P1 y 1 de P2 (precio 2*100 + 50 = 250)
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contrato.agregar_usuario_sistema("Vendedor".into(), "A".into(), "Dir".into(), "v@mail.com".into(), Rol::Vend).unwrap();
+            contrato.cargar_producto("P1".into(), "Desc".into(), 100, "cat".into(), 10).unwrap();
+            contrato.cargar_producto("P2".into(), "Desc".into(), 50, "cat".into(), 4).unwrap();
+            contrato.crear_publicacion(vec![(1, 2), (2, 1)]).unwrap();
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contrato.agregar_usuario_sistema("Comprador".into(), "B".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
+            contrato
+        }
+
+        #[ink::test]
+        fn test_crear_orden_de_varios_paquetes_con_descuento() {
+            let accounts = default_accounts();
+            let mut contrato = contrato_con_paquete();
+
+            // El producto más escaso (P2: 4 unidades / 1 por paquete) define la disponibilidad
+            assert_eq!(contrato.paquetes_disponibles(0), Ok(4));
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contrato.establecer_descuento_publicacion(0, 20), Ok(()));
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contrato.crear_orden_de_compra(0, 3), Ok(()));
+
+            let orden = contrato.historial_ordenes_de_compra.get(0).expect("No se creó la orden");
+            assert_eq!(orden.1.cantidad, 3);
+            assert_eq!(orden.1.info_publicacion.1, vec![(1, 6), (2, 3)]);
+            assert_eq!(orden.1.info_publicacion.2, 600); // 250 con 20% de descuento = 200, por 3 paquetes
+
+            assert_eq!(contrato.historial_productos.get(1).unwrap().1, 4);
+            assert_eq!(contrato.historial_productos.get(2).unwrap().1, 1);
+            assert_eq!(contrato.paquetes_disponibles(0), Ok(1));
+        }
+
+        #[ink::test]
+        fn test_crear_orden_sin_stock_de_un_producto_no_descuenta_ninguno() {
+            let mut contrato = contrato_con_paquete();
+
+            // P1 alcanza para 5 paquetes pero P2 solo para 4
+            let result = contrato.crear_orden_de_compra(0, 5);
+            assert_eq!(result, Err(Error::StockInsuficiente));
+            assert_eq!(contrato.crear_orden_de_compra(0, 0), Err(Error::CantidadInvalida));
+
+            assert_eq!(contrato.historial_productos.get(1).unwrap().1, 10);
+            assert_eq!(contrato.historial_productos.get(2).unwrap().1, 4);
+            assert_eq!(contrato.historial_ordenes_de_compra.len(), 0);
+        }
+
+        #[ink::test]
+        fn test_establecer_descuento_publicacion_falla() {
+            let accounts = default_accounts();
+            let mut contrato = contrato_con_paquete();
+
+            // Bob no es el dueño de la publicación
+            assert_eq!(contrato.establecer_descuento_publicacion(0, 10), Err(Error::PublicacionDeOtroVendedor));
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contrato.establecer_descuento_publicacion(0, 100), Err(Error::DescuentoInvalido));
+            assert_eq!(contrato.establecer_descuento_publicacion(999, 10), Err(Error::PublicacionInexistente));
+            assert_eq!(contrato.paquetes_disponibles(999), Err(Error::PublicacionInexistente));
         }
 
         #[ink::test]
@@ -1295,7 +1525,7 @@ mod primer_contrato {
             // Comprador crea orden
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             contrato.agregar_usuario_sistema("C".into(), "Y".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
-            contrato.crear_orden_de_compra(0).unwrap();
+            contrato.crear_orden_de_compra(0, 1).unwrap();
 
             // Vendedor envía
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
@@ -1319,11 +1549,11 @@ mod primer_contrato {
 
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             contrato.agregar_usuario_sistema("C".into(), "Y".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
-            contrato.crear_orden_de_compra(0).unwrap();
+            contrato.crear_orden_de_compra(0, 1).unwrap();
 
             // Comprador intenta enviar
             let result = contrato.enviar_compra(0);
-            assert_eq!(result.unwrap_err(), "El usuario no posee el rol de vendedor.");
+            assert_eq!(result.unwrap_err(), Error::NoPoseeRolVendedor);
         }
 
         #[ink::test]
@@ -1340,13 +1570,13 @@ mod primer_contrato {
             // Comprador crea orden
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
             contrato.agregar_usuario_sistema("C".into(), "Y".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
-            contrato.crear_orden_de_compra(0).unwrap();
+            contrato.crear_orden_de_compra(0, 1).unwrap();
 
             // Vendedor 2 intenta enviar
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             contrato.agregar_usuario_sistema("V2".into(), "Z".into(), "Dir".into(), "v2@mail.com".into(), Rol::Vend).unwrap();
             let result = contrato.enviar_compra(0);
-            assert_eq!(result.unwrap_err(), "La publicacion buscada no pertenece a este vendedor.");
+            assert_eq!(result.unwrap_err(), Error::PublicacionDeOtroVendedor);
         }
 
         #[ink::test]
@@ -1358,7 +1588,7 @@ mod primer_contrato {
             contrato.agregar_usuario_sistema("V".into(), "X".into(), "Dir".into(), "v@mail.com".into(), Rol::Vend).unwrap();
 
             let result = contrato.enviar_compra(99);
-            assert_eq!(result.unwrap_err(), "No existe la orden buscada.");
+            assert_eq!(result.unwrap_err(), Error::OrdenInexistente);
         }
 
         #[ink::test]
@@ -1367,7 +1597,7 @@ mod primer_contrato {
 
             // Nadie registrado
             let result = contrato.enviar_compra(0);
-            assert_eq!(result.unwrap_err(), "No existe el usuario.");
+            assert_eq!(result.unwrap_err(), Error::UsuarioInexistente);
         }
 
         #[ink::test]
@@ -1384,7 +1614,7 @@ mod primer_contrato {
             // Comprador crea orden
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             contrato.agregar_usuario_sistema("C".into(), "Y".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
-            contrato.crear_orden_de_compra(0).unwrap();
+            contrato.crear_orden_de_compra(0, 1).unwrap();
 
             // Vendedor envía
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
@@ -1413,12 +1643,12 @@ mod primer_contrato {
             // Comprador ordena
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             contrato.agregar_usuario_sistema("C".into(), "Y".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
-            contrato.crear_orden_de_compra(0).unwrap();
+            contrato.crear_orden_de_compra(0, 1).unwrap();
 
             // Recibe sin que se haya enviado
             let result = contrato.recibir_compra(0);
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "El producto todavia no fue enviado.");
+            assert_eq!(result.unwrap_err(), Error::CompraNoEnviada);
         }
 
         #[ink::test]
@@ -1435,7 +1665,7 @@ mod primer_contrato {
             // Comprador ordena y vendedor envía
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             contrato.agregar_usuario_sistema("C".into(), "Y".into(), "Dir".into(), "c@mail.com".into(), Rol::Comp).unwrap();
-            contrato.crear_orden_de_compra(0).unwrap();
+            contrato.crear_orden_de_compra(0, 1).unwrap();
 
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
             contrato.enviar_compra(0).unwrap();
@@ -1443,7 +1673,7 @@ mod primer_contrato {
             // Vendedor intenta recibir (no puede)
             let result = contrato.recibir_compra(0);
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "El usuario no posee el rol de comprador.");
+            assert_eq!(result.unwrap_err(), Error::NoPoseeRolComprador);
         }
 
         #[ink::test]
@@ -1456,7 +1686,7 @@ mod primer_contrato {
 
             let result = contrato.recibir_compra(42);
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "No existe la orden buscada.");
+            assert_eq!(result.unwrap_err(), Error::OrdenInexistente);
         }
 
         #[ink::test]
@@ -1466,7 +1696,7 @@ mod primer_contrato {
             // Nadie registrado
             let result = contrato.recibir_compra(0);
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "No existe el usuario.");
+            assert_eq!(result.unwrap_err(), Error::UsuarioInexistente);
         }
 
         #[ink::test]
@@ -1,11 +1,19 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub use self::marketplace::{
+    Categoria, ErrorSistema, Estado, Marketplace, MarketplaceRef, OrdenCompra, Publicacion,
+    MAX_PAGINA,
+};
+
 #[ink::contract]
 mod marketplace {
     use ink::prelude::string::String;
     use ink::prelude::vec::Vec;
     use ink::storage::Mapping;
 
+    // Cantidad maxima de elementos devueltos por las consultas paginadas
+    pub const MAX_PAGINA: u32 = 50;
+
     #[ink(storage)]
     pub struct Marketplace {
         usuarios: Mapping<AccountId, Usuario>, // (id_usuario, datos_usuario) este capaz tmbn tenga
@@ -17,8 +25,17 @@ mod marketplace {
         ordenes_compra: Vec<OrdenCompra>,
         publicaciones_mapping: Mapping<AccountId, Vec<u32>>, // (id_vendedor, id's publicaciones)
         ordenes_compra_mapping: Mapping<AccountId, Vec<u32>>, // (id_comprador, id's ordenes)
-                                                             // u32 parece ser la mejor opción, usize no existe en ink porque depende de la arquitectura
-                                                             // u64 incrementaría los costos de transacción
+        // u32 parece ser la mejor opción, usize no existe en ink porque depende de la arquitectura
+        // u64 incrementaría los costos de transacción
+
+        // totales que se actualizan con cada orden, para que los reportes no recorran todo
+        recaudacion_por_categoria: Mapping<Categoria, u64>, // (categoria, monto de ordenes no canceladas)
+        ventas_por_publicacion: Mapping<u32, u32>, // (id_publicacion, ordenes no canceladas)
+        ordenes_por_vendedor: Mapping<AccountId, u32>, // (id_vendedor, ordenes recibidas)
+        peticiones_cancelacion_por_vendedor: Mapping<AccountId, u32>, // (id_vendedor, peticiones de cancelacion)
+        // cantidad y suma de los precios de las ordenes no canceladas
+        cantidad_ventas: u64,
+        monto_ventas: u64,
     }
 
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
@@ -35,6 +52,11 @@ mod marketplace {
         PublicacionNoExistente,
         UnderflowPublicaciones,
         UnderflowOrdenes,
+        OrdenNoExistente,
+        OrdenNoPerteneceAlUsuario,
+        OrdenNoCancelable,
+        CancelacionYaSolicitada,
+        CancelacionNoSolicitada,
     }
 
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
@@ -86,7 +108,7 @@ mod marketplace {
         publicacion: Publicacion,
         comprador_id: AccountId,
         peticion_cancelacion: bool, // La peticion la hace el comprador, el vendedor acepta, esta
-                                    // logica se maneja en el método (?)
+                                    // logica se maneja en solicitar_cancelacion y aceptar_cancelacion
     }
 
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
@@ -108,6 +130,12 @@ mod marketplace {
                 ordenes_compra: Default::default(),
                 publicaciones_mapping: Default::default(),
                 ordenes_compra_mapping: Default::default(),
+                recaudacion_por_categoria: Default::default(),
+                ventas_por_publicacion: Default::default(),
+                ordenes_por_vendedor: Default::default(),
+                peticiones_cancelacion_por_vendedor: Default::default(),
+                cantidad_ventas: 0,
+                monto_ventas: 0,
             }
         }
 
@@ -336,9 +364,158 @@ mod marketplace {
             self.ordenes_compra_mapping
                 .insert(usuario.account_id, &ordenes_compra_comprador);
 
+            //Actualiza los totales de los reportes
+            self.sumar_venta(&publicacion);
+            let ordenes_vendedor = self
+                .ordenes_por_vendedor
+                .get(publicacion.vendedor_id)
+                .unwrap_or(0);
+            self.ordenes_por_vendedor
+                .insert(publicacion.vendedor_id, &ordenes_vendedor.saturating_add(1));
+
             Ok(orden_compra)
         }
 
+        //El comprador pide cancelar una orden que todavia no fue enviada
+        #[ink(message)]
+        pub fn solicitar_cancelacion(
+            &mut self,
+            idx_orden: u32,
+        ) -> Result<OrdenCompra, ErrorSistema> {
+            self._solicitar_cancelacion(self.env().caller(), idx_orden)
+        }
+
+        //Funcion prueba solicitar_cancelacion()
+        fn _solicitar_cancelacion(
+            &mut self,
+            caller: AccountId,
+            idx_orden: u32,
+        ) -> Result<OrdenCompra, ErrorSistema> {
+            //Validacion de usuario
+            let usuario = self._get_usuario(caller)?;
+            usuario.es_comprador()?;
+
+            //Buscar orden
+            let mut orden = self
+                .ordenes_compra
+                .get(idx_orden as usize)
+                .cloned()
+                .ok_or(ErrorSistema::OrdenNoExistente)?;
+
+            if orden.comprador_id != usuario.account_id {
+                return Err(ErrorSistema::OrdenNoPerteneceAlUsuario);
+            }
+            if orden.estado != Estado::Pendiente {
+                return Err(ErrorSistema::OrdenNoCancelable);
+            }
+            if orden.peticion_cancelacion {
+                return Err(ErrorSistema::CancelacionYaSolicitada);
+            }
+
+            //Marca la peticion y la cuenta para el vendedor
+            orden.peticion_cancelacion = true;
+            self.ordenes_compra[idx_orden as usize] = orden.clone();
+
+            let vendedor_id = orden.publicacion.vendedor_id;
+            let peticiones = self
+                .peticiones_cancelacion_por_vendedor
+                .get(vendedor_id)
+                .unwrap_or(0);
+            self.peticiones_cancelacion_por_vendedor
+                .insert(vendedor_id, &peticiones.saturating_add(1));
+
+            Ok(orden)
+        }
+
+        //El vendedor acepta la peticion de cancelacion, la orden queda Cancelada y se repone el stock
+        #[ink(message)]
+        pub fn aceptar_cancelacion(&mut self, idx_orden: u32) -> Result<OrdenCompra, ErrorSistema> {
+            self._aceptar_cancelacion(self.env().caller(), idx_orden)
+        }
+
+        //Funcion prueba aceptar_cancelacion()
+        fn _aceptar_cancelacion(
+            &mut self,
+            caller: AccountId,
+            idx_orden: u32,
+        ) -> Result<OrdenCompra, ErrorSistema> {
+            //Validacion de usuario
+            let usuario = self._get_usuario(caller)?;
+            usuario.es_vendedor()?;
+
+            //Buscar orden
+            let mut orden = self
+                .ordenes_compra
+                .get(idx_orden as usize)
+                .cloned()
+                .ok_or(ErrorSistema::OrdenNoExistente)?;
+
+            if orden.publicacion.vendedor_id != usuario.account_id {
+                return Err(ErrorSistema::OrdenNoPerteneceAlUsuario);
+            }
+            if orden.estado != Estado::Pendiente {
+                return Err(ErrorSistema::OrdenNoCancelable);
+            }
+            if !orden.peticion_cancelacion {
+                return Err(ErrorSistema::CancelacionNoSolicitada);
+            }
+
+            //Repone la unidad en la publicacion
+            let idx_publicacion = orden.publicacion.id_publicacion as usize;
+            let publicacion = self
+                .publicaciones
+                .get_mut(idx_publicacion)
+                .ok_or(ErrorSistema::PublicacionNoExistente)?;
+            publicacion.stock = publicacion.stock.saturating_add(1);
+
+            //Cancela la orden y la descuenta de los totales de los reportes
+            orden.estado = Estado::Cancelada;
+            self.ordenes_compra[idx_orden as usize] = orden.clone();
+            self.restar_venta(&orden.publicacion);
+
+            Ok(orden)
+        }
+
+        //Suma una orden a la recaudacion de su categoria, a las ventas de su publicacion y al total
+        fn sumar_venta(&mut self, publicacion: &Publicacion) {
+            let recaudado = self
+                .recaudacion_por_categoria
+                .get(&publicacion.categoria)
+                .unwrap_or(0);
+            self.recaudacion_por_categoria.insert(
+                &publicacion.categoria,
+                &recaudado.saturating_add(publicacion.precio),
+            );
+
+            let id_publicacion = publicacion.id_publicacion as u32;
+            let ventas = self.ventas_por_publicacion.get(id_publicacion).unwrap_or(0);
+            self.ventas_por_publicacion
+                .insert(id_publicacion, &ventas.saturating_add(1));
+
+            self.cantidad_ventas = self.cantidad_ventas.saturating_add(1);
+            self.monto_ventas = self.monto_ventas.saturating_add(publicacion.precio);
+        }
+
+        //Descuenta de los totales una orden que se cancelo
+        fn restar_venta(&mut self, publicacion: &Publicacion) {
+            let recaudado = self
+                .recaudacion_por_categoria
+                .get(&publicacion.categoria)
+                .unwrap_or(0);
+            self.recaudacion_por_categoria.insert(
+                &publicacion.categoria,
+                &recaudado.saturating_sub(publicacion.precio),
+            );
+
+            let id_publicacion = publicacion.id_publicacion as u32;
+            let ventas = self.ventas_por_publicacion.get(id_publicacion).unwrap_or(0);
+            self.ventas_por_publicacion
+                .insert(id_publicacion, &ventas.saturating_sub(1));
+
+            self.cantidad_ventas = self.cantidad_ventas.saturating_sub(1);
+            self.monto_ventas = self.monto_ventas.saturating_sub(publicacion.precio);
+        }
+
         //Retorna las ordenes de compra del comprador solicitante
         #[ink(message)]
         #[ignore]
@@ -384,6 +561,83 @@ mod marketplace {
             self._get_usuario(caller)?;
             Ok(self.ordenes_compra.clone())
         }
+
+        //Retorna la cantidad total de publicaciones del sistema
+        //No requiere usuario registrado, pensado para contratos de reportes
+        #[ink(message)]
+        pub fn get_cantidad_publicaciones(&self) -> u32 {
+            self.publicaciones.len() as u32
+        }
+
+        //Retorna una pagina de publicaciones a partir de la posicion `desde`
+        //La cantidad devuelta se limita a MAX_PAGINA
+        #[ink(message)]
+        pub fn get_publicaciones_paginado(&self, desde: u32, cantidad: u32) -> Vec<Publicacion> {
+            paginar(&self.publicaciones, desde, cantidad)
+        }
+
+        //Retorna la cantidad total de ordenes de compra del sistema
+        //No requiere usuario registrado, pensado para contratos de reportes
+        #[ink(message)]
+        pub fn get_cantidad_ordenes(&self) -> u32 {
+            self.ordenes_compra.len() as u32
+        }
+
+        //Retorna una pagina de ordenes de compra a partir de la posicion `desde`
+        //La cantidad devuelta se limita a MAX_PAGINA
+        #[ink(message)]
+        pub fn get_ordenes_paginado(&self, desde: u32, cantidad: u32) -> Vec<OrdenCompra> {
+            paginar(&self.ordenes_compra, desde, cantidad)
+        }
+
+        //Retorna una pagina de publicaciones junto a sus ventas (ordenes no canceladas)
+        //La cantidad devuelta se limita a MAX_PAGINA
+        #[ink(message)]
+        pub fn get_ventas_paginado(&self, desde: u32, cantidad: u32) -> Vec<(Publicacion, u32)> {
+            paginar(&self.publicaciones, desde, cantidad)
+                .into_iter()
+                .map(|publicacion| {
+                    let ventas = self
+                        .ventas_por_publicacion
+                        .get(publicacion.id_publicacion as u32)
+                        .unwrap_or(0);
+                    (publicacion, ventas)
+                })
+                .collect()
+        }
+
+        //Retorna lo recaudado por las ordenes no canceladas de una categoria
+        #[ink(message)]
+        pub fn get_recaudacion_categoria(&self, categoria: Categoria) -> u64 {
+            self.recaudacion_por_categoria.get(&categoria).unwrap_or(0)
+        }
+
+        //Retorna (ordenes recibidas, ordenes con peticion de cancelacion) de un vendedor
+        #[ink(message)]
+        pub fn get_cancelaciones_vendedor(&self, vendedor_id: AccountId) -> (u32, u32) {
+            (
+                self.ordenes_por_vendedor.get(vendedor_id).unwrap_or(0),
+                self.peticiones_cancelacion_por_vendedor
+                    .get(vendedor_id)
+                    .unwrap_or(0),
+            )
+        }
+
+        //Retorna (cantidad, monto total) de las ordenes no canceladas
+        #[ink(message)]
+        pub fn get_totales_ventas(&self) -> (u64, u64) {
+            (self.cantidad_ventas, self.monto_ventas)
+        }
+    }
+
+    //Devuelve una copia de los elementos en [desde, desde + cantidad), con cantidad acotada a MAX_PAGINA
+    fn paginar<T: Clone>(elementos: &[T], desde: u32, cantidad: u32) -> Vec<T> {
+        elementos
+            .iter()
+            .skip(desde as usize)
+            .take(cantidad.min(MAX_PAGINA) as usize)
+            .cloned()
+            .collect()
     }
 
     impl Publicacion {
@@ -406,6 +660,52 @@ mod marketplace {
                 vendedor_id,
             }
         }
+
+        pub fn get_id_publicacion(&self) -> u64 {
+            self.id_publicacion
+        }
+
+        pub fn get_nombre_producto(&self) -> &String {
+            &self.nombre_producto
+        }
+
+        pub fn get_descripcion(&self) -> &String {
+            &self.descripcion
+        }
+
+        pub fn get_precio(&self) -> u64 {
+            self.precio
+        }
+
+        pub fn get_categoria(&self) -> &Categoria {
+            &self.categoria
+        }
+
+        pub fn get_stock(&self) -> u64 {
+            self.stock
+        }
+
+        pub fn get_vendedor_id(&self) -> AccountId {
+            self.vendedor_id
+        }
+    }
+
+    impl OrdenCompra {
+        pub fn get_estado(&self) -> &Estado {
+            &self.estado
+        }
+
+        pub fn get_publicacion(&self) -> &Publicacion {
+            &self.publicacion
+        }
+
+        pub fn get_comprador_id(&self) -> AccountId {
+            self.comprador_id
+        }
+
+        pub fn get_peticion_cancelacion(&self) -> bool {
+            self.peticion_cancelacion
+        }
     }
 
     impl Usuario {
@@ -1024,6 +1324,172 @@ mod marketplace {
             }
         }
 
+        mod tests_consultas_paginadas {
+            use super::*;
+
+            fn marketplace_con_publicaciones(cantidad: u64) -> (Marketplace, AccountId) {
+                let mut marketplace = Marketplace::new();
+
+                let caller = AccountId::from([0xAA; 32]);
+                let _ = marketplace._registrar_usuario(caller, "agustin".to_string(), Rol::Ambos);
+
+                for i in 0..cantidad {
+                    let _ = marketplace._publicar(
+                        caller,
+                        "Remera".to_string(),
+                        "algodon".to_string(),
+                        1000 + i,
+                        Categoria::Ropa,
+                        10,
+                    );
+                }
+
+                (marketplace, caller)
+            }
+
+            #[ink::test]
+            fn tests_get_publicaciones_paginado_correcto() {
+                let (marketplace, _) = marketplace_con_publicaciones(5);
+
+                assert_eq!(marketplace.get_cantidad_publicaciones(), 5);
+
+                let pagina = marketplace.get_publicaciones_paginado(1, 2);
+                assert_eq!(pagina.len(), 2);
+                assert_eq!(pagina[0].id_publicacion, 1);
+                assert_eq!(pagina[1].id_publicacion, 2);
+
+                // Pagina final incompleta y pagina fuera de rango
+                assert_eq!(marketplace.get_publicaciones_paginado(4, 10).len(), 1);
+                assert!(marketplace.get_publicaciones_paginado(5, 10).is_empty());
+            }
+
+            #[ink::test]
+            fn tests_get_publicaciones_paginado_limite_maximo() {
+                let (marketplace, _) = marketplace_con_publicaciones(MAX_PAGINA as u64 + 5);
+
+                let pagina = marketplace.get_publicaciones_paginado(0, u32::MAX);
+                assert_eq!(pagina.len(), MAX_PAGINA as usize);
+            }
+
+            #[ink::test]
+            fn tests_get_ordenes_paginado_correcto() {
+                let (mut marketplace, caller) = marketplace_con_publicaciones(2);
+
+                let _ = marketplace._ordenar_compra(caller, 0_u32);
+                let _ = marketplace._ordenar_compra(caller, 1_u32);
+                let _ = marketplace._ordenar_compra(caller, 1_u32);
+
+                assert_eq!(marketplace.get_cantidad_ordenes(), 3);
+
+                let pagina = marketplace.get_ordenes_paginado(1, 5);
+                assert_eq!(pagina.len(), 2);
+                assert_eq!(pagina[0].publicacion.id_publicacion, 1);
+                assert_eq!(pagina[0].comprador_id, caller);
+            }
+        }
+
+        mod tests_cancelacion {
+            use super::*;
+
+            //Vendedor 0xAA con una remera publicada y comprador 0xBB con una orden sobre ella
+            fn marketplace_con_orden() -> (Marketplace, AccountId, AccountId) {
+                let mut marketplace = Marketplace::new();
+
+                let vendedor = AccountId::from([0xAA; 32]);
+                let comprador = AccountId::from([0xBB; 32]);
+                let _ =
+                    marketplace._registrar_usuario(vendedor, "agustin".to_string(), Rol::Vendedor);
+                let _ =
+                    marketplace._registrar_usuario(comprador, "lucia".to_string(), Rol::Comprador);
+
+                let _ = marketplace._publicar(
+                    vendedor,
+                    "Remera".to_string(),
+                    "algodon".to_string(),
+                    1000,
+                    Categoria::Ropa,
+                    10,
+                );
+                let _ = marketplace._ordenar_compra(comprador, 0_u32);
+
+                (marketplace, vendedor, comprador)
+            }
+
+            #[ink::test]
+            fn tests_cancelacion_correcto() {
+                let (mut marketplace, vendedor, comprador) = marketplace_con_orden();
+
+                assert_eq!(marketplace.get_totales_ventas(), (1, 1000));
+                assert_eq!(marketplace.get_recaudacion_categoria(Categoria::Ropa), 1000);
+
+                let orden = marketplace._solicitar_cancelacion(comprador, 0).unwrap();
+                assert!(orden.get_peticion_cancelacion());
+                assert_eq!(marketplace.get_cancelaciones_vendedor(vendedor), (1, 1));
+
+                let orden = marketplace._aceptar_cancelacion(vendedor, 0).unwrap();
+                assert_eq!(orden.get_estado(), &Estado::Cancelada);
+                assert_eq!(marketplace.publicaciones[0].stock, 10);
+
+                // La orden cancelada deja de contar como venta, la peticion se sigue contando
+                assert_eq!(marketplace.get_totales_ventas(), (0, 0));
+                assert_eq!(marketplace.get_recaudacion_categoria(Categoria::Ropa), 0);
+                assert_eq!(marketplace.get_ventas_paginado(0, 1)[0].1, 0);
+                assert_eq!(marketplace.get_cancelaciones_vendedor(vendedor), (1, 1));
+            }
+
+            #[ink::test]
+            fn tests_solicitar_cancelacion_errores() {
+                let (mut marketplace, vendedor, comprador) = marketplace_con_orden();
+
+                let otro = AccountId::from([0xCC; 32]);
+                let _ = marketplace._registrar_usuario(otro, "juan".to_string(), Rol::Comprador);
+
+                assert_eq!(
+                    marketplace._solicitar_cancelacion(comprador, 1),
+                    Err(ErrorSistema::OrdenNoExistente)
+                );
+                assert_eq!(
+                    marketplace._solicitar_cancelacion(otro, 0),
+                    Err(ErrorSistema::OrdenNoPerteneceAlUsuario)
+                );
+                assert_eq!(
+                    marketplace._solicitar_cancelacion(vendedor, 0),
+                    Err(ErrorSistema::UsuarioNoEsComprador)
+                );
+
+                assert!(marketplace._solicitar_cancelacion(comprador, 0).is_ok());
+                assert_eq!(
+                    marketplace._solicitar_cancelacion(comprador, 0),
+                    Err(ErrorSistema::CancelacionYaSolicitada)
+                );
+            }
+
+            #[ink::test]
+            fn tests_aceptar_cancelacion_errores() {
+                let (mut marketplace, vendedor, comprador) = marketplace_con_orden();
+
+                let otro = AccountId::from([0xCC; 32]);
+                let _ = marketplace._registrar_usuario(otro, "juan".to_string(), Rol::Vendedor);
+
+                assert_eq!(
+                    marketplace._aceptar_cancelacion(vendedor, 0),
+                    Err(ErrorSistema::CancelacionNoSolicitada)
+                );
+
+                let _ = marketplace._solicitar_cancelacion(comprador, 0);
+                assert_eq!(
+                    marketplace._aceptar_cancelacion(otro, 0),
+                    Err(ErrorSistema::OrdenNoPerteneceAlUsuario)
+                );
+
+                assert!(marketplace._aceptar_cancelacion(vendedor, 0).is_ok());
+                assert_eq!(
+                    marketplace._aceptar_cancelacion(vendedor, 0),
+                    Err(ErrorSistema::OrdenNoCancelable)
+                );
+            }
+        }
+
         mod tests_get_ordenes {
             use super::*;
 
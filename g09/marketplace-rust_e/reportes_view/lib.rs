@@ -2,140 +2,192 @@
 
 #[ink::contract]
 mod reportes_view {
+    use ink::env::call::FromAccountId;
+    use ink::prelude::vec::Vec;
+    use marketplace::{Categoria, MarketplaceRef, Publicacion};
 
-    /// Defines the storage of your contract.
-    /// Add new fields to the below struct in order
-    /// to add new static storage fields to your contract.
     #[ink(storage)]
     pub struct ReportesView {
-        /// Stores a single `bool` value on the storage.
-        value: bool,
+        marketplace: MarketplaceRef, // contrato del que se leen publicaciones y ordenes
     }
 
     impl ReportesView {
-        /// Constructor that initializes the `bool` value to the given `init_value`.
+        //Crea la vista apuntando a un Marketplace ya desplegado
         #[ink(constructor)]
-        pub fn new(init_value: bool) -> Self {
-            Self { value: init_value }
+        pub fn new(marketplace_id: AccountId) -> Self {
+            Self {
+                marketplace: MarketplaceRef::from_account_id(marketplace_id),
+            }
         }
 
-        /// Constructor that initializes the `bool` value to `false`.
-        ///
-        /// Constructors can delegate to other constructors.
-        #[ink(constructor)]
-        pub fn default() -> Self {
-            Self::new(Default::default())
+        //Retorna el AccountId del Marketplace consultado
+        #[ink(message)]
+        pub fn get_marketplace(&self) -> AccountId {
+            ink::ToAccountId::to_account_id(&self.marketplace)
+        }
+
+        //Retorna lo recaudado por cada categoria, sin contar ordenes canceladas
+        #[ink(message)]
+        pub fn get_recaudacion_por_categoria(&self) -> Vec<(Categoria, u64)> {
+            [
+                Categoria::Computacion,
+                Categoria::Ropa,
+                Categoria::Herramientas,
+                Categoria::Muebles,
+            ]
+            .into_iter()
+            .map(|categoria| {
+                let total = self
+                    .marketplace
+                    .get_recaudacion_categoria(categoria.clone());
+                (categoria, total)
+            })
+            .collect()
+        }
+
+        //Retorna las `cantidad` publicaciones con mas ventas de la pagina [desde, desde + limite),
+        //de mayor a menor. El limite lo acota el Marketplace a MAX_PAGINA, el que consulta
+        //recorre las paginas y combina los rankings
+        #[ink(message)]
+        pub fn get_publicaciones_mas_vendidas(
+            &self,
+            desde: u32,
+            limite: u32,
+            cantidad: u32,
+        ) -> Vec<(Publicacion, u32)> {
+            publicaciones_mas_vendidas(
+                self.marketplace.get_ventas_paginado(desde, limite),
+                cantidad,
+            )
         }
 
-        /// A message that can be called on instantiated contracts.
-        /// This one flips the value of the stored `bool` from `true`
-        /// to `false` and vice versa.
+        //Retorna el porcentaje (0 a 100) de las ordenes de un vendedor con peticion de cancelacion
         #[ink(message)]
-        pub fn flip(&mut self) {
-            self.value = !self.value;
+        pub fn get_tasa_cancelacion_vendedor(&self, vendedor_id: AccountId) -> u32 {
+            let (ordenes, peticiones) = self.marketplace.get_cancelaciones_vendedor(vendedor_id);
+            tasa_cancelacion(ordenes, peticiones)
         }
 
-        /// Simply returns the current value of our `bool`.
+        //Retorna el monto promedio de las ordenes no canceladas (0 si no hay ordenes)
         #[ink(message)]
-        pub fn get(&self) -> bool {
-            self.value
+        pub fn get_promedio_monto_orden(&self) -> u64 {
+            let (cantidad, monto) = self.marketplace.get_totales_ventas();
+            promedio_monto_orden(cantidad, monto)
         }
     }
 
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
+    fn publicaciones_mas_vendidas(
+        ventas: Vec<(Publicacion, u32)>,
+        cantidad: u32,
+    ) -> Vec<(Publicacion, u32)> {
+        let mut ranking: Vec<(Publicacion, u32)> =
+            ventas.into_iter().filter(|(_, total)| *total > 0).collect();
+
+        //Mayor cantidad de ventas primero, a igualdad la publicacion mas antigua
+        ranking.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.get_id_publicacion().cmp(&b.0.get_id_publicacion()))
+        });
+        ranking.truncate(cantidad as usize);
+        ranking
+    }
+
+    fn tasa_cancelacion(ordenes: u32, peticiones: u32) -> u32 {
+        peticiones
+            .saturating_mul(100)
+            .checked_div(ordenes)
+            .unwrap_or(0)
+    }
+
+    fn promedio_monto_orden(cantidad: u64, monto: u64) -> u64 {
+        monto.checked_div(cantidad).unwrap_or(0)
+    }
+
     #[cfg(test)]
     mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
 
-        /// We test if the default constructor does its job.
-        #[ink::test]
-        fn default_works() {
-            let reportes_view = ReportesView::default();
-            assert_eq!(reportes_view.get(), false);
+        fn publicacion(id: u64, precio: u64, categoria: Categoria, vendedor: u8) -> Publicacion {
+            Publicacion::new(
+                id,
+                "Producto".to_string(),
+                "descripcion".to_string(),
+                precio,
+                categoria,
+                10,
+                AccountId::from([vendedor; 32]),
+            )
         }
 
-        /// We test a simple use case of our contract.
-        #[ink::test]
-        fn it_works() {
-            let mut reportes_view = ReportesView::new(false);
-            assert_eq!(reportes_view.get(), false);
-            reportes_view.flip();
-            assert_eq!(reportes_view.get(), true);
+        mod tests_new {
+            use super::*;
+
+            #[ink::test]
+            fn tests_new_guarda_marketplace() {
+                let marketplace_id = AccountId::from([0xAA; 32]);
+                let reportes = ReportesView::new(marketplace_id);
+
+                assert_eq!(reportes.get_marketplace(), marketplace_id);
+            }
         }
-    }
 
+        mod tests_publicaciones_mas_vendidas {
+            use super::*;
 
-    /// This is how you'd write end-to-end (E2E) or integration tests for ink! contracts.
-    ///
-    /// When running these you need to make sure that you:
-    /// - Compile the tests with the `e2e-tests` feature flag enabled (`--features e2e-tests`)
-    /// - Are running a Substrate node which contains `pallet-contracts` in the background
-    #[cfg(all(test, feature = "e2e-tests"))]
-    mod e2e_tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
+            #[test]
+            fn tests_publicaciones_mas_vendidas_correcto() {
+                let remera = publicacion(0, 1000, Categoria::Ropa, 0xAA);
+                let silla = publicacion(1, 8000, Categoria::Muebles, 0xAA);
+                let martillo = publicacion(2, 3000, Categoria::Herramientas, 0xBB);
+                let mesa = publicacion(3, 9000, Categoria::Muebles, 0xBB);
 
-        /// A helper function used for calling contract messages.
-        use ink_e2e::ContractsBackend;
+                let ventas = vec![
+                    (remera.clone(), 1),
+                    (silla.clone(), 2),
+                    (martillo, 1),
+                    (mesa, 0),
+                ];
 
-        /// The End-to-End test `Result` type.
-        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+                let ranking = publicaciones_mas_vendidas(ventas, 2);
 
-        /// We test that we can upload and instantiate the contract using its default constructor.
-        #[ink_e2e::test]
-        async fn default_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Given
-            let mut constructor = ReportesViewRef::default();
+                assert_eq!(ranking, vec![(silla, 2), (remera, 1)]);
+            }
 
-            // When
-            let contract = client
-                .instantiate("reportes_view", &ink_e2e::alice(), &mut constructor)
-                .submit()
-                .await
-                .expect("instantiate failed");
-            let call_builder = contract.call_builder::<ReportesView>();
+            #[test]
+            fn tests_publicaciones_mas_vendidas_sin_ventas() {
+                let ventas = vec![(publicacion(0, 1000, Categoria::Ropa, 0xAA), 0)];
 
-            // Then
-            let get = call_builder.get();
-            let get_result = client.call(&ink_e2e::alice(), &get).dry_run().await?;
-            assert!(matches!(get_result.return_value(), false));
+                assert!(publicaciones_mas_vendidas(ventas, 5).is_empty());
+            }
+        }
+
+        mod tests_tasa_cancelacion {
+            use super::*;
+
+            #[test]
+            fn tests_tasa_cancelacion_correcto() {
+                assert_eq!(tasa_cancelacion(4, 2), 50);
+                assert_eq!(tasa_cancelacion(3, 0), 0);
+            }
 
-            Ok(())
+            #[test]
+            fn tests_tasa_cancelacion_sin_ordenes() {
+                assert_eq!(tasa_cancelacion(0, 0), 0);
+            }
         }
 
-        /// We test that we can read and write a value from the on-chain contract.
-        #[ink_e2e::test]
-        async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Given
-            let mut constructor = ReportesViewRef::new(false);
-            let contract = client
-                .instantiate("reportes_view", &ink_e2e::bob(), &mut constructor)
-                .submit()
-                .await
-                .expect("instantiate failed");
-            let mut call_builder = contract.call_builder::<ReportesView>();
-
-            let get = call_builder.get();
-            let get_result = client.call(&ink_e2e::bob(), &get).dry_run().await?;
-            assert!(matches!(get_result.return_value(), false));
-
-            // When
-            let flip = call_builder.flip();
-            let _flip_result = client
-                .call(&ink_e2e::bob(), &flip)
-                .submit()
-                .await
-                .expect("flip failed");
-
-            // Then
-            let get = call_builder.get();
-            let get_result = client.call(&ink_e2e::bob(), &get).dry_run().await?;
-            assert!(matches!(get_result.return_value(), true));
-
-            Ok(())
+        mod tests_promedio_monto_orden {
+            use super::*;
+
+            #[test]
+            fn tests_promedio_monto_orden_correcto() {
+                assert_eq!(promedio_monto_orden(2, 9000), 4500);
+            }
+
+            #[test]
+            fn tests_promedio_monto_orden_sin_ordenes() {
+                assert_eq!(promedio_monto_orden(0, 0), 0);
+            }
         }
     }
 }
@@ -7,9 +7,13 @@
 /// Este contrato permite a los usuarios registrarse como compradores, vendedores o ambos.
 /// Los vendedores pueden publicar productos, y los compradores pueden comprar esos productos,
 /// creando órdenes que siguen un flujo de estado (Pendiente -> Enviado -> Recibido).
+///
+/// El pago de cada orden queda retenido en el contrato (escrow) y se libera al vendedor
+/// recién cuando el comprador marca la orden como recibida.
 #[ink::contract]
 mod marketplace {
     use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
     use ink::storage::Mapping;
     use scale::{Decode, Encode};
 
@@ -72,7 +76,23 @@ mod marketplace {
         pub stock: u32,
     }
 
-    /// Representa una orden de compra de un producto.
+    /// Cantidad máxima de líneas (productos distintos) que puede tener un carrito.
+    pub const MAX_LINEAS_CARRITO: u32 = 20;
+
+    /// Representa una línea de un carrito o de una orden: un producto y cuántas unidades se compran.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Linea {
+        /// El identificador del producto.
+        pub id_prod: u32,
+        /// La cantidad de unidades.
+        pub cantidad: u32,
+    }
+
+    /// Representa una orden de compra a un vendedor.
     #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
     #[cfg_attr(
         feature = "std",
@@ -81,14 +101,14 @@ mod marketplace {
     pub struct Orden {
         /// La cuenta del comprador que realizó la orden.
         pub comprador: AccountId,
-        /// La cuenta del vendedor del producto.
+        /// La cuenta del vendedor de los productos.
         pub vendedor: AccountId,
-        /// El identificador del producto comprado.
-        pub id_prod: u32,
-        /// La cantidad de unidades compradas.
-        pub cantidad: u32,
+        /// Los productos comprados, todos del mismo vendedor.
+        pub lineas: Vec<Linea>,
         /// El estado actual de la orden.
         pub estado: Estado,
+        /// El monto pagado por el comprador que el contrato retiene hasta que la orden se recibe.
+        pub monto: Balance,
     }
 
     /// Enumera los posibles errores que pueden ocurrir en el contrato.
@@ -113,6 +133,95 @@ mod marketplace {
         EstadoInvalido,
         /// El contador de IDs ha alcanzado su valor máximo y no se pueden crear más elementos.
         IdOverflow,
+        /// El monto transferido no alcanza para pagar el total de la compra.
+        PagoInsuf,
+        /// No se pudo transferir fondos desde el contrato.
+        TransferFallida,
+        /// El carrito ya tiene la cantidad máxima de líneas.
+        CarritoLleno,
+        /// El carrito no tiene productos.
+        CarritoVacio,
+        /// El producto no está en el carrito.
+        NoEnCarrito,
+    }
+
+    /// Evento emitido cuando un usuario se registra en el marketplace.
+    #[ink(event)]
+    pub struct UsuarioRegistrado {
+        /// La cuenta del usuario registrado.
+        #[ink(topic)]
+        usuario: AccountId,
+        /// El rol con el que se registró.
+        rol: Rol,
+    }
+
+    /// Evento emitido cuando un vendedor publica un producto.
+    #[ink(event)]
+    pub struct ProductoPublicado {
+        /// El ID asignado al producto.
+        #[ink(topic)]
+        id_prod: u32,
+        /// La cuenta del vendedor que publicó el producto.
+        #[ink(topic)]
+        vendedor: AccountId,
+        /// El nombre del producto.
+        nombre: String,
+        /// El precio del producto.
+        precio: Balance,
+        /// La cantidad de unidades publicadas.
+        stock: u32,
+    }
+
+    /// Evento emitido cuando un comprador crea una orden.
+    #[ink(event)]
+    pub struct OrdenCreada {
+        /// El ID asignado a la orden.
+        #[ink(topic)]
+        oid: u32,
+        /// La cuenta del comprador.
+        #[ink(topic)]
+        comprador: AccountId,
+        /// La cuenta del vendedor.
+        #[ink(topic)]
+        vendedor: AccountId,
+        /// Los productos comprados.
+        lineas: Vec<Linea>,
+        /// El monto retenido por el contrato.
+        monto: Balance,
+    }
+
+    /// Evento emitido cuando el vendedor marca una orden como enviada.
+    #[ink(event)]
+    pub struct OrdenEnviada {
+        /// El ID de la orden.
+        #[ink(topic)]
+        oid: u32,
+        /// La cuenta del vendedor.
+        #[ink(topic)]
+        vendedor: AccountId,
+    }
+
+    /// Evento emitido cuando el comprador marca una orden como recibida y se libera el pago.
+    #[ink(event)]
+    pub struct OrdenRecibida {
+        /// El ID de la orden.
+        #[ink(topic)]
+        oid: u32,
+        /// La cuenta del comprador.
+        #[ink(topic)]
+        comprador: AccountId,
+        /// El monto liberado al vendedor.
+        monto: Balance,
+    }
+
+    /// Evento emitido cuando cambia el stock de un producto.
+    #[ink(event)]
+    pub struct StockActualizado {
+        /// El ID del producto.
+        #[ink(topic)]
+        id_prod: u32,
+        /// El stock restante del producto.
+        stock: u32,
     }
 
     /// La estructura de almacenamiento principal del contrato.
@@ -124,6 +233,8 @@ mod marketplace {
         productos: Mapping<u32, Producto>,
         /// Almacena las órdenes de compra, mapeadas por su ID.
         ordenes: Mapping<u32, Orden>,
+        /// El carrito de cada comprador, pendiente de pagar.
+        carritos: Mapping<AccountId, Vec<Linea>>,
         /// El ID que se asignará al próximo producto publicado.
         next_prod_id: u32,
         /// El ID que se asignará a la próxima orden creada.
@@ -149,6 +260,7 @@ mod marketplace {
                 roles: Mapping::default(),
                 productos: Mapping::default(),
                 ordenes: Mapping::default(),
+                carritos: Mapping::default(),
                 next_prod_id: 1,
                 next_order_id: 1,
             }
@@ -230,6 +342,8 @@ mod marketplace {
         /// Permite a un comprador crear una orden para un producto.
         ///
         /// El llamante debe estar registrado como `Comprador` o `Ambos`.
+        /// Debe transferir al menos `precio * cant`: ese monto queda retenido en el contrato
+        /// hasta que la orden se marca como recibida, y el excedente se le devuelve.
         ///
         /// # Argumentos
         ///
@@ -242,15 +356,18 @@ mod marketplace {
         /// - `Error::ParamInvalido` si la cantidad es 0.
         /// - `Error::ProdInexistente` si el producto no existe.
         /// - `Error::StockInsuf` si no hay suficiente stock para la cantidad solicitada.
+        /// - `Error::PagoInsuf` si el monto transferido es menor que `precio * cant`.
         /// - `Error::IdOverflow` si se ha alcanzado el número máximo de órdenes.
+        /// - `Error::TransferFallida` si no se pudo devolver el excedente al comprador.
         ///
         /// # Retorno
         ///
         /// Devuelve el `id` de la nueva orden creada.
-        #[ink(message)]
+        #[ink(message, payable)]
         pub fn comprar(&mut self, id_prod: u32, cant: u32) -> Result<u32, Error> {
             let comprador = self.env().caller();
-            self._comprar(comprador, id_prod, cant)
+            let pago = self.env().transferred_value();
+            self._comprar(comprador, id_prod, cant, pago)
         }
 
         /// Marca una orden como enviada.
@@ -277,6 +394,7 @@ mod marketplace {
         ///
         /// Solo el comprador de la orden puede llamar a esta función.
         /// La orden debe estar en estado `Enviado`.
+        /// Al marcarse como recibida, el monto retenido se transfiere al vendedor.
         ///
         /// # Argumentos
         ///
@@ -287,6 +405,7 @@ mod marketplace {
         /// - `Error::OrdenInexistente` si la orden no existe.
         /// - `Error::SinPermiso` si el llamante no es el comprador de la orden.
         /// - `Error::EstadoInvalido` si la orden no está en estado `Enviado`.
+        /// - `Error::TransferFallida` si no se pudo pagar al vendedor.
         #[ink(message)]
         pub fn marcar_recibido(&mut self, oid: u32) -> Result<(), Error> {
             let caller = self.env().caller();
@@ -307,6 +426,88 @@ mod marketplace {
             self.ordenes.get(id)
         }
 
+        /// Agrega unidades de un producto al carrito del llamante.
+        ///
+        /// Si el producto ya está en el carrito se suman las unidades a su línea.
+        /// El stock se vuelve a validar al pagar, porque puede cambiar mientras tanto.
+        ///
+        /// # Argumentos
+        ///
+        /// * `id_prod` - El ID del producto a agregar.
+        /// * `cant` - La cantidad de unidades a agregar (debe ser mayor que 0).
+        ///
+        /// # Errores
+        ///
+        /// - `Error::SinPermiso` si el llamante no es un comprador.
+        /// - `Error::ParamInvalido` si la cantidad es 0.
+        /// - `Error::ProdInexistente` si el producto no existe.
+        /// - `Error::StockInsuf` si el carrito pasaría a tener más unidades que el stock del producto.
+        /// - `Error::CarritoLleno` si el producto es nuevo y el carrito ya tiene `MAX_LINEAS_CARRITO` líneas.
+        #[ink(message)]
+        pub fn agregar_al_carrito(&mut self, id_prod: u32, cant: u32) -> Result<(), Error> {
+            let comprador = self.env().caller();
+            self._agregar_al_carrito(comprador, id_prod, cant)
+        }
+
+        /// Quita un producto del carrito del llamante, con todas sus unidades.
+        ///
+        /// # Argumentos
+        ///
+        /// * `id_prod` - El ID del producto a quitar.
+        ///
+        /// # Errores
+        ///
+        /// - `Error::NoEnCarrito` si el producto no está en el carrito.
+        #[ink(message)]
+        pub fn quitar_del_carrito(&mut self, id_prod: u32) -> Result<(), Error> {
+            let comprador = self.env().caller();
+            self._quitar_del_carrito(comprador, id_prod)
+        }
+
+        /// Vacía el carrito del llamante.
+        #[ink(message)]
+        pub fn vaciar_carrito(&mut self) {
+            let comprador = self.env().caller();
+            self.carritos.remove(comprador);
+        }
+
+        /// Obtiene el carrito del llamante.
+        ///
+        /// # Retorno
+        ///
+        /// Devuelve las líneas del carrito en el orden en que se agregaron (vacío si no tiene).
+        #[ink(message)]
+        pub fn ver_carrito(&self) -> Vec<Linea> {
+            self.carritos.get(self.env().caller()).unwrap_or_default()
+        }
+
+        /// Compra todo el carrito del llamante en una sola operación.
+        ///
+        /// Primero se valida cada línea (producto existente y stock suficiente) y el pago total;
+        /// si alguna falla no se modifica nada. Después se descuenta el stock y se crea una orden
+        /// por cada vendedor, con sus líneas y su parte del pago retenida en el contrato.
+        /// El carrito queda vacío y el excedente se le devuelve al comprador.
+        ///
+        /// # Errores
+        ///
+        /// - `Error::SinPermiso` si el llamante no es un comprador.
+        /// - `Error::CarritoVacio` si el carrito no tiene productos.
+        /// - `Error::ProdInexistente` si algún producto ya no existe.
+        /// - `Error::StockInsuf` si algún producto no tiene stock suficiente.
+        /// - `Error::PagoInsuf` si el monto transferido es menor que el total del carrito.
+        /// - `Error::IdOverflow` si no alcanzan los IDs para crear todas las órdenes.
+        /// - `Error::TransferFallida` si no se pudo devolver el excedente al comprador.
+        ///
+        /// # Retorno
+        ///
+        /// Devuelve los `id` de las órdenes creadas, una por vendedor.
+        #[ink(message, payable)]
+        pub fn comprar_carrito(&mut self) -> Result<Vec<u32>, Error> {
+            let comprador = self.env().caller();
+            let pago = self.env().transferred_value();
+            self._comprar_carrito(comprador, pago)
+        }
+
         // A partir de acá están las funciones internas que implementan la lógica del contrato.
 
         /// Lógica interna para registrar un usuario.
@@ -315,6 +516,11 @@ mod marketplace {
             self.ensure(!self.roles.contains(caller), Error::YaRegistrado)?;
             // Inserta el nuevo rol para el usuario en el mapping `roles`.
             self.roles.insert(caller, &rol);
+            // Emite el evento de registro.
+            self.env().emit_event(UsuarioRegistrado {
+                usuario: caller,
+                rol,
+            });
             // Devuelve `Ok` para indicar que el registro fue exitoso.
             Ok(())
         }
@@ -354,6 +560,14 @@ mod marketplace {
 
             // Inserta el nuevo producto en el mapping `productos`.
             self.productos.insert(pid, &producto);
+            // Emite el evento de publicación.
+            self.env().emit_event(ProductoPublicado {
+                id_prod: pid,
+                vendedor,
+                nombre: producto.nombre,
+                precio,
+                stock,
+            });
             // Devuelve el ID del producto recién creado.
             Ok(pid)
         }
@@ -364,6 +578,7 @@ mod marketplace {
             comprador: AccountId,
             id_prod: u32,
             cant: u32,
+            pago: Balance,
         ) -> Result<u32, Error> {
             // Obtiene el rol del comprador. Devuelve `Error::SinRegistro` si no está registrado.
             let rol_comprador = self.rol_de(comprador)?;
@@ -377,10 +592,23 @@ mod marketplace {
             // Verifica que haya suficiente stock. Si no, devuelve `Error::StockInsuf`.
             self.ensure(producto.stock >= cant, Error::StockInsuf)?;
 
+            // Calcula el total a pagar. Un desbordamiento implica un pedido imposible de pagar.
+            let total = producto
+                .precio
+                .checked_mul(Balance::from(cant))
+                .ok_or(Error::PagoInsuf)?;
+            // Verifica que el comprador haya transferido lo suficiente. Si no, devuelve `Error::PagoInsuf`.
+            self.ensure(pago >= total, Error::PagoInsuf)?;
+
             // Reduce el stock del producto y maneja un posible subdesbordamiento.
             producto.stock = producto.stock.checked_sub(cant).ok_or(Error::StockInsuf)?;
             // Actualiza la información del producto en el almacenamiento.
             self.productos.insert(id_prod, &producto);
+            // Emite el evento con el stock restante.
+            self.env().emit_event(StockActualizado {
+                id_prod,
+                stock: producto.stock,
+            });
 
             // Obtiene el ID para la nueva orden.
             let oid = self.next_order_id;
@@ -391,18 +619,196 @@ mod marketplace {
             let orden = Orden {
                 comprador,
                 vendedor: producto.vendedor,
-                id_prod,
-                cantidad: cant,
+                lineas: Vec::from([Linea {
+                    id_prod,
+                    cantidad: cant,
+                }]),
                 estado: Estado::Pendiente,
+                monto: total,
             };
 
             // Inserta la nueva orden en el mapping `ordenes`.
             self.ordenes.insert(oid, &orden);
+            // Emite el evento de creación de la orden.
+            self.env().emit_event(OrdenCreada {
+                oid,
+                comprador,
+                vendedor: orden.vendedor,
+                lineas: orden.lineas,
+                monto: total,
+            });
+
+            // Devuelve el excedente al comprador. El total queda retenido en el contrato.
+            let excedente = pago.checked_sub(total).ok_or(Error::PagoInsuf)?;
+            if excedente > 0 {
+                self.transferir(comprador, excedente)?;
+            }
 
             // Devuelve el ID de la orden recién creada.
             Ok(oid)
         }
 
+        /// Lógica interna para agregar un producto al carrito.
+        fn _agregar_al_carrito(
+            &mut self,
+            comprador: AccountId,
+            id_prod: u32,
+            cant: u32,
+        ) -> Result<(), Error> {
+            // Obtiene el rol del comprador. Devuelve `Error::SinRegistro` si no está registrado.
+            let rol_comprador = self.rol_de(comprador)?;
+            // Asegura que el usuario tenga permisos de comprador. Si no, devuelve `Error::SinPermiso`.
+            self.ensure(rol_comprador.es_comprador(), Error::SinPermiso)?;
+            // Asegura que la cantidad a agregar sea mayor que cero.
+            self.ensure(cant > 0, Error::ParamInvalido)?;
+            // Obtiene el producto. Si no existe, devuelve `Error::ProdInexistente`.
+            let producto = self.productos.get(id_prod).ok_or(Error::ProdInexistente)?;
+
+            let mut carrito = self.carritos.get(comprador).unwrap_or_default();
+            match carrito.iter_mut().find(|linea| linea.id_prod == id_prod) {
+                // Si el producto ya está en el carrito, suma las unidades a su línea.
+                Some(linea) => {
+                    linea.cantidad = linea.cantidad.checked_add(cant).ok_or(Error::StockInsuf)?;
+                    self.ensure(producto.stock >= linea.cantidad, Error::StockInsuf)?;
+                }
+                // Si es nuevo, agrega una línea siempre que no se supere el máximo.
+                None => {
+                    self.ensure(producto.stock >= cant, Error::StockInsuf)?;
+                    self.ensure(
+                        carrito.len() < MAX_LINEAS_CARRITO as usize,
+                        Error::CarritoLleno,
+                    )?;
+                    carrito.push(Linea {
+                        id_prod,
+                        cantidad: cant,
+                    });
+                }
+            }
+
+            self.carritos.insert(comprador, &carrito);
+            Ok(())
+        }
+
+        /// Lógica interna para quitar un producto del carrito.
+        fn _quitar_del_carrito(&mut self, comprador: AccountId, id_prod: u32) -> Result<(), Error> {
+            let mut carrito = self.carritos.get(comprador).unwrap_or_default();
+            // Busca la línea del producto. Si no está, devuelve `Error::NoEnCarrito`.
+            let pos = carrito
+                .iter()
+                .position(|linea| linea.id_prod == id_prod)
+                .ok_or(Error::NoEnCarrito)?;
+            carrito.remove(pos);
+
+            // Si el carrito queda vacío se libera su almacenamiento.
+            if carrito.is_empty() {
+                self.carritos.remove(comprador);
+            } else {
+                self.carritos.insert(comprador, &carrito);
+            }
+            Ok(())
+        }
+
+        /// Lógica interna para comprar el carrito completo.
+        fn _comprar_carrito(
+            &mut self,
+            comprador: AccountId,
+            pago: Balance,
+        ) -> Result<Vec<u32>, Error> {
+            // Obtiene el rol del comprador. Devuelve `Error::SinRegistro` si no está registrado.
+            let rol_comprador = self.rol_de(comprador)?;
+            // Asegura que el usuario tenga permisos de comprador. Si no, devuelve `Error::SinPermiso`.
+            self.ensure(rol_comprador.es_comprador(), Error::SinPermiso)?;
+            let carrito = self.carritos.get(comprador).unwrap_or_default();
+            self.ensure(!carrito.is_empty(), Error::CarritoVacio)?;
+
+            // Primera pasada: valida todas las líneas sin tocar el almacenamiento.
+            // Acumula los productos con su stock ya descontado y las órdenes agrupadas por vendedor.
+            let mut productos: Vec<(u32, Producto)> = Vec::new();
+            let mut pedidos: Vec<(AccountId, Vec<Linea>, Balance)> = Vec::new();
+            let mut total: Balance = 0;
+            for linea in carrito {
+                // Obtiene el producto. Si ya no existe, devuelve `Error::ProdInexistente`.
+                let mut producto = self
+                    .productos
+                    .get(linea.id_prod)
+                    .ok_or(Error::ProdInexistente)?;
+                // Descuenta el stock. Si no alcanza, devuelve `Error::StockInsuf`.
+                producto.stock = producto
+                    .stock
+                    .checked_sub(linea.cantidad)
+                    .ok_or(Error::StockInsuf)?;
+                // Calcula el subtotal de la línea. Un desbordamiento implica un pedido imposible de pagar.
+                let subtotal = producto
+                    .precio
+                    .checked_mul(Balance::from(linea.cantidad))
+                    .ok_or(Error::PagoInsuf)?;
+                total = total.checked_add(subtotal).ok_or(Error::PagoInsuf)?;
+
+                // Agrega la línea a la orden de su vendedor, o abre una nueva.
+                match pedidos
+                    .iter_mut()
+                    .find(|(vendedor, _, _)| *vendedor == producto.vendedor)
+                {
+                    Some((_, lineas, monto)) => {
+                        lineas.push(linea);
+                        *monto = monto.checked_add(subtotal).ok_or(Error::PagoInsuf)?;
+                    }
+                    None => pedidos.push((producto.vendedor, Vec::from([linea]), subtotal)),
+                }
+                productos.push((linea.id_prod, producto));
+            }
+
+            // Verifica que el comprador haya transferido lo suficiente. Si no, devuelve `Error::PagoInsuf`.
+            self.ensure(pago >= total, Error::PagoInsuf)?;
+            // Reserva los IDs de todas las órdenes. Si no alcanzan, devuelve `Error::IdOverflow`.
+            let primer_oid = self.next_order_id;
+            let cant_ordenes = u32::try_from(pedidos.len()).map_err(|_| Error::IdOverflow)?;
+            let next_order_id = primer_oid
+                .checked_add(cant_ordenes)
+                .ok_or(Error::IdOverflow)?;
+
+            // Segunda pasada: con todo validado, aplica los cambios.
+            for (id_prod, producto) in productos {
+                self.productos.insert(id_prod, &producto);
+                self.env().emit_event(StockActualizado {
+                    id_prod,
+                    stock: producto.stock,
+                });
+            }
+
+            self.next_order_id = next_order_id;
+            let mut oids = Vec::new();
+            for (oid, (vendedor, lineas, monto)) in (primer_oid..next_order_id).zip(pedidos) {
+                let orden = Orden {
+                    comprador,
+                    vendedor,
+                    lineas,
+                    estado: Estado::Pendiente,
+                    monto,
+                };
+                self.ordenes.insert(oid, &orden);
+                self.env().emit_event(OrdenCreada {
+                    oid,
+                    comprador,
+                    vendedor,
+                    lineas: orden.lineas,
+                    monto,
+                });
+                oids.push(oid);
+            }
+
+            // El carrito ya se compró.
+            self.carritos.remove(comprador);
+
+            // Devuelve el excedente al comprador. El total queda retenido en el contrato.
+            let excedente = pago.checked_sub(total).ok_or(Error::PagoInsuf)?;
+            if excedente > 0 {
+                self.transferir(comprador, excedente)?;
+            }
+
+            Ok(oids)
+        }
+
         /// Lógica interna para marcar una orden como enviada.
         fn _marcar_enviado(&mut self, caller: AccountId, oid: u32) -> Result<(), Error> {
             // Obtiene la orden. Si no existe, devuelve `Error::OrdenInexistente`.
@@ -417,6 +823,11 @@ mod marketplace {
             orden.estado = Estado::Enviado;
             // Actualiza la orden en el almacenamiento.
             self.ordenes.insert(oid, &orden);
+            // Emite el evento de envío.
+            self.env().emit_event(OrdenEnviada {
+                oid,
+                vendedor: caller,
+            });
             Ok(())
         }
 
@@ -434,7 +845,15 @@ mod marketplace {
             orden.estado = Estado::Recibido;
             // Actualiza la orden en el almacenamiento.
             self.ordenes.insert(oid, &orden);
-            Ok(())
+            // Emite el evento de recepción.
+            self.env().emit_event(OrdenRecibida {
+                oid,
+                comprador: caller,
+                monto: orden.monto,
+            });
+
+            // Libera al vendedor el monto retenido en la compra.
+            self.transferir(orden.vendedor, orden.monto)
         }
 
         /// Helper para validar condiciones.
@@ -449,6 +868,14 @@ mod marketplace {
             }
         }
 
+        /// Helper para transferir fondos del contrato a una cuenta.
+        // Si la transferencia falla el mensaje devuelve error y ink! revierte los cambios de estado.
+        fn transferir(&mut self, destino: AccountId, monto: Balance) -> Result<(), Error> {
+            self.env()
+                .transfer(destino, monto)
+                .map_err(|_| Error::TransferFallida)
+        }
+
         /// Helper que retorna rol.
         fn rol_de(&self, quien: AccountId) -> Result<Rol, Error> {
             // Intenta obtener el rol del usuario. Si no existe, devuelve `Error::SinRegistro`.
@@ -470,6 +897,55 @@ mod marketplace {
             test::default_accounts::<DefaultEnvironment>()
         }
 
+        fn balance_de(cuenta: AccountId) -> Balance {
+            test::get_account_balance::<DefaultEnvironment>(cuenta).unwrap_or_default()
+        }
+
+        /// Usa una cuenta propia para el contrato, así sus fondos no se mezclan con los de los usuarios.
+        fn set_contrato() -> AccountId {
+            let contrato = AccountId::from([0xFF; 32]);
+            test::set_callee::<DefaultEnvironment>(contrato);
+            test::set_account_balance::<DefaultEnvironment>(contrato, 0);
+            contrato
+        }
+
+        /// Llama a `comprar` como `comprador` transfiriendo `pago` al contrato.
+        fn comprar_pagando(
+            mp: &mut Marketplace,
+            comprador: AccountId,
+            id_prod: u32,
+            cant: u32,
+            pago: Balance,
+        ) -> Result<u32, Error> {
+            set_next_caller(comprador);
+            test::transfer_in::<DefaultEnvironment>(pago);
+            let res = mp.comprar(id_prod, cant);
+            test::set_value_transferred::<DefaultEnvironment>(0);
+            res
+        }
+
+        /// Llama a `comprar_carrito` como `comprador` transfiriendo `pago` al contrato.
+        fn comprar_carrito_pagando(
+            mp: &mut Marketplace,
+            comprador: AccountId,
+            pago: Balance,
+        ) -> Result<Vec<u32>, Error> {
+            set_next_caller(comprador);
+            test::transfer_in::<DefaultEnvironment>(pago);
+            let res = mp.comprar_carrito();
+            test::set_value_transferred::<DefaultEnvironment>(0);
+            res
+        }
+
+        fn linea(id_prod: u32, cantidad: u32) -> Linea {
+            Linea { id_prod, cantidad }
+        }
+
+        /// Decodifica los datos del evento registrado en la posición `i`.
+        fn evento<E: scale::Decode>(eventos: &[test::EmittedEvent], i: usize) -> E {
+            E::decode(&mut &eventos[i].data[..]).expect("no se pudo decodificar el evento")
+        }
+
         /// Test para el flujo completo y casos de éxito.
         /// 1. Registra un comprador y un vendedor.
         /// 2. El vendedor publica un producto.
@@ -501,8 +977,7 @@ mod marketplace {
             assert_eq!(prod.stock, 10);
 
             // 3. Compra
-            set_next_caller(comprador_acc);
-            let res_compra = mp.comprar(pid, 5);
+            let res_compra = comprar_pagando(&mut mp, comprador_acc, pid, 5, 500);
             assert_eq!(res_compra, Ok(1));
             let oid = res_compra.unwrap();
             let prod_actualizado = mp.obtener_producto(pid).unwrap();
@@ -512,6 +987,7 @@ mod marketplace {
             assert_eq!(orden.comprador, comprador_acc);
             assert_eq!(orden.vendedor, vendedor_acc);
             assert_eq!(orden.estado, Estado::Pendiente);
+            assert_eq!(orden.monto, 500);
 
             // 4. Marcar como enviado
             set_next_caller(vendedor_acc);
@@ -595,7 +1071,7 @@ mod marketplace {
             let pid = mp.publicar("Test".to_string(), 10, 5).unwrap();
             set_next_caller(comprador_acc);
             mp.registrar(Rol::Comprador).unwrap();
-            let oid = mp.comprar(pid, 2).unwrap();
+            let oid = comprar_pagando(&mut mp, comprador_acc, pid, 2, 20).unwrap();
 
             // Errores al marcar como enviado
             set_next_caller(comprador_acc); // Comprador no puede marcar enviado
@@ -653,7 +1129,403 @@ mod marketplace {
 
             // Simula overflow de ID de orden
             mp.next_order_id = u32::MAX;
-            assert_eq!(mp.comprar(pid, 1), Err(Error::IdOverflow));
+            assert_eq!(
+                comprar_pagando(&mut mp, accounts.bob, pid, 1, 1),
+                Err(Error::IdOverflow)
+            );
+        }
+
+        /// Test para el escrow de la compra.
+        /// 1. El pago queda retenido en el contrato al comprar.
+        /// 2. El vendedor no cobra al enviar.
+        /// 3. El monto retenido se libera al vendedor cuando el comprador marca la orden como recibida.
+        #[ink::test]
+        fn test_escrow_libera_fondos_al_recibir() {
+            let accounts = get_accounts();
+            let (vendedor_acc, comprador_acc) = (accounts.alice, accounts.bob);
+            let contrato = set_contrato();
+            let mut mp = Marketplace::new();
+
+            set_next_caller(vendedor_acc);
+            mp.registrar(Rol::Vendedor).unwrap();
+            let pid = mp.publicar("Test".to_string(), 100, 10).unwrap();
+            set_next_caller(comprador_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+
+            let saldo_vendedor = balance_de(vendedor_acc);
+            let saldo_comprador = balance_de(comprador_acc);
+
+            // 1. Compra: los fondos quedan en el contrato
+            let oid = comprar_pagando(&mut mp, comprador_acc, pid, 3, 300).unwrap();
+            assert_eq!(mp.obtener_orden(oid).unwrap().monto, 300);
+            assert_eq!(balance_de(contrato), 300);
+            assert_eq!(balance_de(comprador_acc), saldo_comprador - 300);
+
+            // 2. Envío: el vendedor todavía no cobra
+            set_next_caller(vendedor_acc);
+            mp.marcar_enviado(oid).unwrap();
+            assert_eq!(balance_de(vendedor_acc), saldo_vendedor);
+            assert_eq!(balance_de(contrato), 300);
+
+            // 3. Recepción: se libera el pago al vendedor
+            set_next_caller(comprador_acc);
+            mp.marcar_recibido(oid).unwrap();
+            assert_eq!(balance_de(vendedor_acc), saldo_vendedor + 300);
+            assert_eq!(balance_de(contrato), 0);
+        }
+
+        /// Test para los pagos al comprar.
+        /// - Un pago menor al total es rechazado y no descuenta stock.
+        /// - Un pago mayor al total devuelve el excedente al comprador.
+        #[ink::test]
+        fn test_escrow_pago_insuficiente_y_excedente() {
+            let accounts = get_accounts();
+            let (vendedor_acc, comprador_acc) = (accounts.alice, accounts.bob);
+            let contrato = set_contrato();
+            let mut mp = Marketplace::new();
+
+            set_next_caller(vendedor_acc);
+            mp.registrar(Rol::Vendedor).unwrap();
+            let pid = mp.publicar("Test".to_string(), 100, 10).unwrap();
+            set_next_caller(comprador_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+
+            // Pago insuficiente
+            assert_eq!(
+                comprar_pagando(&mut mp, comprador_acc, pid, 3, 299),
+                Err(Error::PagoInsuf)
+            );
+            assert_eq!(mp.obtener_producto(pid).unwrap().stock, 10);
+
+            // Sin pago
+            assert_eq!(
+                comprar_pagando(&mut mp, comprador_acc, pid, 1, 0),
+                Err(Error::PagoInsuf)
+            );
+
+            // Pago con excedente: se retiene el total y se devuelve el resto
+            test::set_account_balance::<DefaultEnvironment>(contrato, 0);
+            let saldo_comprador = balance_de(comprador_acc);
+            let oid = comprar_pagando(&mut mp, comprador_acc, pid, 2, 250).unwrap();
+            assert_eq!(mp.obtener_orden(oid).unwrap().monto, 200);
+            assert_eq!(balance_de(contrato), 200);
+            assert_eq!(balance_de(comprador_acc), saldo_comprador - 200);
+        }
+
+        /// Test para los eventos emitidos en el flujo completo.
+        /// Cada cambio de estado (registro, publicación, compra, envío y recepción)
+        /// emite su evento, en el orden en que ocurre.
+        #[ink::test]
+        fn test_eventos_flujo_completo() {
+            let accounts = get_accounts();
+            let (vendedor_acc, comprador_acc) = (accounts.alice, accounts.bob);
+            set_contrato();
+            let mut mp = Marketplace::new();
+
+            set_next_caller(vendedor_acc);
+            mp.registrar(Rol::Vendedor).unwrap();
+            set_next_caller(comprador_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+            set_next_caller(vendedor_acc);
+            let pid = mp.publicar("Test".to_string(), 100, 10).unwrap();
+            let oid = comprar_pagando(&mut mp, comprador_acc, pid, 3, 300).unwrap();
+            set_next_caller(vendedor_acc);
+            mp.marcar_enviado(oid).unwrap();
+            set_next_caller(comprador_acc);
+            mp.marcar_recibido(oid).unwrap();
+
+            let eventos: Vec<test::EmittedEvent> = test::recorded_events().collect();
+            assert_eq!(eventos.len(), 7);
+
+            // Registros
+            let registro: UsuarioRegistrado = evento(&eventos, 0);
+            assert_eq!(registro.usuario, vendedor_acc);
+            assert_eq!(registro.rol, Rol::Vendedor);
+            let registro: UsuarioRegistrado = evento(&eventos, 1);
+            assert_eq!(registro.usuario, comprador_acc);
+            assert_eq!(registro.rol, Rol::Comprador);
+
+            // Publicación
+            let publicado: ProductoPublicado = evento(&eventos, 2);
+            assert_eq!(publicado.id_prod, pid);
+            assert_eq!(publicado.vendedor, vendedor_acc);
+            assert_eq!(publicado.nombre, "Test");
+            assert_eq!(publicado.precio, 100);
+            assert_eq!(publicado.stock, 10);
+
+            // Compra: primero se actualiza el stock y después se crea la orden
+            let stock: StockActualizado = evento(&eventos, 3);
+            assert_eq!(stock.id_prod, pid);
+            assert_eq!(stock.stock, 7);
+            let creada: OrdenCreada = evento(&eventos, 4);
+            assert_eq!(creada.oid, oid);
+            assert_eq!(creada.comprador, comprador_acc);
+            assert_eq!(creada.vendedor, vendedor_acc);
+            assert_eq!(
+                creada.lineas,
+                vec![Linea {
+                    id_prod: pid,
+                    cantidad: 3
+                }]
+            );
+            assert_eq!(creada.monto, 300);
+
+            // Envío y recepción
+            let enviada: OrdenEnviada = evento(&eventos, 5);
+            assert_eq!(enviada.oid, oid);
+            assert_eq!(enviada.vendedor, vendedor_acc);
+            let recibida: OrdenRecibida = evento(&eventos, 6);
+            assert_eq!(recibida.oid, oid);
+            assert_eq!(recibida.comprador, comprador_acc);
+            assert_eq!(recibida.monto, 300);
+        }
+
+        /// Test para los topics de `OrdenCreada`.
+        /// El comprador y el vendedor van como topics para que se puedan filtrar las órdenes de cada cuenta.
+        #[ink::test]
+        fn test_eventos_topics_orden_creada() {
+            let accounts = get_accounts();
+            let (vendedor_acc, comprador_acc) = (accounts.alice, accounts.bob);
+            set_contrato();
+            let mut mp = Marketplace::new();
+
+            set_next_caller(vendedor_acc);
+            mp.registrar(Rol::Vendedor).unwrap();
+            let pid = mp.publicar("Test".to_string(), 100, 10).unwrap();
+            set_next_caller(comprador_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+            comprar_pagando(&mut mp, comprador_acc, pid, 1, 100).unwrap();
+
+            let orden_creada = test::recorded_events().last().unwrap();
+            // Topics: firma del evento, oid, comprador y vendedor.
+            assert_eq!(orden_creada.topics.len(), 4);
+            assert_eq!(
+                &orden_creada.topics[2][..],
+                AsRef::<[u8]>::as_ref(&comprador_acc)
+            );
+            assert_eq!(
+                &orden_creada.topics[3][..],
+                AsRef::<[u8]>::as_ref(&vendedor_acc)
+            );
+        }
+
+        /// Test para verificar que las operaciones fallidas no emiten eventos.
+        #[ink::test]
+        fn test_eventos_no_se_emiten_en_errores() {
+            let accounts = get_accounts();
+            let mut mp = Marketplace::new();
+
+            set_next_caller(accounts.alice);
+            mp.registrar(Rol::Comprador).unwrap();
+            assert_eq!(mp.registrar(Rol::Vendedor), Err(Error::YaRegistrado));
+            assert_eq!(
+                mp.publicar("Fail".to_string(), 1, 1),
+                Err(Error::SinPermiso)
+            );
+            assert_eq!(mp.comprar(1, 1), Err(Error::ProdInexistente));
+            assert_eq!(mp.marcar_enviado(1), Err(Error::OrdenInexistente));
+
+            // Solo queda el evento del registro exitoso.
+            assert_eq!(test::recorded_events().count(), 1);
+        }
+
+        /// Test para las operaciones del carrito.
+        /// - Agregar un producto que ya está suma las unidades a su línea.
+        /// - Quitar saca la línea completa y vaciar deja el carrito sin líneas.
+        /// - Se validan rol, cantidad, existencia del producto y stock.
+        #[ink::test]
+        fn test_carrito_agregar_quitar_vaciar() {
+            let accounts = get_accounts();
+            let (vendedor_acc, comprador_acc) = (accounts.alice, accounts.bob);
+            let mut mp = Marketplace::new();
+
+            set_next_caller(vendedor_acc);
+            mp.registrar(Rol::Vendedor).unwrap();
+            let pid1 = mp.publicar("Uno".to_string(), 10, 5).unwrap();
+            let pid2 = mp.publicar("Dos".to_string(), 20, 5).unwrap();
+            assert_eq!(mp.agregar_al_carrito(pid1, 1), Err(Error::SinPermiso));
+
+            set_next_caller(comprador_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+            assert_eq!(mp.ver_carrito(), vec![]);
+
+            // Agregar
+            assert_eq!(mp.agregar_al_carrito(pid1, 2), Ok(()));
+            assert_eq!(mp.agregar_al_carrito(pid2, 1), Ok(()));
+            assert_eq!(mp.agregar_al_carrito(pid1, 3), Ok(()));
+            assert_eq!(mp.ver_carrito(), vec![linea(pid1, 5), linea(pid2, 1)]);
+
+            // Errores al agregar: el carrito no cambia
+            assert_eq!(mp.agregar_al_carrito(pid2, 0), Err(Error::ParamInvalido));
+            assert_eq!(mp.agregar_al_carrito(99, 1), Err(Error::ProdInexistente));
+            assert_eq!(mp.agregar_al_carrito(pid1, 1), Err(Error::StockInsuf));
+            assert_eq!(
+                mp.agregar_al_carrito(pid2, u32::MAX),
+                Err(Error::StockInsuf)
+            );
+            assert_eq!(mp.ver_carrito(), vec![linea(pid1, 5), linea(pid2, 1)]);
+
+            // Quitar
+            assert_eq!(mp.quitar_del_carrito(pid1), Ok(()));
+            assert_eq!(mp.ver_carrito(), vec![linea(pid2, 1)]);
+            assert_eq!(mp.quitar_del_carrito(pid1), Err(Error::NoEnCarrito));
+
+            // Vaciar
+            mp.agregar_al_carrito(pid1, 1).unwrap();
+            mp.vaciar_carrito();
+            assert_eq!(mp.ver_carrito(), vec![]);
+
+            // Cada comprador tiene su propio carrito
+            mp.agregar_al_carrito(pid1, 1).unwrap();
+            set_next_caller(vendedor_acc);
+            assert_eq!(mp.ver_carrito(), vec![]);
+        }
+
+        /// Test para el máximo de líneas por carrito.
+        /// Superado el máximo no se pueden agregar productos nuevos, pero sí unidades de los que ya están.
+        #[ink::test]
+        fn test_carrito_maximo_de_lineas() {
+            let accounts = get_accounts();
+            let (vendedor_acc, comprador_acc) = (accounts.alice, accounts.bob);
+            let mut mp = Marketplace::new();
+
+            set_next_caller(vendedor_acc);
+            mp.registrar(Rol::Vendedor).unwrap();
+            let pids: Vec<u32> = (0..=MAX_LINEAS_CARRITO)
+                .map(|_| mp.publicar("Test".to_string(), 10, 5).unwrap())
+                .collect();
+
+            set_next_caller(comprador_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+            for pid in &pids[..MAX_LINEAS_CARRITO as usize] {
+                mp.agregar_al_carrito(*pid, 1).unwrap();
+            }
+
+            let ultimo = pids[MAX_LINEAS_CARRITO as usize];
+            assert_eq!(mp.agregar_al_carrito(ultimo, 1), Err(Error::CarritoLleno));
+            assert_eq!(mp.agregar_al_carrito(pids[0], 1), Ok(()));
+            assert_eq!(mp.ver_carrito().len(), MAX_LINEAS_CARRITO as usize);
+
+            // Al quitar una línea vuelve a haber lugar.
+            mp.quitar_del_carrito(pids[1]).unwrap();
+            assert_eq!(mp.agregar_al_carrito(ultimo, 1), Ok(()));
+        }
+
+        /// Test para la compra del carrito.
+        /// 1. Se crea una orden por vendedor con sus líneas y su parte del pago.
+        /// 2. Se descuenta el stock, se vacía el carrito y se devuelve el excedente.
+        /// 3. Cada vendedor cobra su orden al recibirse.
+        #[ink::test]
+        fn test_comprar_carrito_una_orden_por_vendedor() {
+            let accounts = get_accounts();
+            let (vendedor1, vendedor2, comprador_acc) =
+                (accounts.alice, accounts.charlie, accounts.bob);
+            let contrato = set_contrato();
+            let mut mp = Marketplace::new();
+
+            set_next_caller(vendedor1);
+            mp.registrar(Rol::Vendedor).unwrap();
+            let mate = mp.publicar("Mate".to_string(), 100, 10).unwrap();
+            let termo = mp.publicar("Termo".to_string(), 300, 10).unwrap();
+            set_next_caller(vendedor2);
+            mp.registrar(Rol::Vendedor).unwrap();
+            let yerba = mp.publicar("Yerba".to_string(), 50, 10).unwrap();
+
+            set_next_caller(comprador_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+            mp.agregar_al_carrito(mate, 2).unwrap();
+            mp.agregar_al_carrito(yerba, 4).unwrap();
+            mp.agregar_al_carrito(termo, 1).unwrap();
+
+            // 1. Compra con excedente: total = 2*100 + 4*50 + 1*300 = 700
+            let saldo_comprador = balance_de(comprador_acc);
+            let oids = comprar_carrito_pagando(&mut mp, comprador_acc, 750).unwrap();
+            assert_eq!(oids, vec![1, 2]);
+
+            let orden1 = mp.obtener_orden(1).unwrap();
+            assert_eq!(orden1.vendedor, vendedor1);
+            assert_eq!(orden1.lineas, vec![linea(mate, 2), linea(termo, 1)]);
+            assert_eq!(orden1.monto, 500);
+            assert_eq!(orden1.estado, Estado::Pendiente);
+            let orden2 = mp.obtener_orden(2).unwrap();
+            assert_eq!(orden2.vendedor, vendedor2);
+            assert_eq!(orden2.lineas, vec![linea(yerba, 4)]);
+            assert_eq!(orden2.monto, 200);
+
+            // 2. Stock, carrito y fondos
+            assert_eq!(mp.obtener_producto(mate).unwrap().stock, 8);
+            assert_eq!(mp.obtener_producto(termo).unwrap().stock, 9);
+            assert_eq!(mp.obtener_producto(yerba).unwrap().stock, 6);
+            assert_eq!(mp.ver_carrito(), vec![]);
+            assert_eq!(balance_de(contrato), 700);
+            assert_eq!(balance_de(comprador_acc), saldo_comprador - 700);
+
+            // 3. Cada vendedor cobra solo su parte
+            let saldo_vendedor2 = balance_de(vendedor2);
+            set_next_caller(vendedor2);
+            mp.marcar_enviado(2).unwrap();
+            set_next_caller(comprador_acc);
+            mp.marcar_recibido(2).unwrap();
+            assert_eq!(balance_de(vendedor2), saldo_vendedor2 + 200);
+            assert_eq!(balance_de(contrato), 500);
+        }
+
+        /// Test para la atomicidad de la compra del carrito.
+        /// Si alguna línea o el pago no son válidos no se descuenta stock, no se crean órdenes,
+        /// no se emiten eventos y el carrito queda como estaba.
+        #[ink::test]
+        fn test_comprar_carrito_falla_sin_cambios() {
+            let accounts = get_accounts();
+            let (vendedor_acc, comprador_acc, otro_acc) =
+                (accounts.alice, accounts.bob, accounts.charlie);
+            set_contrato();
+            let mut mp = Marketplace::new();
+
+            set_next_caller(vendedor_acc);
+            mp.registrar(Rol::Vendedor).unwrap();
+            let pid1 = mp.publicar("Uno".to_string(), 10, 5).unwrap();
+            let pid2 = mp.publicar("Dos".to_string(), 20, 5).unwrap();
+            set_next_caller(otro_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+            set_next_caller(comprador_acc);
+            mp.registrar(Rol::Comprador).unwrap();
+            assert_eq!(
+                comprar_carrito_pagando(&mut mp, comprador_acc, 100),
+                Err(Error::CarritoVacio)
+            );
+
+            mp.agregar_al_carrito(pid1, 2).unwrap();
+            mp.agregar_al_carrito(pid2, 4).unwrap();
+
+            // Pago insuficiente: el total es 2*10 + 4*20 = 100
+            assert_eq!(
+                comprar_carrito_pagando(&mut mp, comprador_acc, 99),
+                Err(Error::PagoInsuf)
+            );
+
+            // Otro comprador se lleva parte del stock de la segunda línea
+            comprar_pagando(&mut mp, otro_acc, pid2, 2, 40).unwrap();
+            let eventos_antes = test::recorded_events().count();
+
+            assert_eq!(
+                comprar_carrito_pagando(&mut mp, comprador_acc, 100),
+                Err(Error::StockInsuf)
+            );
+            assert_eq!(mp.obtener_producto(pid1).unwrap().stock, 5);
+            assert_eq!(mp.obtener_producto(pid2).unwrap().stock, 3);
+            assert_eq!(mp.obtener_orden(2), None);
+            assert_eq!(mp.ver_carrito(), vec![linea(pid1, 2), linea(pid2, 4)]);
+            assert_eq!(test::recorded_events().count(), eventos_antes);
+
+            // Sin IDs para crear la orden
+            mp.quitar_del_carrito(pid2).unwrap();
+            mp.next_order_id = u32::MAX;
+            assert_eq!(
+                comprar_carrito_pagando(&mut mp, comprador_acc, 20),
+                Err(Error::IdOverflow)
+            );
+            assert_eq!(mp.obtener_producto(pid1).unwrap().stock, 5);
+            assert_eq!(mp.ver_carrito(), vec![linea(pid1, 2)]);
         }
     }
 }